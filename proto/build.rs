@@ -2,6 +2,19 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     tonic_build::configure()
         .build_client(true)
         .out_dir("src/service")
-        .compile(&["defs/todo.proto", "defs/auth.proto"], &["defs"])?;
+        // Todos are streamed far more often than the page info closing them.
+        .type_attribute(
+            "todo.GetTodosResponse.message",
+            "#[allow(clippy::large_enum_variant)]",
+        )
+        .compile(
+            &[
+                "defs/todo.proto",
+                "defs/auth.proto",
+                "defs/google/rpc/status.proto",
+                "defs/google/rpc/error_details.proto",
+            ],
+            &["defs"],
+        )?;
     Ok(())
 }
@@ -1,9 +1,16 @@
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SignUpRequest {
+    /// 3 to 32 letters, digits, '.', '_' or '-', starting with a letter or a
+    /// digit.
     #[prost(string, tag = "1")]
     pub username: ::prost::alloc::string::String,
+    /// Optional 4 digit PIN, required along with the password on sign in once
+    /// set. 0 when the account has no PIN.
     #[prost(int32, tag = "2")]
     pub pin: i32,
+    /// Checked against the password policy of the server.
+    #[prost(string, tag = "3")]
+    pub password: ::prost::alloc::string::String,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SignUpResponse {
@@ -16,13 +23,226 @@ pub struct SignUpResponse {
 pub struct SignInRequest {
     #[prost(string, tag = "1")]
     pub username: ::prost::alloc::string::String,
+    /// Required when the account has a PIN. Accounts created before passwords
+    /// sign in with their PIN alone.
     #[prost(int32, tag = "2")]
     pub pin: i32,
+    #[prost(string, tag = "3")]
+    pub password: ::prost::alloc::string::String,
 }
+/// Accounts with two-factor authentication get a challenge instead of tokens,
+/// answered through VerifySecondFactor.
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SignInResponse {
+    /// Short-lived JWT sent as the `authorization` metadata of Todo calls.
     #[prost(string, tag = "1")]
     pub token: ::prost::alloc::string::String,
+    /// Long-lived token exchanged through RefreshToken for a new access token.
+    #[prost(string, tag = "2")]
+    pub refresh_token: ::prost::alloc::string::String,
+    /// Seconds until `token` expires, or until `challenge_token` does.
+    #[prost(int64, tag = "3")]
+    pub expires_in: i64,
+    /// Set, and the tokens left empty, when a second factor is required.
+    #[prost(string, tag = "4")]
+    pub challenge_token: ::prost::alloc::string::String,
+    #[prost(bool, tag = "5")]
+    pub second_factor_required: bool,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct VerifySecondFactorRequest {
+    #[prost(string, tag = "1")]
+    pub challenge_token: ::prost::alloc::string::String,
+    /// A 6 digit code of the authenticator app, or one of the recovery codes.
+    #[prost(string, tag = "2")]
+    pub code: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct VerifySecondFactorResponse {
+    #[prost(string, tag = "1")]
+    pub token: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub refresh_token: ::prost::alloc::string::String,
+    #[prost(int64, tag = "3")]
+    pub expires_in: i64,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RefreshTokenRequest {
+    #[prost(string, tag = "1")]
+    pub refresh_token: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RefreshTokenResponse {
+    #[prost(string, tag = "1")]
+    pub token: ::prost::alloc::string::String,
+    /// The presented refresh token is revoked and replaced by this one.
+    #[prost(string, tag = "2")]
+    pub refresh_token: ::prost::alloc::string::String,
+    #[prost(int64, tag = "3")]
+    pub expires_in: i64,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SignOutRequest {
+    /// Optional refresh token to revoke along with the access token of the call.
+    #[prost(string, tag = "1")]
+    pub refresh_token: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SignOutResponse {}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SignOutEverywhereRequest {}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SignOutEverywhereResponse {
+    /// Number of access tokens that were still live and have been revoked.
+    #[prost(uint32, tag = "1")]
+    pub revoked_tokens: u32,
+}
+/// Credentials are checked as on sign in, from the password and the current
+/// PIN.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ChangePinRequest {
+    #[prost(int32, tag = "1")]
+    pub current_pin: i32,
+    /// 0 removes the PIN, which only accounts with a password can do.
+    #[prost(int32, tag = "2")]
+    pub new_pin: i32,
+    #[prost(string, tag = "3")]
+    pub password: ::prost::alloc::string::String,
+}
+/// Every token of the account is revoked by ChangePin, ChangePassword and
+/// ChangeUsername, and replaced by the pair returned.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ChangePinResponse {
+    #[prost(string, tag = "1")]
+    pub token: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub refresh_token: ::prost::alloc::string::String,
+    #[prost(int64, tag = "3")]
+    pub expires_in: i64,
+}
+/// Accounts created before passwords set their first one with their PIN alone.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ChangePasswordRequest {
+    #[prost(string, tag = "1")]
+    pub current_password: ::prost::alloc::string::String,
+    #[prost(int32, tag = "2")]
+    pub pin: i32,
+    #[prost(string, tag = "3")]
+    pub new_password: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ChangePasswordResponse {
+    #[prost(string, tag = "1")]
+    pub token: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub refresh_token: ::prost::alloc::string::String,
+    #[prost(int64, tag = "3")]
+    pub expires_in: i64,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ChangeUsernameRequest {
+    #[prost(string, tag = "1")]
+    pub username: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ChangeUsernameResponse {
+    #[prost(string, tag = "1")]
+    pub token: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub refresh_token: ::prost::alloc::string::String,
+    #[prost(int64, tag = "3")]
+    pub expires_in: i64,
+}
+/// Current credentials, confirming the deletion.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DeleteAccountRequest {
+    #[prost(int32, tag = "1")]
+    pub pin: i32,
+    #[prost(string, tag = "2")]
+    pub password: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DeleteAccountResponse {}
+/// Current credentials, confirming the enrollment.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct EnrollTotpRequest {
+    #[prost(string, tag = "1")]
+    pub password: ::prost::alloc::string::String,
+    #[prost(int32, tag = "2")]
+    pub pin: i32,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct EnrollTotpResponse {
+    /// Base32 secret, for authenticator apps that cannot scan `otpauth_uri`.
+    #[prost(string, tag = "1")]
+    pub secret: ::prost::alloc::string::String,
+    /// `otpauth://totp/` URI, usually shown as a QR code.
+    #[prost(string, tag = "2")]
+    pub otpauth_uri: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ConfirmTotpRequest {
+    /// A code of the authenticator app enrolled with the secret.
+    #[prost(string, tag = "1")]
+    pub code: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ConfirmTotpResponse {
+    /// One-time codes standing in for the authenticator app. They are only
+    /// shown once.
+    #[prost(string, repeated, tag = "1")]
+    pub recovery_codes: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DisableTotpRequest {
+    #[prost(string, tag = "1")]
+    pub password: ::prost::alloc::string::String,
+    #[prost(int32, tag = "2")]
+    pub pin: i32,
+    /// A code of the authenticator app, or one of the recovery codes.
+    #[prost(string, tag = "3")]
+    pub code: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DisableTotpResponse {}
+/// A username or a peer address that cannot sign in for now.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Lockout {
+    /// Set for the lockout of a username.
+    #[prost(string, tag = "1")]
+    pub username: ::prost::alloc::string::String,
+    /// Set for the lockout of a peer address.
+    #[prost(string, tag = "2")]
+    pub address: ::prost::alloc::string::String,
+    #[prost(uint32, tag = "3")]
+    pub failures: u32,
+    /// Whether the maximum of failures was reached, rather than a backoff
+    /// between two attempts.
+    #[prost(bool, tag = "4")]
+    pub locked: bool,
+    #[prost(message, optional, tag = "5")]
+    pub blocked_until: ::core::option::Option<::prost_types::Timestamp>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetLockoutsRequest {}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetLockoutsResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub lockouts: ::prost::alloc::vec::Vec<Lockout>,
+}
+/// Exactly one of `username` and `address` is set.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UnlockRequest {
+    #[prost(string, tag = "1")]
+    pub username: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub address: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UnlockResponse {
+    /// Whether there were failed attempts to forget.
+    #[prost(bool, tag = "1")]
+    pub unlocked: bool,
 }
 #[doc = r" Generated client implementations."]
 pub mod auth_client {
@@ -43,98 +263,1063 @@ pub mod auth_client {
             Ok(Self::new(conn))
         }
     }
-    impl<T> AuthClient<T>
-    where
-        T: tonic::client::GrpcService<tonic::body::BoxBody>,
-        T::ResponseBody: Body + Send + Sync + 'static,
-        T::Error: Into<StdError>,
-        <T::ResponseBody as Body>::Error: Into<StdError> + Send,
-    {
-        pub fn new(inner: T) -> Self {
-            let inner = tonic::client::Grpc::new(inner);
-            Self { inner }
-        }
-        pub fn with_interceptor<F>(inner: T, interceptor: F) -> AuthClient<InterceptedService<T, F>>
-        where
-            F: tonic::service::Interceptor,
-            T: tonic::codegen::Service<
-                http::Request<tonic::body::BoxBody>,
-                Response = http::Response<
-                    <T as tonic::client::GrpcService<tonic::body::BoxBody>>::ResponseBody,
-                >,
-            >,
-            <T as tonic::codegen::Service<http::Request<tonic::body::BoxBody>>>::Error:
-                Into<StdError> + Send + Sync,
-        {
-            AuthClient::new(InterceptedService::new(inner, interceptor))
-        }
-        #[doc = r" Compress requests with `gzip`."]
-        #[doc = r""]
-        #[doc = r" This requires the server to support it otherwise it might respond with an"]
-        #[doc = r" error."]
-        pub fn send_gzip(mut self) -> Self {
-            self.inner = self.inner.send_gzip();
-            self
-        }
-        #[doc = r" Enable decompressing responses with `gzip`."]
-        pub fn accept_gzip(mut self) -> Self {
-            self.inner = self.inner.accept_gzip();
-            self
+    impl<T> AuthClient<T>
+    where
+        T: tonic::client::GrpcService<tonic::body::BoxBody>,
+        T::ResponseBody: Body + Send + Sync + 'static,
+        T::Error: Into<StdError>,
+        <T::ResponseBody as Body>::Error: Into<StdError> + Send,
+    {
+        pub fn new(inner: T) -> Self {
+            let inner = tonic::client::Grpc::new(inner);
+            Self { inner }
+        }
+        pub fn with_interceptor<F>(inner: T, interceptor: F) -> AuthClient<InterceptedService<T, F>>
+        where
+            F: tonic::service::Interceptor,
+            T: tonic::codegen::Service<
+                http::Request<tonic::body::BoxBody>,
+                Response = http::Response<
+                    <T as tonic::client::GrpcService<tonic::body::BoxBody>>::ResponseBody,
+                >,
+            >,
+            <T as tonic::codegen::Service<http::Request<tonic::body::BoxBody>>>::Error:
+                Into<StdError> + Send + Sync,
+        {
+            AuthClient::new(InterceptedService::new(inner, interceptor))
+        }
+        #[doc = r" Compress requests with `gzip`."]
+        #[doc = r""]
+        #[doc = r" This requires the server to support it otherwise it might respond with an"]
+        #[doc = r" error."]
+        pub fn send_gzip(mut self) -> Self {
+            self.inner = self.inner.send_gzip();
+            self
+        }
+        #[doc = r" Enable decompressing responses with `gzip`."]
+        pub fn accept_gzip(mut self) -> Self {
+            self.inner = self.inner.accept_gzip();
+            self
+        }
+        pub async fn sign_up(
+            &mut self,
+            request: impl tonic::IntoRequest<super::SignUpRequest>,
+        ) -> Result<tonic::Response<super::SignUpResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/auth.Auth/SignUp");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        #[doc = " Failed attempts slow down further attempts for the username and the"]
+        #[doc = " peer address with RESOURCE_EXHAUSTED, until too many failures lock the"]
+        #[doc = " username out with PERMISSION_DENIED."]
+        pub async fn sign_in(
+            &mut self,
+            request: impl tonic::IntoRequest<super::SignInRequest>,
+        ) -> Result<tonic::Response<super::SignInResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/auth.Auth/SignIn");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        #[doc = " Exchanges the challenge of a sign in for tokens. A challenge expires"]
+        #[doc = " after a few minutes or attempts, and can only be exchanged once."]
+        pub async fn verify_second_factor(
+            &mut self,
+            request: impl tonic::IntoRequest<super::VerifySecondFactorRequest>,
+        ) -> Result<tonic::Response<super::VerifySecondFactorResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/auth.Auth/VerifySecondFactor");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        pub async fn refresh_token(
+            &mut self,
+            request: impl tonic::IntoRequest<super::RefreshTokenRequest>,
+        ) -> Result<tonic::Response<super::RefreshTokenResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/auth.Auth/RefreshToken");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        #[doc = " SignOut and SignOutEverywhere require the `authorization` metadata."]
+        pub async fn sign_out(
+            &mut self,
+            request: impl tonic::IntoRequest<super::SignOutRequest>,
+        ) -> Result<tonic::Response<super::SignOutResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/auth.Auth/SignOut");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        pub async fn sign_out_everywhere(
+            &mut self,
+            request: impl tonic::IntoRequest<super::SignOutEverywhereRequest>,
+        ) -> Result<tonic::Response<super::SignOutEverywhereResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/auth.Auth/SignOutEverywhere");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+    }
+}
+#[doc = r" Generated client implementations."]
+pub mod account_client {
+    #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
+    use tonic::codegen::*;
+    #[doc = " Management of the signed in account. Every call requires the"]
+    #[doc = " `authorization` metadata."]
+    #[derive(Debug, Clone)]
+    pub struct AccountClient<T> {
+        inner: tonic::client::Grpc<T>,
+    }
+    impl AccountClient<tonic::transport::Channel> {
+        #[doc = r" Attempt to create a new client by connecting to a given endpoint."]
+        pub async fn connect<D>(dst: D) -> Result<Self, tonic::transport::Error>
+        where
+            D: std::convert::TryInto<tonic::transport::Endpoint>,
+            D::Error: Into<StdError>,
+        {
+            let conn = tonic::transport::Endpoint::new(dst)?.connect().await?;
+            Ok(Self::new(conn))
+        }
+    }
+    impl<T> AccountClient<T>
+    where
+        T: tonic::client::GrpcService<tonic::body::BoxBody>,
+        T::ResponseBody: Body + Send + Sync + 'static,
+        T::Error: Into<StdError>,
+        <T::ResponseBody as Body>::Error: Into<StdError> + Send,
+    {
+        pub fn new(inner: T) -> Self {
+            let inner = tonic::client::Grpc::new(inner);
+            Self { inner }
+        }
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> AccountClient<InterceptedService<T, F>>
+        where
+            F: tonic::service::Interceptor,
+            T: tonic::codegen::Service<
+                http::Request<tonic::body::BoxBody>,
+                Response = http::Response<
+                    <T as tonic::client::GrpcService<tonic::body::BoxBody>>::ResponseBody,
+                >,
+            >,
+            <T as tonic::codegen::Service<http::Request<tonic::body::BoxBody>>>::Error:
+                Into<StdError> + Send + Sync,
+        {
+            AccountClient::new(InterceptedService::new(inner, interceptor))
+        }
+        #[doc = r" Compress requests with `gzip`."]
+        #[doc = r""]
+        #[doc = r" This requires the server to support it otherwise it might respond with an"]
+        #[doc = r" error."]
+        pub fn send_gzip(mut self) -> Self {
+            self.inner = self.inner.send_gzip();
+            self
+        }
+        #[doc = r" Enable decompressing responses with `gzip`."]
+        pub fn accept_gzip(mut self) -> Self {
+            self.inner = self.inner.accept_gzip();
+            self
+        }
+        pub async fn change_pin(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ChangePinRequest>,
+        ) -> Result<tonic::Response<super::ChangePinResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/auth.Account/ChangePin");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        pub async fn change_password(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ChangePasswordRequest>,
+        ) -> Result<tonic::Response<super::ChangePasswordResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/auth.Account/ChangePassword");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        pub async fn change_username(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ChangeUsernameRequest>,
+        ) -> Result<tonic::Response<super::ChangeUsernameResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/auth.Account/ChangeUsername");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        #[doc = " Deletes the todos, projects and tags of the account and signs it out"]
+        #[doc = " everywhere. The username becomes free to sign up with again."]
+        pub async fn delete_account(
+            &mut self,
+            request: impl tonic::IntoRequest<super::DeleteAccountRequest>,
+        ) -> Result<tonic::Response<super::DeleteAccountResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/auth.Account/DeleteAccount");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        #[doc = " Two-factor authentication is enabled by EnrollTotp, then ConfirmTotp"]
+        #[doc = " with a code of the enrolled authenticator app. SignIn requires a code"]
+        #[doc = " from then on."]
+        pub async fn enroll_totp(
+            &mut self,
+            request: impl tonic::IntoRequest<super::EnrollTotpRequest>,
+        ) -> Result<tonic::Response<super::EnrollTotpResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/auth.Account/EnrollTotp");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        pub async fn confirm_totp(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ConfirmTotpRequest>,
+        ) -> Result<tonic::Response<super::ConfirmTotpResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/auth.Account/ConfirmTotp");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        pub async fn disable_totp(
+            &mut self,
+            request: impl tonic::IntoRequest<super::DisableTotpRequest>,
+        ) -> Result<tonic::Response<super::DisableTotpResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/auth.Account/DisableTotp");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+    }
+}
+#[doc = r" Generated client implementations."]
+pub mod admin_client {
+    #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
+    use tonic::codegen::*;
+    #[doc = " Reserved to the administrators of the server, who send its `ADMIN_TOKEN` as"]
+    #[doc = " the `authorization` metadata."]
+    #[derive(Debug, Clone)]
+    pub struct AdminClient<T> {
+        inner: tonic::client::Grpc<T>,
+    }
+    impl AdminClient<tonic::transport::Channel> {
+        #[doc = r" Attempt to create a new client by connecting to a given endpoint."]
+        pub async fn connect<D>(dst: D) -> Result<Self, tonic::transport::Error>
+        where
+            D: std::convert::TryInto<tonic::transport::Endpoint>,
+            D::Error: Into<StdError>,
+        {
+            let conn = tonic::transport::Endpoint::new(dst)?.connect().await?;
+            Ok(Self::new(conn))
+        }
+    }
+    impl<T> AdminClient<T>
+    where
+        T: tonic::client::GrpcService<tonic::body::BoxBody>,
+        T::ResponseBody: Body + Send + Sync + 'static,
+        T::Error: Into<StdError>,
+        <T::ResponseBody as Body>::Error: Into<StdError> + Send,
+    {
+        pub fn new(inner: T) -> Self {
+            let inner = tonic::client::Grpc::new(inner);
+            Self { inner }
+        }
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> AdminClient<InterceptedService<T, F>>
+        where
+            F: tonic::service::Interceptor,
+            T: tonic::codegen::Service<
+                http::Request<tonic::body::BoxBody>,
+                Response = http::Response<
+                    <T as tonic::client::GrpcService<tonic::body::BoxBody>>::ResponseBody,
+                >,
+            >,
+            <T as tonic::codegen::Service<http::Request<tonic::body::BoxBody>>>::Error:
+                Into<StdError> + Send + Sync,
+        {
+            AdminClient::new(InterceptedService::new(inner, interceptor))
+        }
+        #[doc = r" Compress requests with `gzip`."]
+        #[doc = r""]
+        #[doc = r" This requires the server to support it otherwise it might respond with an"]
+        #[doc = r" error."]
+        pub fn send_gzip(mut self) -> Self {
+            self.inner = self.inner.send_gzip();
+            self
+        }
+        #[doc = r" Enable decompressing responses with `gzip`."]
+        pub fn accept_gzip(mut self) -> Self {
+            self.inner = self.inner.accept_gzip();
+            self
+        }
+        pub async fn get_lockouts(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetLockoutsRequest>,
+        ) -> Result<tonic::Response<super::GetLockoutsResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/auth.Admin/GetLockouts");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        #[doc = " Forgets the failed sign in attempts of a username or a peer address."]
+        pub async fn unlock(
+            &mut self,
+            request: impl tonic::IntoRequest<super::UnlockRequest>,
+        ) -> Result<tonic::Response<super::UnlockResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/auth.Admin/Unlock");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+    }
+}
+#[doc = r" Generated server implementations."]
+pub mod auth_server {
+    #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
+    use tonic::codegen::*;
+    #[doc = "Generated trait containing gRPC methods that should be implemented for use with AuthServer."]
+    #[async_trait]
+    pub trait Auth: Send + Sync + 'static {
+        async fn sign_up(
+            &self,
+            request: tonic::Request<super::SignUpRequest>,
+        ) -> Result<tonic::Response<super::SignUpResponse>, tonic::Status>;
+        #[doc = " Failed attempts slow down further attempts for the username and the"]
+        #[doc = " peer address with RESOURCE_EXHAUSTED, until too many failures lock the"]
+        #[doc = " username out with PERMISSION_DENIED."]
+        async fn sign_in(
+            &self,
+            request: tonic::Request<super::SignInRequest>,
+        ) -> Result<tonic::Response<super::SignInResponse>, tonic::Status>;
+        #[doc = " Exchanges the challenge of a sign in for tokens. A challenge expires"]
+        #[doc = " after a few minutes or attempts, and can only be exchanged once."]
+        async fn verify_second_factor(
+            &self,
+            request: tonic::Request<super::VerifySecondFactorRequest>,
+        ) -> Result<tonic::Response<super::VerifySecondFactorResponse>, tonic::Status>;
+        async fn refresh_token(
+            &self,
+            request: tonic::Request<super::RefreshTokenRequest>,
+        ) -> Result<tonic::Response<super::RefreshTokenResponse>, tonic::Status>;
+        #[doc = " SignOut and SignOutEverywhere require the `authorization` metadata."]
+        async fn sign_out(
+            &self,
+            request: tonic::Request<super::SignOutRequest>,
+        ) -> Result<tonic::Response<super::SignOutResponse>, tonic::Status>;
+        async fn sign_out_everywhere(
+            &self,
+            request: tonic::Request<super::SignOutEverywhereRequest>,
+        ) -> Result<tonic::Response<super::SignOutEverywhereResponse>, tonic::Status>;
+    }
+    #[derive(Debug)]
+    pub struct AuthServer<T: Auth> {
+        inner: _Inner<T>,
+        accept_compression_encodings: (),
+        send_compression_encodings: (),
+    }
+    struct _Inner<T>(Arc<T>);
+    impl<T: Auth> AuthServer<T> {
+        pub fn new(inner: T) -> Self {
+            let inner = Arc::new(inner);
+            let inner = _Inner(inner);
+            Self {
+                inner,
+                accept_compression_encodings: Default::default(),
+                send_compression_encodings: Default::default(),
+            }
+        }
+        pub fn with_interceptor<F>(inner: T, interceptor: F) -> InterceptedService<Self, F>
+        where
+            F: tonic::service::Interceptor,
+        {
+            InterceptedService::new(Self::new(inner), interceptor)
+        }
+    }
+    impl<T, B> tonic::codegen::Service<http::Request<B>> for AuthServer<T>
+    where
+        T: Auth,
+        B: Body + Send + Sync + 'static,
+        B::Error: Into<StdError> + Send + 'static,
+    {
+        type Response = http::Response<tonic::body::BoxBody>;
+        type Error = Never;
+        type Future = BoxFuture<Self::Response, Self::Error>;
+        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+        fn call(&mut self, req: http::Request<B>) -> Self::Future {
+            let inner = self.inner.clone();
+            match req.uri().path() {
+                "/auth.Auth/SignUp" => {
+                    #[allow(non_camel_case_types)]
+                    struct SignUpSvc<T: Auth>(pub Arc<T>);
+                    impl<T: Auth> tonic::server::UnaryService<super::SignUpRequest> for SignUpSvc<T> {
+                        type Response = super::SignUpResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::SignUpRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).sign_up(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = SignUpSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/auth.Auth/SignIn" => {
+                    #[allow(non_camel_case_types)]
+                    struct SignInSvc<T: Auth>(pub Arc<T>);
+                    impl<T: Auth> tonic::server::UnaryService<super::SignInRequest> for SignInSvc<T> {
+                        type Response = super::SignInResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::SignInRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).sign_in(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = SignInSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/auth.Auth/VerifySecondFactor" => {
+                    #[allow(non_camel_case_types)]
+                    struct VerifySecondFactorSvc<T: Auth>(pub Arc<T>);
+                    impl<T: Auth> tonic::server::UnaryService<super::VerifySecondFactorRequest>
+                        for VerifySecondFactorSvc<T>
+                    {
+                        type Response = super::VerifySecondFactorResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::VerifySecondFactorRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).verify_second_factor(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = VerifySecondFactorSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/auth.Auth/RefreshToken" => {
+                    #[allow(non_camel_case_types)]
+                    struct RefreshTokenSvc<T: Auth>(pub Arc<T>);
+                    impl<T: Auth> tonic::server::UnaryService<super::RefreshTokenRequest> for RefreshTokenSvc<T> {
+                        type Response = super::RefreshTokenResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::RefreshTokenRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).refresh_token(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = RefreshTokenSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/auth.Auth/SignOut" => {
+                    #[allow(non_camel_case_types)]
+                    struct SignOutSvc<T: Auth>(pub Arc<T>);
+                    impl<T: Auth> tonic::server::UnaryService<super::SignOutRequest> for SignOutSvc<T> {
+                        type Response = super::SignOutResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::SignOutRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).sign_out(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = SignOutSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/auth.Auth/SignOutEverywhere" => {
+                    #[allow(non_camel_case_types)]
+                    struct SignOutEverywhereSvc<T: Auth>(pub Arc<T>);
+                    impl<T: Auth> tonic::server::UnaryService<super::SignOutEverywhereRequest>
+                        for SignOutEverywhereSvc<T>
+                    {
+                        type Response = super::SignOutEverywhereResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::SignOutEverywhereRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).sign_out_everywhere(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = SignOutEverywhereSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                _ => Box::pin(async move {
+                    Ok(http::Response::builder()
+                        .status(200)
+                        .header("grpc-status", "12")
+                        .header("content-type", "application/grpc")
+                        .body(empty_body())
+                        .unwrap())
+                }),
+            }
+        }
+    }
+    impl<T: Auth> Clone for AuthServer<T> {
+        fn clone(&self) -> Self {
+            let inner = self.inner.clone();
+            Self {
+                inner,
+                accept_compression_encodings: self.accept_compression_encodings,
+                send_compression_encodings: self.send_compression_encodings,
+            }
+        }
+    }
+    impl<T: Auth> Clone for _Inner<T> {
+        fn clone(&self) -> Self {
+            Self(self.0.clone())
+        }
+    }
+    impl<T: std::fmt::Debug> std::fmt::Debug for _Inner<T> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "{:?}", self.0)
+        }
+    }
+    impl<T: Auth> tonic::transport::NamedService for AuthServer<T> {
+        const NAME: &'static str = "auth.Auth";
+    }
+}
+#[doc = r" Generated server implementations."]
+pub mod account_server {
+    #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
+    use tonic::codegen::*;
+    #[doc = "Generated trait containing gRPC methods that should be implemented for use with AccountServer."]
+    #[async_trait]
+    pub trait Account: Send + Sync + 'static {
+        async fn change_pin(
+            &self,
+            request: tonic::Request<super::ChangePinRequest>,
+        ) -> Result<tonic::Response<super::ChangePinResponse>, tonic::Status>;
+        async fn change_password(
+            &self,
+            request: tonic::Request<super::ChangePasswordRequest>,
+        ) -> Result<tonic::Response<super::ChangePasswordResponse>, tonic::Status>;
+        async fn change_username(
+            &self,
+            request: tonic::Request<super::ChangeUsernameRequest>,
+        ) -> Result<tonic::Response<super::ChangeUsernameResponse>, tonic::Status>;
+        #[doc = " Deletes the todos, projects and tags of the account and signs it out"]
+        #[doc = " everywhere. The username becomes free to sign up with again."]
+        async fn delete_account(
+            &self,
+            request: tonic::Request<super::DeleteAccountRequest>,
+        ) -> Result<tonic::Response<super::DeleteAccountResponse>, tonic::Status>;
+        #[doc = " Two-factor authentication is enabled by EnrollTotp, then ConfirmTotp"]
+        #[doc = " with a code of the enrolled authenticator app. SignIn requires a code"]
+        #[doc = " from then on."]
+        async fn enroll_totp(
+            &self,
+            request: tonic::Request<super::EnrollTotpRequest>,
+        ) -> Result<tonic::Response<super::EnrollTotpResponse>, tonic::Status>;
+        async fn confirm_totp(
+            &self,
+            request: tonic::Request<super::ConfirmTotpRequest>,
+        ) -> Result<tonic::Response<super::ConfirmTotpResponse>, tonic::Status>;
+        async fn disable_totp(
+            &self,
+            request: tonic::Request<super::DisableTotpRequest>,
+        ) -> Result<tonic::Response<super::DisableTotpResponse>, tonic::Status>;
+    }
+    #[doc = " Management of the signed in account. Every call requires the"]
+    #[doc = " `authorization` metadata."]
+    #[derive(Debug)]
+    pub struct AccountServer<T: Account> {
+        inner: _Inner<T>,
+        accept_compression_encodings: (),
+        send_compression_encodings: (),
+    }
+    struct _Inner<T>(Arc<T>);
+    impl<T: Account> AccountServer<T> {
+        pub fn new(inner: T) -> Self {
+            let inner = Arc::new(inner);
+            let inner = _Inner(inner);
+            Self {
+                inner,
+                accept_compression_encodings: Default::default(),
+                send_compression_encodings: Default::default(),
+            }
+        }
+        pub fn with_interceptor<F>(inner: T, interceptor: F) -> InterceptedService<Self, F>
+        where
+            F: tonic::service::Interceptor,
+        {
+            InterceptedService::new(Self::new(inner), interceptor)
+        }
+    }
+    impl<T, B> tonic::codegen::Service<http::Request<B>> for AccountServer<T>
+    where
+        T: Account,
+        B: Body + Send + Sync + 'static,
+        B::Error: Into<StdError> + Send + 'static,
+    {
+        type Response = http::Response<tonic::body::BoxBody>;
+        type Error = Never;
+        type Future = BoxFuture<Self::Response, Self::Error>;
+        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+        fn call(&mut self, req: http::Request<B>) -> Self::Future {
+            let inner = self.inner.clone();
+            match req.uri().path() {
+                "/auth.Account/ChangePin" => {
+                    #[allow(non_camel_case_types)]
+                    struct ChangePinSvc<T: Account>(pub Arc<T>);
+                    impl<T: Account> tonic::server::UnaryService<super::ChangePinRequest> for ChangePinSvc<T> {
+                        type Response = super::ChangePinResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ChangePinRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).change_pin(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ChangePinSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/auth.Account/ChangePassword" => {
+                    #[allow(non_camel_case_types)]
+                    struct ChangePasswordSvc<T: Account>(pub Arc<T>);
+                    impl<T: Account> tonic::server::UnaryService<super::ChangePasswordRequest>
+                        for ChangePasswordSvc<T>
+                    {
+                        type Response = super::ChangePasswordResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ChangePasswordRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).change_password(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ChangePasswordSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/auth.Account/ChangeUsername" => {
+                    #[allow(non_camel_case_types)]
+                    struct ChangeUsernameSvc<T: Account>(pub Arc<T>);
+                    impl<T: Account> tonic::server::UnaryService<super::ChangeUsernameRequest>
+                        for ChangeUsernameSvc<T>
+                    {
+                        type Response = super::ChangeUsernameResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ChangeUsernameRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).change_username(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ChangeUsernameSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/auth.Account/DeleteAccount" => {
+                    #[allow(non_camel_case_types)]
+                    struct DeleteAccountSvc<T: Account>(pub Arc<T>);
+                    impl<T: Account> tonic::server::UnaryService<super::DeleteAccountRequest> for DeleteAccountSvc<T> {
+                        type Response = super::DeleteAccountResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::DeleteAccountRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).delete_account(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = DeleteAccountSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/auth.Account/EnrollTotp" => {
+                    #[allow(non_camel_case_types)]
+                    struct EnrollTotpSvc<T: Account>(pub Arc<T>);
+                    impl<T: Account> tonic::server::UnaryService<super::EnrollTotpRequest> for EnrollTotpSvc<T> {
+                        type Response = super::EnrollTotpResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::EnrollTotpRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).enroll_totp(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = EnrollTotpSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/auth.Account/ConfirmTotp" => {
+                    #[allow(non_camel_case_types)]
+                    struct ConfirmTotpSvc<T: Account>(pub Arc<T>);
+                    impl<T: Account> tonic::server::UnaryService<super::ConfirmTotpRequest> for ConfirmTotpSvc<T> {
+                        type Response = super::ConfirmTotpResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ConfirmTotpRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).confirm_totp(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ConfirmTotpSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/auth.Account/DisableTotp" => {
+                    #[allow(non_camel_case_types)]
+                    struct DisableTotpSvc<T: Account>(pub Arc<T>);
+                    impl<T: Account> tonic::server::UnaryService<super::DisableTotpRequest> for DisableTotpSvc<T> {
+                        type Response = super::DisableTotpResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::DisableTotpRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).disable_totp(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = DisableTotpSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                _ => Box::pin(async move {
+                    Ok(http::Response::builder()
+                        .status(200)
+                        .header("grpc-status", "12")
+                        .header("content-type", "application/grpc")
+                        .body(empty_body())
+                        .unwrap())
+                }),
+            }
+        }
+    }
+    impl<T: Account> Clone for AccountServer<T> {
+        fn clone(&self) -> Self {
+            let inner = self.inner.clone();
+            Self {
+                inner,
+                accept_compression_encodings: self.accept_compression_encodings,
+                send_compression_encodings: self.send_compression_encodings,
+            }
         }
-        pub async fn sign_up(
-            &mut self,
-            request: impl tonic::IntoRequest<super::SignUpRequest>,
-        ) -> Result<tonic::Response<super::SignUpResponse>, tonic::Status> {
-            self.inner.ready().await.map_err(|e| {
-                tonic::Status::new(
-                    tonic::Code::Unknown,
-                    format!("Service was not ready: {}", e.into()),
-                )
-            })?;
-            let codec = tonic::codec::ProstCodec::default();
-            let path = http::uri::PathAndQuery::from_static("/auth.Auth/SignUp");
-            self.inner.unary(request.into_request(), path, codec).await
+    }
+    impl<T: Account> Clone for _Inner<T> {
+        fn clone(&self) -> Self {
+            Self(self.0.clone())
         }
-        pub async fn sign_in(
-            &mut self,
-            request: impl tonic::IntoRequest<super::SignInRequest>,
-        ) -> Result<tonic::Response<super::SignInResponse>, tonic::Status> {
-            self.inner.ready().await.map_err(|e| {
-                tonic::Status::new(
-                    tonic::Code::Unknown,
-                    format!("Service was not ready: {}", e.into()),
-                )
-            })?;
-            let codec = tonic::codec::ProstCodec::default();
-            let path = http::uri::PathAndQuery::from_static("/auth.Auth/SignIn");
-            self.inner.unary(request.into_request(), path, codec).await
+    }
+    impl<T: std::fmt::Debug> std::fmt::Debug for _Inner<T> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "{:?}", self.0)
         }
     }
+    impl<T: Account> tonic::transport::NamedService for AccountServer<T> {
+        const NAME: &'static str = "auth.Account";
+    }
 }
 #[doc = r" Generated server implementations."]
-pub mod auth_server {
+pub mod admin_server {
     #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
     use tonic::codegen::*;
-    #[doc = "Generated trait containing gRPC methods that should be implemented for use with AuthServer."]
+    #[doc = "Generated trait containing gRPC methods that should be implemented for use with AdminServer."]
     #[async_trait]
-    pub trait Auth: Send + Sync + 'static {
-        async fn sign_up(
+    pub trait Admin: Send + Sync + 'static {
+        async fn get_lockouts(
             &self,
-            request: tonic::Request<super::SignUpRequest>,
-        ) -> Result<tonic::Response<super::SignUpResponse>, tonic::Status>;
-        async fn sign_in(
+            request: tonic::Request<super::GetLockoutsRequest>,
+        ) -> Result<tonic::Response<super::GetLockoutsResponse>, tonic::Status>;
+        #[doc = " Forgets the failed sign in attempts of a username or a peer address."]
+        async fn unlock(
             &self,
-            request: tonic::Request<super::SignInRequest>,
-        ) -> Result<tonic::Response<super::SignInResponse>, tonic::Status>;
+            request: tonic::Request<super::UnlockRequest>,
+        ) -> Result<tonic::Response<super::UnlockResponse>, tonic::Status>;
     }
+    #[doc = " Reserved to the administrators of the server, who send its `ADMIN_TOKEN` as"]
+    #[doc = " the `authorization` metadata."]
     #[derive(Debug)]
-    pub struct AuthServer<T: Auth> {
+    pub struct AdminServer<T: Admin> {
         inner: _Inner<T>,
         accept_compression_encodings: (),
         send_compression_encodings: (),
     }
     struct _Inner<T>(Arc<T>);
-    impl<T: Auth> AuthServer<T> {
+    impl<T: Admin> AdminServer<T> {
         pub fn new(inner: T) -> Self {
             let inner = Arc::new(inner);
             let inner = _Inner(inner);
@@ -151,9 +1336,9 @@ pub mod auth_server {
             InterceptedService::new(Self::new(inner), interceptor)
         }
     }
-    impl<T, B> tonic::codegen::Service<http::Request<B>> for AuthServer<T>
+    impl<T, B> tonic::codegen::Service<http::Request<B>> for AdminServer<T>
     where
-        T: Auth,
+        T: Admin,
         B: Body + Send + Sync + 'static,
         B::Error: Into<StdError> + Send + 'static,
     {
@@ -166,18 +1351,18 @@ pub mod auth_server {
         fn call(&mut self, req: http::Request<B>) -> Self::Future {
             let inner = self.inner.clone();
             match req.uri().path() {
-                "/auth.Auth/SignUp" => {
+                "/auth.Admin/GetLockouts" => {
                     #[allow(non_camel_case_types)]
-                    struct SignUpSvc<T: Auth>(pub Arc<T>);
-                    impl<T: Auth> tonic::server::UnaryService<super::SignUpRequest> for SignUpSvc<T> {
-                        type Response = super::SignUpResponse;
+                    struct GetLockoutsSvc<T: Admin>(pub Arc<T>);
+                    impl<T: Admin> tonic::server::UnaryService<super::GetLockoutsRequest> for GetLockoutsSvc<T> {
+                        type Response = super::GetLockoutsResponse;
                         type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::SignUpRequest>,
+                            request: tonic::Request<super::GetLockoutsRequest>,
                         ) -> Self::Future {
                             let inner = self.0.clone();
-                            let fut = async move { (*inner).sign_up(request).await };
+                            let fut = async move { (*inner).get_lockouts(request).await };
                             Box::pin(fut)
                         }
                     }
@@ -186,7 +1371,7 @@ pub mod auth_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = SignUpSvc(inner);
+                        let method = GetLockoutsSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
                             accept_compression_encodings,
@@ -197,18 +1382,18 @@ pub mod auth_server {
                     };
                     Box::pin(fut)
                 }
-                "/auth.Auth/SignIn" => {
+                "/auth.Admin/Unlock" => {
                     #[allow(non_camel_case_types)]
-                    struct SignInSvc<T: Auth>(pub Arc<T>);
-                    impl<T: Auth> tonic::server::UnaryService<super::SignInRequest> for SignInSvc<T> {
-                        type Response = super::SignInResponse;
+                    struct UnlockSvc<T: Admin>(pub Arc<T>);
+                    impl<T: Admin> tonic::server::UnaryService<super::UnlockRequest> for UnlockSvc<T> {
+                        type Response = super::UnlockResponse;
                         type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::SignInRequest>,
+                            request: tonic::Request<super::UnlockRequest>,
                         ) -> Self::Future {
                             let inner = self.0.clone();
-                            let fut = async move { (*inner).sign_in(request).await };
+                            let fut = async move { (*inner).unlock(request).await };
                             Box::pin(fut)
                         }
                     }
@@ -217,7 +1402,7 @@ pub mod auth_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = SignInSvc(inner);
+                        let method = UnlockSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
                             accept_compression_encodings,
@@ -239,7 +1424,7 @@ pub mod auth_server {
             }
         }
     }
-    impl<T: Auth> Clone for AuthServer<T> {
+    impl<T: Admin> Clone for AdminServer<T> {
         fn clone(&self) -> Self {
             let inner = self.inner.clone();
             Self {
@@ -249,7 +1434,7 @@ pub mod auth_server {
             }
         }
     }
-    impl<T: Auth> Clone for _Inner<T> {
+    impl<T: Admin> Clone for _Inner<T> {
         fn clone(&self) -> Self {
             Self(self.0.clone())
         }
@@ -259,7 +1444,7 @@ pub mod auth_server {
             write!(f, "{:?}", self.0)
         }
     }
-    impl<T: Auth> tonic::transport::NamedService for AuthServer<T> {
-        const NAME: &'static str = "auth.Auth";
+    impl<T: Admin> tonic::transport::NamedService for AdminServer<T> {
+        const NAME: &'static str = "auth.Admin";
     }
 }
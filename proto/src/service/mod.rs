@@ -1,2 +1,8 @@
 pub mod auth;
 pub mod todo;
+
+pub mod google {
+    pub mod rpc {
+        include!("google.rpc.rs");
+    }
+}
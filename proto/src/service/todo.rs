@@ -6,19 +6,547 @@ pub struct TodoItem {
     pub description: ::prost::alloc::string::String,
     #[prost(enumeration = "TodoStatus", tag = "3")]
     pub status: i32,
+    #[prost(message, optional, tag = "4")]
+    pub created_at: ::core::option::Option<::prost_types::Timestamp>,
+    #[prost(string, tag = "5")]
+    pub title: ::prost::alloc::string::String,
+    #[prost(string, tag = "6")]
+    pub notes: ::prost::alloc::string::String,
+    #[prost(enumeration = "TodoPriority", tag = "7")]
+    pub priority: i32,
+    #[prost(message, optional, tag = "8")]
+    pub due_at: ::core::option::Option<::prost_types::Timestamp>,
+    #[prost(message, optional, tag = "9")]
+    pub updated_at: ::core::option::Option<::prost_types::Timestamp>,
+    /// Set while the todo is completed.
+    #[prost(message, optional, tag = "10")]
+    pub completed_at: ::core::option::Option<::prost_types::Timestamp>,
+    /// Sorted by name.
+    #[prost(string, repeated, tag = "11")]
+    pub tags: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// 0 when the todo is in the inbox rather than in a project.
+    #[prost(uint32, tag = "12")]
+    pub project_id: u32,
+    /// Place of the todo within its project or the inbox.
+    #[prost(int64, tag = "13")]
+    pub position: i64,
+    /// Todo this one is a subtask of, 0 for top-level todos.
+    #[prost(uint32, tag = "14")]
+    pub parent_id: u32,
+    /// Ids of the todos this one is blocked by.
+    #[prost(uint32, repeated, tag = "15")]
+    pub blocked_by: ::prost::alloc::vec::Vec<u32>,
+    /// RFC 5545 RRULE such as `FREQ=WEEKLY;BYDAY=MO,TH`, empty for todos that
+    /// do not recur. See CreateTodoRequest.
+    #[prost(string, tag = "16")]
+    pub recurrence: ::prost::alloc::string::String,
+    /// When the owner and the members of the project of the todo are reminded
+    /// of it, see StreamReminders. Reminders of todos that are done are not
+    /// sent.
+    #[prost(message, optional, tag = "17")]
+    pub reminder_at: ::core::option::Option<::prost_types::Timestamp>,
 }
+/// The token of the next page is sent in the `PageInfo` that ends the GetTodos
+/// stream.
 #[derive(Clone, PartialEq, ::prost::Message)]
-pub struct GetTodoRequest {}
+pub struct GetTodoRequest {
+    /// Only todos with one of these statuses are returned, all of them when empty.
+    #[prost(enumeration = "TodoStatus", repeated, tag = "1")]
+    pub statuses: ::prost::alloc::vec::Vec<i32>,
+    /// Case-insensitive text the title, description or notes must contain.
+    #[prost(string, tag = "2")]
+    pub search: ::prost::alloc::string::String,
+    #[prost(enumeration = "TodoSortField", tag = "3")]
+    pub sort_by: i32,
+    #[prost(bool, tag = "4")]
+    pub descending: bool,
+    /// Defaults to 50, at most 100.
+    #[prost(uint32, tag = "5")]
+    pub page_size: u32,
+    /// `next_page_token` of the previous page, empty for the first one. Only
+    /// valid with the same filters and ordering as that page.
+    #[prost(string, tag = "6")]
+    pub page_token: ::prost::alloc::string::String,
+    /// Only todos with one of these priorities are returned, all of them when empty.
+    #[prost(enumeration = "TodoPriority", repeated, tag = "7")]
+    pub priorities: ::prost::alloc::vec::Vec<i32>,
+    /// Only todos due at or after this time.
+    #[prost(message, optional, tag = "8")]
+    pub due_after: ::core::option::Option<::prost_types::Timestamp>,
+    /// Only todos due before this time.
+    #[prost(message, optional, tag = "9")]
+    pub due_before: ::core::option::Option<::prost_types::Timestamp>,
+    /// Only todos with these tags, matched according to `tag_match`.
+    #[prost(string, repeated, tag = "10")]
+    pub tags: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    #[prost(enumeration = "TagMatch", tag = "11")]
+    pub tag_match: i32,
+    /// Only todos of this project, or of the inbox when 0.
+    #[prost(message, optional, tag = "12")]
+    pub project_id: ::core::option::Option<u32>,
+}
+/// Sent after the todos of a GetTodos page.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PageInfo {
+    /// Token of the next page, empty on the last one.
+    #[prost(string, tag = "1")]
+    pub next_page_token: ::prost::alloc::string::String,
+}
+/// GetTodos streams the todos of the page, followed by a single `page_info`.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetTodosResponse {
+    #[prost(oneof = "get_todos_response::Message", tags = "1, 2")]
+    pub message: ::core::option::Option<get_todos_response::Message>,
+}
+/// Nested message and enum types in `GetTodosResponse`.
+pub mod get_todos_response {
+    #[allow(clippy::large_enum_variant)]
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Message {
+        #[prost(message, tag = "1")]
+        Todo(super::TodoItem),
+        #[prost(message, tag = "2")]
+        PageInfo(super::PageInfo),
+    }
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetTodoByIdRequest {
+    #[prost(uint32, tag = "1")]
+    pub id: u32,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CreateTodoRequest {
+    #[prost(string, tag = "1")]
+    pub description: ::prost::alloc::string::String,
+    #[prost(enumeration = "TodoStatus", tag = "2")]
+    pub status: i32,
+    #[prost(string, tag = "3")]
+    pub title: ::prost::alloc::string::String,
+    #[prost(string, tag = "4")]
+    pub notes: ::prost::alloc::string::String,
+    #[prost(enumeration = "TodoPriority", tag = "5")]
+    pub priority: i32,
+    #[prost(message, optional, tag = "6")]
+    pub due_at: ::core::option::Option<::prost_types::Timestamp>,
+    /// The todo is added at the end of this project, or of the inbox when 0.
+    #[prost(uint32, tag = "7")]
+    pub project_id: u32,
+    /// Creates the todo as a subtask of this one.
+    #[prost(uint32, tag = "8")]
+    pub parent_id: u32,
+    /// Makes the todo recur, starting at `due_at` which is then required.
+    /// Supports FREQ (DAILY, WEEKLY, MONTHLY or YEARLY), INTERVAL, COUNT,
+    /// UNTIL and, for weekly rules, BYDAY; times are in UTC. When the todo is
+    /// completed, or once it is overdue, the next occurrence is created with
+    /// the rule, which is cleared from this todo. Cancelling the todo ends the
+    /// series.
+    #[prost(string, tag = "9")]
+    pub recurrence: ::prost::alloc::string::String,
+    /// Next occurrences are reminded as long before their due date as this
+    /// todo is.
+    #[prost(message, optional, tag = "10")]
+    pub reminder_at: ::core::option::Option<::prost_types::Timestamp>,
+}
+/// Replaces every field of the todo. A change of status must be a valid
+/// transition, see TransitionTodo.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UpdateTodoRequest {
+    #[prost(uint32, tag = "1")]
+    pub id: u32,
+    #[prost(string, tag = "2")]
+    pub description: ::prost::alloc::string::String,
+    #[prost(enumeration = "TodoStatus", tag = "3")]
+    pub status: i32,
+    #[prost(string, tag = "4")]
+    pub title: ::prost::alloc::string::String,
+    #[prost(string, tag = "5")]
+    pub notes: ::prost::alloc::string::String,
+    #[prost(enumeration = "TodoPriority", tag = "6")]
+    pub priority: i32,
+    #[prost(message, optional, tag = "7")]
+    pub due_at: ::core::option::Option<::prost_types::Timestamp>,
+    #[prost(string, tag = "8")]
+    pub recurrence: ::prost::alloc::string::String,
+    /// Changing the reminder time sends the reminder again once it is due.
+    #[prost(message, optional, tag = "9")]
+    pub reminder_at: ::core::option::Option<::prost_types::Timestamp>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DeleteTodoRequest {
+    #[prost(uint32, tag = "1")]
+    pub id: u32,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DeleteTodoResponse {
+    #[prost(uint32, tag = "1")]
+    pub id: u32,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ImportRejection {
+    /// Position of the rejected todo in the import stream, starting at 0.
+    #[prost(uint32, tag = "1")]
+    pub index: u32,
+    #[prost(string, tag = "2")]
+    pub reason: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ImportSummary {
+    #[prost(uint32, tag = "1")]
+    pub imported: u32,
+    #[prost(message, repeated, tag = "2")]
+    pub rejected: ::prost::alloc::vec::Vec<ImportRejection>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SyncChange {
+    /// Chosen by the client and echoed in the acknowledgement.
+    #[prost(string, tag = "1")]
+    pub change_id: ::prost::alloc::string::String,
+    /// Resume token of the last event seen, to receive the changes missed while
+    /// offline. Only read from the first message, which may carry no change.
+    #[prost(string, tag = "5")]
+    pub resume_token: ::prost::alloc::string::String,
+    #[prost(oneof = "sync_change::Change", tags = "2, 3, 4")]
+    pub change: ::core::option::Option<sync_change::Change>,
+}
+/// Nested message and enum types in `SyncChange`.
+pub mod sync_change {
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Change {
+        #[prost(message, tag = "2")]
+        Create(super::CreateTodoRequest),
+        #[prost(message, tag = "3")]
+        Update(super::UpdateTodoRequest),
+        #[prost(message, tag = "4")]
+        Delete(super::DeleteTodoRequest),
+    }
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SyncAck {
+    #[prost(string, tag = "1")]
+    pub change_id: ::prost::alloc::string::String,
+    #[prost(bool, tag = "2")]
+    pub success: bool,
+    #[prost(string, tag = "3")]
+    pub error: ::prost::alloc::string::String,
+    /// The todo after the change. Only `id` is set for deletes.
+    #[prost(message, optional, tag = "4")]
+    pub todo: ::core::option::Option<TodoItem>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SyncResponse {
+    #[prost(oneof = "sync_response::Message", tags = "1, 2")]
+    pub message: ::core::option::Option<sync_response::Message>,
+}
+/// Nested message and enum types in `SyncResponse`.
+pub mod sync_response {
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Message {
+        #[prost(message, tag = "1")]
+        Ack(super::SyncAck),
+        #[prost(message, tag = "2")]
+        Event(super::TodoEvent),
+    }
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TransitionTodoRequest {
+    #[prost(uint32, tag = "1")]
+    pub id: u32,
+    #[prost(enumeration = "TodoStatus", tag = "2")]
+    pub status: i32,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TodoTransition {
+    #[prost(enumeration = "TodoStatus", tag = "1")]
+    pub from_status: i32,
+    #[prost(enumeration = "TodoStatus", tag = "2")]
+    pub to_status: i32,
+    /// Username of the user who changed the status.
+    #[prost(string, tag = "3")]
+    pub changed_by: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "4")]
+    pub changed_at: ::core::option::Option<::prost_types::Timestamp>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TransitionTodoResponse {
+    #[prost(message, optional, tag = "1")]
+    pub todo: ::core::option::Option<TodoItem>,
+    #[prost(message, optional, tag = "2")]
+    pub transition: ::core::option::Option<TodoTransition>,
+}
+/// Tag names are case-insensitive and returned in lower case.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TagTodoRequest {
+    #[prost(uint32, tag = "1")]
+    pub id: u32,
+    #[prost(string, repeated, tag = "2")]
+    pub tags: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListTagsRequest {}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Tag {
+    #[prost(string, tag = "1")]
+    pub name: ::prost::alloc::string::String,
+    /// Number of todos with this tag.
+    #[prost(uint32, tag = "2")]
+    pub todo_count: u32,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ListTagsResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub tags: ::prost::alloc::vec::Vec<Tag>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Project {
+    #[prost(uint32, tag = "1")]
+    pub id: u32,
+    #[prost(string, tag = "2")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "3")]
+    pub created_at: ::core::option::Option<::prost_types::Timestamp>,
+    #[prost(message, optional, tag = "4")]
+    pub updated_at: ::core::option::Option<::prost_types::Timestamp>,
+    /// User the todos of the project belong to.
+    #[prost(string, tag = "5")]
+    pub owner: ::prost::alloc::string::String,
+    /// Role of the caller in the project.
+    #[prost(enumeration = "ProjectRole", tag = "6")]
+    pub role: i32,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CreateProjectRequest {
+    #[prost(string, tag = "1")]
+    pub name: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetProjectsRequest {}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetProjectsResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub projects: ::prost::alloc::vec::Vec<Project>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UpdateProjectRequest {
+    #[prost(uint32, tag = "1")]
+    pub id: u32,
+    #[prost(string, tag = "2")]
+    pub name: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DeleteProjectRequest {
+    #[prost(uint32, tag = "1")]
+    pub id: u32,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DeleteProjectResponse {}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ProjectMember {
+    #[prost(string, tag = "1")]
+    pub username: ::prost::alloc::string::String,
+    #[prost(enumeration = "ProjectRole", tag = "2")]
+    pub role: i32,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetMembersRequest {
+    #[prost(uint32, tag = "1")]
+    pub project_id: u32,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetMembersResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub members: ::prost::alloc::vec::Vec<ProjectMember>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct InviteMemberRequest {
+    #[prost(uint32, tag = "1")]
+    pub project_id: u32,
+    #[prost(string, tag = "2")]
+    pub username: ::prost::alloc::string::String,
+    #[prost(enumeration = "ProjectRole", tag = "3")]
+    pub role: i32,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Invitation {
+    #[prost(uint32, tag = "1")]
+    pub id: u32,
+    #[prost(uint32, tag = "2")]
+    pub project_id: u32,
+    #[prost(string, tag = "3")]
+    pub project_name: ::prost::alloc::string::String,
+    #[prost(string, tag = "4")]
+    pub invited_by: ::prost::alloc::string::String,
+    #[prost(enumeration = "ProjectRole", tag = "5")]
+    pub role: i32,
+    #[prost(message, optional, tag = "6")]
+    pub created_at: ::core::option::Option<::prost_types::Timestamp>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetInvitationsRequest {}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetInvitationsResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub invitations: ::prost::alloc::vec::Vec<Invitation>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AcceptInvitationRequest {
+    #[prost(uint32, tag = "1")]
+    pub id: u32,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DeclineInvitationRequest {
+    #[prost(uint32, tag = "1")]
+    pub id: u32,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DeclineInvitationResponse {}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SetMemberRoleRequest {
+    #[prost(uint32, tag = "1")]
+    pub project_id: u32,
+    #[prost(string, tag = "2")]
+    pub username: ::prost::alloc::string::String,
+    #[prost(enumeration = "ProjectRole", tag = "3")]
+    pub role: i32,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RemoveMemberRequest {
+    #[prost(uint32, tag = "1")]
+    pub project_id: u32,
+    #[prost(string, tag = "2")]
+    pub username: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RemoveMemberResponse {}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MoveTodoRequest {
+    #[prost(uint32, tag = "1")]
+    pub id: u32,
+    /// Destination project, or the inbox when 0.
+    #[prost(uint32, tag = "2")]
+    pub project_id: u32,
+    /// Index of the todo within the destination once moved. Indexes past the
+    /// end put it last.
+    #[prost(uint32, tag = "3")]
+    pub position: u32,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SetParentRequest {
+    #[prost(uint32, tag = "1")]
+    pub id: u32,
+    /// New parent of the todo, or 0 to make it a top-level todo.
+    #[prost(uint32, tag = "2")]
+    pub parent_id: u32,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct LinkTodosRequest {
+    #[prost(uint32, tag = "1")]
+    pub id: u32,
+    /// Todo that `id` is blocked by.
+    #[prost(uint32, tag = "2")]
+    pub blocked_by_id: u32,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetTodoTreeRequest {
+    #[prost(uint32, tag = "1")]
+    pub id: u32,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TodoTree {
+    #[prost(message, optional, tag = "1")]
+    pub todo: ::core::option::Option<TodoItem>,
+    /// Ordered by position.
+    #[prost(message, repeated, tag = "2")]
+    pub subtasks: ::prost::alloc::vec::Vec<TodoTree>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct WatchTodosRequest {
+    /// Resume token of the last event received before reconnecting. Events after
+    /// it are replayed first; when empty only new events are sent.
+    #[prost(string, tag = "1")]
+    pub resume_token: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct StreamRemindersRequest {}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Reminder {
+    #[prost(message, optional, tag = "1")]
+    pub todo: ::core::option::Option<TodoItem>,
+    #[prost(message, optional, tag = "2")]
+    pub reminder_at: ::core::option::Option<::prost_types::Timestamp>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TodoEvent {
+    #[prost(string, tag = "1")]
+    pub resume_token: ::prost::alloc::string::String,
+    #[prost(enumeration = "TodoEventKind", tag = "2")]
+    pub kind: i32,
+    /// Only `id` is set for deleted todos.
+    #[prost(message, optional, tag = "3")]
+    pub todo: ::core::option::Option<TodoItem>,
+}
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
 #[repr(i32)]
 pub enum TodoStatus {
     Active = 0,
     Completed = 1,
+    InProgress = 2,
+    Blocked = 3,
+    Cancelled = 4,
+    Archived = 5,
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum TodoPriority {
+    None = 0,
+    Low = 1,
+    Medium = 2,
+    High = 3,
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum TagMatch {
+    /// Todos with at least one of the tags.
+    Any = 0,
+    /// Todos with every one of the tags.
+    All = 1,
+}
+/// Roles of the members of a shared project. Viewers can read its todos,
+/// editors can also change them, and owners can also manage the project and
+/// its members.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum ProjectRole {
+    Viewer = 0,
+    Editor = 1,
+    Owner = 2,
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum TodoEventKind {
+    Created = 0,
+    Updated = 1,
+    Deleted = 2,
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum TodoSortField {
+    Id = 0,
+    CreatedAt = 1,
+    UpdatedAt = 2,
+    /// Todos without a due date come last in ascending order.
+    DueAt = 3,
+    Priority = 4,
+    /// Order of the todos within their project, see MoveTodo.
+    Position = 5,
 }
 #[doc = r" Generated client implementations."]
 pub mod todo_client {
     #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
     use tonic::codegen::*;
+    #[doc = " Todos of shared projects are visible to every member of the project and can"]
+    #[doc = " be changed by its editors and owners. Calls on todos the caller cannot see"]
+    #[doc = " fail with NOT_FOUND, calls their role does not allow with"]
+    #[doc = " PERMISSION_DENIED."]
     #[derive(Debug, Clone)]
     pub struct TodoClient<T> {
         inner: tonic::client::Grpc<T>,
@@ -75,7 +603,7 @@ pub mod todo_client {
         pub async fn get_todos(
             &mut self,
             request: impl tonic::IntoRequest<super::GetTodoRequest>,
-        ) -> Result<tonic::Response<tonic::codec::Streaming<super::TodoItem>>, tonic::Status>
+        ) -> Result<tonic::Response<tonic::codec::Streaming<super::GetTodosResponse>>, tonic::Status>
         {
             self.inner.ready().await.map_err(|e| {
                 tonic::Status::new(
@@ -89,79 +617,1198 @@ pub mod todo_client {
                 .server_streaming(request.into_request(), path, codec)
                 .await
         }
-    }
-}
-#[doc = r" Generated server implementations."]
-pub mod todo_server {
-    #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
-    use tonic::codegen::*;
-    #[doc = "Generated trait containing gRPC methods that should be implemented for use with TodoServer."]
-    #[async_trait]
-    pub trait Todo: Send + Sync + 'static {
-        #[doc = "Server streaming response type for the GetTodos method."]
-        type GetTodosStream: futures_core::Stream<Item = Result<super::TodoItem, tonic::Status>>
-            + Send
-            + Sync
-            + 'static;
-        async fn get_todos(
-            &self,
-            request: tonic::Request<super::GetTodoRequest>,
-        ) -> Result<tonic::Response<Self::GetTodosStream>, tonic::Status>;
-    }
-    #[derive(Debug)]
-    pub struct TodoServer<T: Todo> {
-        inner: _Inner<T>,
-        accept_compression_encodings: (),
-        send_compression_encodings: (),
-    }
-    struct _Inner<T>(Arc<T>);
-    impl<T: Todo> TodoServer<T> {
-        pub fn new(inner: T) -> Self {
-            let inner = Arc::new(inner);
-            let inner = _Inner(inner);
-            Self {
-                inner,
-                accept_compression_encodings: Default::default(),
-                send_compression_encodings: Default::default(),
-            }
+        pub async fn get_todo(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetTodoByIdRequest>,
+        ) -> Result<tonic::Response<super::TodoItem>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/todo.Todo/GetTodo");
+            self.inner.unary(request.into_request(), path, codec).await
         }
-        pub fn with_interceptor<F>(inner: T, interceptor: F) -> InterceptedService<Self, F>
-        where
-            F: tonic::service::Interceptor,
-        {
-            InterceptedService::new(Self::new(inner), interceptor)
+        pub async fn create_todo(
+            &mut self,
+            request: impl tonic::IntoRequest<super::CreateTodoRequest>,
+        ) -> Result<tonic::Response<super::TodoItem>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/todo.Todo/CreateTodo");
+            self.inner.unary(request.into_request(), path, codec).await
         }
-    }
-    impl<T, B> tonic::codegen::Service<http::Request<B>> for TodoServer<T>
-    where
-        T: Todo,
-        B: Body + Send + Sync + 'static,
-        B::Error: Into<StdError> + Send + 'static,
-    {
-        type Response = http::Response<tonic::body::BoxBody>;
-        type Error = Never;
-        type Future = BoxFuture<Self::Response, Self::Error>;
-        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
-            Poll::Ready(Ok(()))
+        pub async fn update_todo(
+            &mut self,
+            request: impl tonic::IntoRequest<super::UpdateTodoRequest>,
+        ) -> Result<tonic::Response<super::TodoItem>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/todo.Todo/UpdateTodo");
+            self.inner.unary(request.into_request(), path, codec).await
         }
-        fn call(&mut self, req: http::Request<B>) -> Self::Future {
-            let inner = self.inner.clone();
-            match req.uri().path() {
-                "/todo.Todo/GetTodos" => {
-                    #[allow(non_camel_case_types)]
-                    struct GetTodosSvc<T: Todo>(pub Arc<T>);
-                    impl<T: Todo> tonic::server::ServerStreamingService<super::GetTodoRequest> for GetTodosSvc<T> {
-                        type Response = super::TodoItem;
-                        type ResponseStream = T::GetTodosStream;
-                        type Future =
-                            BoxFuture<tonic::Response<Self::ResponseStream>, tonic::Status>;
-                        fn call(
-                            &mut self,
-                            request: tonic::Request<super::GetTodoRequest>,
-                        ) -> Self::Future {
-                            let inner = self.0.clone();
-                            let fut = async move { (*inner).get_todos(request).await };
-                            Box::pin(fut)
+        pub async fn delete_todo(
+            &mut self,
+            request: impl tonic::IntoRequest<super::DeleteTodoRequest>,
+        ) -> Result<tonic::Response<super::DeleteTodoResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/todo.Todo/DeleteTodo");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        #[doc = " Moves a todo to another status. Fails with FAILED_PRECONDITION when the"]
+        #[doc = " lifecycle does not allow going from the current status to the new one."]
+        pub async fn transition_todo(
+            &mut self,
+            request: impl tonic::IntoRequest<super::TransitionTodoRequest>,
+        ) -> Result<tonic::Response<super::TransitionTodoResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/todo.Todo/TransitionTodo");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        pub async fn add_tags(
+            &mut self,
+            request: impl tonic::IntoRequest<super::TagTodoRequest>,
+        ) -> Result<tonic::Response<super::TodoItem>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/todo.Todo/AddTags");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        pub async fn remove_tags(
+            &mut self,
+            request: impl tonic::IntoRequest<super::TagTodoRequest>,
+        ) -> Result<tonic::Response<super::TodoItem>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/todo.Todo/RemoveTags");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        #[doc = " Tags of the caller that are on at least one todo."]
+        pub async fn list_tags(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ListTagsRequest>,
+        ) -> Result<tonic::Response<super::ListTagsResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/todo.Todo/ListTags");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        pub async fn create_project(
+            &mut self,
+            request: impl tonic::IntoRequest<super::CreateProjectRequest>,
+        ) -> Result<tonic::Response<super::Project>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/todo.Todo/CreateProject");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        pub async fn get_projects(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetProjectsRequest>,
+        ) -> Result<tonic::Response<super::GetProjectsResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/todo.Todo/GetProjects");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        pub async fn update_project(
+            &mut self,
+            request: impl tonic::IntoRequest<super::UpdateProjectRequest>,
+        ) -> Result<tonic::Response<super::Project>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/todo.Todo/UpdateProject");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        #[doc = " The todos of a deleted project are moved to the inbox."]
+        pub async fn delete_project(
+            &mut self,
+            request: impl tonic::IntoRequest<super::DeleteProjectRequest>,
+        ) -> Result<tonic::Response<super::DeleteProjectResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/todo.Todo/DeleteProject");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        #[doc = " Moves a todo to a project, or within its project. Todos can only move"]
+        #[doc = " between projects of the same owner, and only the owner can move them"]
+        #[doc = " to the inbox."]
+        pub async fn move_todo(
+            &mut self,
+            request: impl tonic::IntoRequest<super::MoveTodoRequest>,
+        ) -> Result<tonic::Response<super::TodoItem>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/todo.Todo/MoveTodo");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        pub async fn get_members(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetMembersRequest>,
+        ) -> Result<tonic::Response<super::GetMembersResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/todo.Todo/GetMembers");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        #[doc = " Invites a user to a project. Only owners can invite."]
+        pub async fn invite_member(
+            &mut self,
+            request: impl tonic::IntoRequest<super::InviteMemberRequest>,
+        ) -> Result<tonic::Response<super::Invitation>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/todo.Todo/InviteMember");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        #[doc = " Pending invitations of the caller."]
+        pub async fn get_invitations(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetInvitationsRequest>,
+        ) -> Result<tonic::Response<super::GetInvitationsResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/todo.Todo/GetInvitations");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        pub async fn accept_invitation(
+            &mut self,
+            request: impl tonic::IntoRequest<super::AcceptInvitationRequest>,
+        ) -> Result<tonic::Response<super::Project>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/todo.Todo/AcceptInvitation");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        pub async fn decline_invitation(
+            &mut self,
+            request: impl tonic::IntoRequest<super::DeclineInvitationRequest>,
+        ) -> Result<tonic::Response<super::DeclineInvitationResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/todo.Todo/DeclineInvitation");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        pub async fn set_member_role(
+            &mut self,
+            request: impl tonic::IntoRequest<super::SetMemberRoleRequest>,
+        ) -> Result<tonic::Response<super::ProjectMember>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/todo.Todo/SetMemberRole");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        #[doc = " Owners can remove any member but the project owner, and every member"]
+        #[doc = " can remove themselves."]
+        pub async fn remove_member(
+            &mut self,
+            request: impl tonic::IntoRequest<super::RemoveMemberRequest>,
+        ) -> Result<tonic::Response<super::RemoveMemberResponse>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/todo.Todo/RemoveMember");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        #[doc = " Makes a todo a subtask of another. A parent is completed once all of"]
+        #[doc = " its subtasks are done, and reopened when one of them is reopened."]
+        #[doc = " Fails with FAILED_PRECONDITION when the todo would become its own"]
+        #[doc = " ancestor."]
+        pub async fn set_parent(
+            &mut self,
+            request: impl tonic::IntoRequest<super::SetParentRequest>,
+        ) -> Result<tonic::Response<super::TodoItem>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/todo.Todo/SetParent");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        #[doc = " Records that a todo is blocked by another. Fails with"]
+        #[doc = " FAILED_PRECONDITION when the dependencies would form a cycle."]
+        pub async fn link_todos(
+            &mut self,
+            request: impl tonic::IntoRequest<super::LinkTodosRequest>,
+        ) -> Result<tonic::Response<super::TodoItem>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/todo.Todo/LinkTodos");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        pub async fn unlink_todos(
+            &mut self,
+            request: impl tonic::IntoRequest<super::LinkTodosRequest>,
+        ) -> Result<tonic::Response<super::TodoItem>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/todo.Todo/UnlinkTodos");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        #[doc = " A todo with its subtasks, nested."]
+        pub async fn get_todo_tree(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetTodoTreeRequest>,
+        ) -> Result<tonic::Response<super::TodoTree>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/todo.Todo/GetTodoTree");
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        pub async fn watch_todos(
+            &mut self,
+            request: impl tonic::IntoRequest<super::WatchTodosRequest>,
+        ) -> Result<tonic::Response<tonic::codec::Streaming<super::TodoEvent>>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/todo.Todo/WatchTodos");
+            self.inner
+                .server_streaming(request.into_request(), path, codec)
+                .await
+        }
+        #[doc = " Reminders of the todos the user can see, as they become due. Each"]
+        #[doc = " reminder is sent once, to the streams open at that time; reminders that"]
+        #[doc = " came due while no stream or webhook received them, or while the server"]
+        #[doc = " was down, are sent as soon as one does. A stream"]
+        #[doc = " falling behind is ended with WATCH_LAGGED."]
+        pub async fn stream_reminders(
+            &mut self,
+            request: impl tonic::IntoRequest<super::StreamRemindersRequest>,
+        ) -> Result<tonic::Response<tonic::codec::Streaming<super::Reminder>>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/todo.Todo/StreamReminders");
+            self.inner
+                .server_streaming(request.into_request(), path, codec)
+                .await
+        }
+        #[doc = " Ids, projects, tags and `updated_at` of the imported todos are ignored,"]
+        #[doc = " the other timestamps are kept when set. Imported todos go to the inbox."]
+        pub async fn import_todos(
+            &mut self,
+            request: impl tonic::IntoStreamingRequest<Message = super::TodoItem>,
+        ) -> Result<tonic::Response<super::ImportSummary>, tonic::Status> {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/todo.Todo/ImportTodos");
+            self.inner
+                .client_streaming(request.into_streaming_request(), path, codec)
+                .await
+        }
+        #[doc = " Every change is acknowledged, and changes to the caller's todos are sent"]
+        #[doc = " as events, including those made through this stream. The first change"]
+        #[doc = " may carry the resume token of the last event seen."]
+        pub async fn sync_todos(
+            &mut self,
+            request: impl tonic::IntoStreamingRequest<Message = super::SyncChange>,
+        ) -> Result<tonic::Response<tonic::codec::Streaming<super::SyncResponse>>, tonic::Status>
+        {
+            self.inner.ready().await.map_err(|e| {
+                tonic::Status::new(
+                    tonic::Code::Unknown,
+                    format!("Service was not ready: {}", e.into()),
+                )
+            })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/todo.Todo/SyncTodos");
+            self.inner
+                .streaming(request.into_streaming_request(), path, codec)
+                .await
+        }
+    }
+}
+#[doc = r" Generated server implementations."]
+pub mod todo_server {
+    #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
+    use tonic::codegen::*;
+    #[doc = "Generated trait containing gRPC methods that should be implemented for use with TodoServer."]
+    #[async_trait]
+    pub trait Todo: Send + Sync + 'static {
+        #[doc = "Server streaming response type for the GetTodos method."]
+        type GetTodosStream: futures_core::Stream<Item = Result<super::GetTodosResponse, tonic::Status>>
+            + Send
+            + Sync
+            + 'static;
+        async fn get_todos(
+            &self,
+            request: tonic::Request<super::GetTodoRequest>,
+        ) -> Result<tonic::Response<Self::GetTodosStream>, tonic::Status>;
+        async fn get_todo(
+            &self,
+            request: tonic::Request<super::GetTodoByIdRequest>,
+        ) -> Result<tonic::Response<super::TodoItem>, tonic::Status>;
+        async fn create_todo(
+            &self,
+            request: tonic::Request<super::CreateTodoRequest>,
+        ) -> Result<tonic::Response<super::TodoItem>, tonic::Status>;
+        async fn update_todo(
+            &self,
+            request: tonic::Request<super::UpdateTodoRequest>,
+        ) -> Result<tonic::Response<super::TodoItem>, tonic::Status>;
+        async fn delete_todo(
+            &self,
+            request: tonic::Request<super::DeleteTodoRequest>,
+        ) -> Result<tonic::Response<super::DeleteTodoResponse>, tonic::Status>;
+        #[doc = " Moves a todo to another status. Fails with FAILED_PRECONDITION when the"]
+        #[doc = " lifecycle does not allow going from the current status to the new one."]
+        async fn transition_todo(
+            &self,
+            request: tonic::Request<super::TransitionTodoRequest>,
+        ) -> Result<tonic::Response<super::TransitionTodoResponse>, tonic::Status>;
+        async fn add_tags(
+            &self,
+            request: tonic::Request<super::TagTodoRequest>,
+        ) -> Result<tonic::Response<super::TodoItem>, tonic::Status>;
+        async fn remove_tags(
+            &self,
+            request: tonic::Request<super::TagTodoRequest>,
+        ) -> Result<tonic::Response<super::TodoItem>, tonic::Status>;
+        #[doc = " Tags of the caller that are on at least one todo."]
+        async fn list_tags(
+            &self,
+            request: tonic::Request<super::ListTagsRequest>,
+        ) -> Result<tonic::Response<super::ListTagsResponse>, tonic::Status>;
+        async fn create_project(
+            &self,
+            request: tonic::Request<super::CreateProjectRequest>,
+        ) -> Result<tonic::Response<super::Project>, tonic::Status>;
+        async fn get_projects(
+            &self,
+            request: tonic::Request<super::GetProjectsRequest>,
+        ) -> Result<tonic::Response<super::GetProjectsResponse>, tonic::Status>;
+        async fn update_project(
+            &self,
+            request: tonic::Request<super::UpdateProjectRequest>,
+        ) -> Result<tonic::Response<super::Project>, tonic::Status>;
+        #[doc = " The todos of a deleted project are moved to the inbox."]
+        async fn delete_project(
+            &self,
+            request: tonic::Request<super::DeleteProjectRequest>,
+        ) -> Result<tonic::Response<super::DeleteProjectResponse>, tonic::Status>;
+        #[doc = " Moves a todo to a project, or within its project. Todos can only move"]
+        #[doc = " between projects of the same owner, and only the owner can move them"]
+        #[doc = " to the inbox."]
+        async fn move_todo(
+            &self,
+            request: tonic::Request<super::MoveTodoRequest>,
+        ) -> Result<tonic::Response<super::TodoItem>, tonic::Status>;
+        async fn get_members(
+            &self,
+            request: tonic::Request<super::GetMembersRequest>,
+        ) -> Result<tonic::Response<super::GetMembersResponse>, tonic::Status>;
+        #[doc = " Invites a user to a project. Only owners can invite."]
+        async fn invite_member(
+            &self,
+            request: tonic::Request<super::InviteMemberRequest>,
+        ) -> Result<tonic::Response<super::Invitation>, tonic::Status>;
+        #[doc = " Pending invitations of the caller."]
+        async fn get_invitations(
+            &self,
+            request: tonic::Request<super::GetInvitationsRequest>,
+        ) -> Result<tonic::Response<super::GetInvitationsResponse>, tonic::Status>;
+        async fn accept_invitation(
+            &self,
+            request: tonic::Request<super::AcceptInvitationRequest>,
+        ) -> Result<tonic::Response<super::Project>, tonic::Status>;
+        async fn decline_invitation(
+            &self,
+            request: tonic::Request<super::DeclineInvitationRequest>,
+        ) -> Result<tonic::Response<super::DeclineInvitationResponse>, tonic::Status>;
+        async fn set_member_role(
+            &self,
+            request: tonic::Request<super::SetMemberRoleRequest>,
+        ) -> Result<tonic::Response<super::ProjectMember>, tonic::Status>;
+        #[doc = " Owners can remove any member but the project owner, and every member"]
+        #[doc = " can remove themselves."]
+        async fn remove_member(
+            &self,
+            request: tonic::Request<super::RemoveMemberRequest>,
+        ) -> Result<tonic::Response<super::RemoveMemberResponse>, tonic::Status>;
+        #[doc = " Makes a todo a subtask of another. A parent is completed once all of"]
+        #[doc = " its subtasks are done, and reopened when one of them is reopened."]
+        #[doc = " Fails with FAILED_PRECONDITION when the todo would become its own"]
+        #[doc = " ancestor."]
+        async fn set_parent(
+            &self,
+            request: tonic::Request<super::SetParentRequest>,
+        ) -> Result<tonic::Response<super::TodoItem>, tonic::Status>;
+        #[doc = " Records that a todo is blocked by another. Fails with"]
+        #[doc = " FAILED_PRECONDITION when the dependencies would form a cycle."]
+        async fn link_todos(
+            &self,
+            request: tonic::Request<super::LinkTodosRequest>,
+        ) -> Result<tonic::Response<super::TodoItem>, tonic::Status>;
+        async fn unlink_todos(
+            &self,
+            request: tonic::Request<super::LinkTodosRequest>,
+        ) -> Result<tonic::Response<super::TodoItem>, tonic::Status>;
+        #[doc = " A todo with its subtasks, nested."]
+        async fn get_todo_tree(
+            &self,
+            request: tonic::Request<super::GetTodoTreeRequest>,
+        ) -> Result<tonic::Response<super::TodoTree>, tonic::Status>;
+        #[doc = "Server streaming response type for the WatchTodos method."]
+        type WatchTodosStream: futures_core::Stream<Item = Result<super::TodoEvent, tonic::Status>>
+            + Send
+            + Sync
+            + 'static;
+        async fn watch_todos(
+            &self,
+            request: tonic::Request<super::WatchTodosRequest>,
+        ) -> Result<tonic::Response<Self::WatchTodosStream>, tonic::Status>;
+        #[doc = "Server streaming response type for the StreamReminders method."]
+        type StreamRemindersStream: futures_core::Stream<Item = Result<super::Reminder, tonic::Status>>
+            + Send
+            + Sync
+            + 'static;
+        #[doc = " Reminders of the todos the user can see, as they become due. Each"]
+        #[doc = " reminder is sent once, to the streams open at that time; reminders that"]
+        #[doc = " came due while no stream or webhook received them, or while the server"]
+        #[doc = " was down, are sent as soon as one does. A stream"]
+        #[doc = " falling behind is ended with WATCH_LAGGED."]
+        async fn stream_reminders(
+            &self,
+            request: tonic::Request<super::StreamRemindersRequest>,
+        ) -> Result<tonic::Response<Self::StreamRemindersStream>, tonic::Status>;
+        #[doc = " Ids, projects, tags and `updated_at` of the imported todos are ignored,"]
+        #[doc = " the other timestamps are kept when set. Imported todos go to the inbox."]
+        async fn import_todos(
+            &self,
+            request: tonic::Request<tonic::Streaming<super::TodoItem>>,
+        ) -> Result<tonic::Response<super::ImportSummary>, tonic::Status>;
+        #[doc = "Server streaming response type for the SyncTodos method."]
+        type SyncTodosStream: futures_core::Stream<Item = Result<super::SyncResponse, tonic::Status>>
+            + Send
+            + Sync
+            + 'static;
+        #[doc = " Every change is acknowledged, and changes to the caller's todos are sent"]
+        #[doc = " as events, including those made through this stream. The first change"]
+        #[doc = " may carry the resume token of the last event seen."]
+        async fn sync_todos(
+            &self,
+            request: tonic::Request<tonic::Streaming<super::SyncChange>>,
+        ) -> Result<tonic::Response<Self::SyncTodosStream>, tonic::Status>;
+    }
+    #[doc = " Todos of shared projects are visible to every member of the project and can"]
+    #[doc = " be changed by its editors and owners. Calls on todos the caller cannot see"]
+    #[doc = " fail with NOT_FOUND, calls their role does not allow with"]
+    #[doc = " PERMISSION_DENIED."]
+    #[derive(Debug)]
+    pub struct TodoServer<T: Todo> {
+        inner: _Inner<T>,
+        accept_compression_encodings: (),
+        send_compression_encodings: (),
+    }
+    struct _Inner<T>(Arc<T>);
+    impl<T: Todo> TodoServer<T> {
+        pub fn new(inner: T) -> Self {
+            let inner = Arc::new(inner);
+            let inner = _Inner(inner);
+            Self {
+                inner,
+                accept_compression_encodings: Default::default(),
+                send_compression_encodings: Default::default(),
+            }
+        }
+        pub fn with_interceptor<F>(inner: T, interceptor: F) -> InterceptedService<Self, F>
+        where
+            F: tonic::service::Interceptor,
+        {
+            InterceptedService::new(Self::new(inner), interceptor)
+        }
+    }
+    impl<T, B> tonic::codegen::Service<http::Request<B>> for TodoServer<T>
+    where
+        T: Todo,
+        B: Body + Send + Sync + 'static,
+        B::Error: Into<StdError> + Send + 'static,
+    {
+        type Response = http::Response<tonic::body::BoxBody>;
+        type Error = Never;
+        type Future = BoxFuture<Self::Response, Self::Error>;
+        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+        fn call(&mut self, req: http::Request<B>) -> Self::Future {
+            let inner = self.inner.clone();
+            match req.uri().path() {
+                "/todo.Todo/GetTodos" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetTodosSvc<T: Todo>(pub Arc<T>);
+                    impl<T: Todo> tonic::server::ServerStreamingService<super::GetTodoRequest> for GetTodosSvc<T> {
+                        type Response = super::GetTodosResponse;
+                        type ResponseStream = T::GetTodosStream;
+                        type Future =
+                            BoxFuture<tonic::Response<Self::ResponseStream>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetTodoRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).get_todos(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetTodosSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.server_streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/todo.Todo/GetTodo" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetTodoSvc<T: Todo>(pub Arc<T>);
+                    impl<T: Todo> tonic::server::UnaryService<super::GetTodoByIdRequest> for GetTodoSvc<T> {
+                        type Response = super::TodoItem;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetTodoByIdRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).get_todo(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetTodoSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/todo.Todo/CreateTodo" => {
+                    #[allow(non_camel_case_types)]
+                    struct CreateTodoSvc<T: Todo>(pub Arc<T>);
+                    impl<T: Todo> tonic::server::UnaryService<super::CreateTodoRequest> for CreateTodoSvc<T> {
+                        type Response = super::TodoItem;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::CreateTodoRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).create_todo(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = CreateTodoSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/todo.Todo/UpdateTodo" => {
+                    #[allow(non_camel_case_types)]
+                    struct UpdateTodoSvc<T: Todo>(pub Arc<T>);
+                    impl<T: Todo> tonic::server::UnaryService<super::UpdateTodoRequest> for UpdateTodoSvc<T> {
+                        type Response = super::TodoItem;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::UpdateTodoRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).update_todo(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = UpdateTodoSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/todo.Todo/DeleteTodo" => {
+                    #[allow(non_camel_case_types)]
+                    struct DeleteTodoSvc<T: Todo>(pub Arc<T>);
+                    impl<T: Todo> tonic::server::UnaryService<super::DeleteTodoRequest> for DeleteTodoSvc<T> {
+                        type Response = super::DeleteTodoResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::DeleteTodoRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).delete_todo(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = DeleteTodoSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/todo.Todo/TransitionTodo" => {
+                    #[allow(non_camel_case_types)]
+                    struct TransitionTodoSvc<T: Todo>(pub Arc<T>);
+                    impl<T: Todo> tonic::server::UnaryService<super::TransitionTodoRequest> for TransitionTodoSvc<T> {
+                        type Response = super::TransitionTodoResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::TransitionTodoRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).transition_todo(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = TransitionTodoSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/todo.Todo/AddTags" => {
+                    #[allow(non_camel_case_types)]
+                    struct AddTagsSvc<T: Todo>(pub Arc<T>);
+                    impl<T: Todo> tonic::server::UnaryService<super::TagTodoRequest> for AddTagsSvc<T> {
+                        type Response = super::TodoItem;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::TagTodoRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).add_tags(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = AddTagsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/todo.Todo/RemoveTags" => {
+                    #[allow(non_camel_case_types)]
+                    struct RemoveTagsSvc<T: Todo>(pub Arc<T>);
+                    impl<T: Todo> tonic::server::UnaryService<super::TagTodoRequest> for RemoveTagsSvc<T> {
+                        type Response = super::TodoItem;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::TagTodoRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).remove_tags(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = RemoveTagsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/todo.Todo/ListTags" => {
+                    #[allow(non_camel_case_types)]
+                    struct ListTagsSvc<T: Todo>(pub Arc<T>);
+                    impl<T: Todo> tonic::server::UnaryService<super::ListTagsRequest> for ListTagsSvc<T> {
+                        type Response = super::ListTagsResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ListTagsRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).list_tags(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ListTagsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/todo.Todo/CreateProject" => {
+                    #[allow(non_camel_case_types)]
+                    struct CreateProjectSvc<T: Todo>(pub Arc<T>);
+                    impl<T: Todo> tonic::server::UnaryService<super::CreateProjectRequest> for CreateProjectSvc<T> {
+                        type Response = super::Project;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::CreateProjectRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).create_project(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = CreateProjectSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/todo.Todo/GetProjects" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetProjectsSvc<T: Todo>(pub Arc<T>);
+                    impl<T: Todo> tonic::server::UnaryService<super::GetProjectsRequest> for GetProjectsSvc<T> {
+                        type Response = super::GetProjectsResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetProjectsRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).get_projects(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetProjectsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/todo.Todo/UpdateProject" => {
+                    #[allow(non_camel_case_types)]
+                    struct UpdateProjectSvc<T: Todo>(pub Arc<T>);
+                    impl<T: Todo> tonic::server::UnaryService<super::UpdateProjectRequest> for UpdateProjectSvc<T> {
+                        type Response = super::Project;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::UpdateProjectRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).update_project(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = UpdateProjectSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/todo.Todo/DeleteProject" => {
+                    #[allow(non_camel_case_types)]
+                    struct DeleteProjectSvc<T: Todo>(pub Arc<T>);
+                    impl<T: Todo> tonic::server::UnaryService<super::DeleteProjectRequest> for DeleteProjectSvc<T> {
+                        type Response = super::DeleteProjectResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::DeleteProjectRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).delete_project(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = DeleteProjectSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/todo.Todo/MoveTodo" => {
+                    #[allow(non_camel_case_types)]
+                    struct MoveTodoSvc<T: Todo>(pub Arc<T>);
+                    impl<T: Todo> tonic::server::UnaryService<super::MoveTodoRequest> for MoveTodoSvc<T> {
+                        type Response = super::TodoItem;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::MoveTodoRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).move_todo(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = MoveTodoSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/todo.Todo/GetMembers" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetMembersSvc<T: Todo>(pub Arc<T>);
+                    impl<T: Todo> tonic::server::UnaryService<super::GetMembersRequest> for GetMembersSvc<T> {
+                        type Response = super::GetMembersResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetMembersRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).get_members(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetMembersSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/todo.Todo/InviteMember" => {
+                    #[allow(non_camel_case_types)]
+                    struct InviteMemberSvc<T: Todo>(pub Arc<T>);
+                    impl<T: Todo> tonic::server::UnaryService<super::InviteMemberRequest> for InviteMemberSvc<T> {
+                        type Response = super::Invitation;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::InviteMemberRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).invite_member(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = InviteMemberSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/todo.Todo/GetInvitations" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetInvitationsSvc<T: Todo>(pub Arc<T>);
+                    impl<T: Todo> tonic::server::UnaryService<super::GetInvitationsRequest> for GetInvitationsSvc<T> {
+                        type Response = super::GetInvitationsResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetInvitationsRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).get_invitations(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetInvitationsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/todo.Todo/AcceptInvitation" => {
+                    #[allow(non_camel_case_types)]
+                    struct AcceptInvitationSvc<T: Todo>(pub Arc<T>);
+                    impl<T: Todo> tonic::server::UnaryService<super::AcceptInvitationRequest>
+                        for AcceptInvitationSvc<T>
+                    {
+                        type Response = super::Project;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::AcceptInvitationRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).accept_invitation(request).await };
+                            Box::pin(fut)
                         }
                     }
                     let accept_compression_encodings = self.accept_compression_encodings;
@@ -169,7 +1816,295 @@ pub mod todo_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = GetTodosSvc(inner);
+                        let method = AcceptInvitationSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/todo.Todo/DeclineInvitation" => {
+                    #[allow(non_camel_case_types)]
+                    struct DeclineInvitationSvc<T: Todo>(pub Arc<T>);
+                    impl<T: Todo> tonic::server::UnaryService<super::DeclineInvitationRequest>
+                        for DeclineInvitationSvc<T>
+                    {
+                        type Response = super::DeclineInvitationResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::DeclineInvitationRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).decline_invitation(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = DeclineInvitationSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/todo.Todo/SetMemberRole" => {
+                    #[allow(non_camel_case_types)]
+                    struct SetMemberRoleSvc<T: Todo>(pub Arc<T>);
+                    impl<T: Todo> tonic::server::UnaryService<super::SetMemberRoleRequest> for SetMemberRoleSvc<T> {
+                        type Response = super::ProjectMember;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::SetMemberRoleRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).set_member_role(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = SetMemberRoleSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/todo.Todo/RemoveMember" => {
+                    #[allow(non_camel_case_types)]
+                    struct RemoveMemberSvc<T: Todo>(pub Arc<T>);
+                    impl<T: Todo> tonic::server::UnaryService<super::RemoveMemberRequest> for RemoveMemberSvc<T> {
+                        type Response = super::RemoveMemberResponse;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::RemoveMemberRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).remove_member(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = RemoveMemberSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/todo.Todo/SetParent" => {
+                    #[allow(non_camel_case_types)]
+                    struct SetParentSvc<T: Todo>(pub Arc<T>);
+                    impl<T: Todo> tonic::server::UnaryService<super::SetParentRequest> for SetParentSvc<T> {
+                        type Response = super::TodoItem;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::SetParentRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).set_parent(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = SetParentSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/todo.Todo/LinkTodos" => {
+                    #[allow(non_camel_case_types)]
+                    struct LinkTodosSvc<T: Todo>(pub Arc<T>);
+                    impl<T: Todo> tonic::server::UnaryService<super::LinkTodosRequest> for LinkTodosSvc<T> {
+                        type Response = super::TodoItem;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::LinkTodosRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).link_todos(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = LinkTodosSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/todo.Todo/UnlinkTodos" => {
+                    #[allow(non_camel_case_types)]
+                    struct UnlinkTodosSvc<T: Todo>(pub Arc<T>);
+                    impl<T: Todo> tonic::server::UnaryService<super::LinkTodosRequest> for UnlinkTodosSvc<T> {
+                        type Response = super::TodoItem;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::LinkTodosRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).unlink_todos(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = UnlinkTodosSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/todo.Todo/GetTodoTree" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetTodoTreeSvc<T: Todo>(pub Arc<T>);
+                    impl<T: Todo> tonic::server::UnaryService<super::GetTodoTreeRequest> for GetTodoTreeSvc<T> {
+                        type Response = super::TodoTree;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetTodoTreeRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).get_todo_tree(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetTodoTreeSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/todo.Todo/WatchTodos" => {
+                    #[allow(non_camel_case_types)]
+                    struct WatchTodosSvc<T: Todo>(pub Arc<T>);
+                    impl<T: Todo> tonic::server::ServerStreamingService<super::WatchTodosRequest> for WatchTodosSvc<T> {
+                        type Response = super::TodoEvent;
+                        type ResponseStream = T::WatchTodosStream;
+                        type Future =
+                            BoxFuture<tonic::Response<Self::ResponseStream>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::WatchTodosRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).watch_todos(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = WatchTodosSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.server_streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/todo.Todo/StreamReminders" => {
+                    #[allow(non_camel_case_types)]
+                    struct StreamRemindersSvc<T: Todo>(pub Arc<T>);
+                    impl<T: Todo>
+                        tonic::server::ServerStreamingService<super::StreamRemindersRequest>
+                        for StreamRemindersSvc<T>
+                    {
+                        type Response = super::Reminder;
+                        type ResponseStream = T::StreamRemindersStream;
+                        type Future =
+                            BoxFuture<tonic::Response<Self::ResponseStream>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::StreamRemindersRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).stream_reminders(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = StreamRemindersSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
                             accept_compression_encodings,
@@ -180,6 +2115,70 @@ pub mod todo_server {
                     };
                     Box::pin(fut)
                 }
+                "/todo.Todo/ImportTodos" => {
+                    #[allow(non_camel_case_types)]
+                    struct ImportTodosSvc<T: Todo>(pub Arc<T>);
+                    impl<T: Todo> tonic::server::ClientStreamingService<super::TodoItem> for ImportTodosSvc<T> {
+                        type Response = super::ImportSummary;
+                        type Future = BoxFuture<tonic::Response<Self::Response>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<tonic::Streaming<super::TodoItem>>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).import_todos(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ImportTodosSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.client_streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/todo.Todo/SyncTodos" => {
+                    #[allow(non_camel_case_types)]
+                    struct SyncTodosSvc<T: Todo>(pub Arc<T>);
+                    impl<T: Todo> tonic::server::StreamingService<super::SyncChange> for SyncTodosSvc<T> {
+                        type Response = super::SyncResponse;
+                        type ResponseStream = T::SyncTodosStream;
+                        type Future =
+                            BoxFuture<tonic::Response<Self::ResponseStream>, tonic::Status>;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<tonic::Streaming<super::SyncChange>>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).sync_todos(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = SyncTodosSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec).apply_compression_config(
+                            accept_compression_encodings,
+                            send_compression_encodings,
+                        );
+                        let res = grpc.streaming(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 _ => Box::pin(async move {
                     Ok(http::Response::builder()
                         .status(200)
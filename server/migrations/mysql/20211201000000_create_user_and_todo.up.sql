@@ -0,0 +1,17 @@
+CREATE TABLE IF NOT EXISTS user (
+    id INT UNSIGNED NOT NULL AUTO_INCREMENT,
+    username VARCHAR(255) NOT NULL,
+    pin INT NOT NULL,
+    PRIMARY KEY (id),
+    UNIQUE KEY user_username_unique (username)
+);
+
+CREATE TABLE IF NOT EXISTS todo (
+    id INT UNSIGNED NOT NULL AUTO_INCREMENT,
+    description TEXT NOT NULL,
+    status INT NOT NULL DEFAULT 0,
+    userId INT UNSIGNED NOT NULL,
+    PRIMARY KEY (id),
+    KEY todo_user_id (userId),
+    CONSTRAINT todo_user_fk FOREIGN KEY (userId) REFERENCES user (id) ON DELETE CASCADE
+);
@@ -0,0 +1,6 @@
+-- Hashed PINs cannot be converted back to integers. Rather than losing the
+-- users that have one, the rollback fails while any is left: strict mode turns
+-- the conversion of a hashed PIN into an error instead of a silent 0. Reset
+-- their PINs to digits first to roll back.
+SET SESSION sql_mode = CONCAT(@@SESSION.sql_mode, ',STRICT_ALL_TABLES');
+ALTER TABLE user MODIFY pin INT NOT NULL;
@@ -0,0 +1,3 @@
+-- PINs are stored as argon2 PHC strings. Existing plaintext rows keep their
+-- digits and are rehashed on the next successful sign in.
+ALTER TABLE user MODIFY pin VARCHAR(255) NOT NULL;
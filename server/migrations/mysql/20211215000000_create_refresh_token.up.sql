@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS refresh_token (
+    id INT UNSIGNED NOT NULL AUTO_INCREMENT,
+    userId INT UNSIGNED NOT NULL,
+    token_hash CHAR(64) NOT NULL,
+    created_at BIGINT NOT NULL,
+    expires_at BIGINT NOT NULL,
+    revoked_at BIGINT NULL,
+    PRIMARY KEY (id),
+    UNIQUE KEY refresh_token_hash_unique (token_hash),
+    CONSTRAINT refresh_token_user_fk FOREIGN KEY (userId) REFERENCES user (id) ON DELETE CASCADE
+);
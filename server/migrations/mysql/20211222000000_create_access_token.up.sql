@@ -0,0 +1,13 @@
+-- Every issued access token is recorded by its `jti` so it can be revoked
+-- individually (SignOut) or together with the rest of a user's tokens
+-- (SignOutEverywhere).
+CREATE TABLE IF NOT EXISTS access_token (
+    jti CHAR(36) NOT NULL,
+    userId INT UNSIGNED NOT NULL,
+    expires_at BIGINT NOT NULL,
+    revoked_at BIGINT NULL,
+    PRIMARY KEY (jti),
+    KEY access_token_user_id (userId),
+    KEY access_token_revoked (revoked_at, expires_at),
+    CONSTRAINT access_token_user_fk FOREIGN KEY (userId) REFERENCES user (id) ON DELETE CASCADE
+);
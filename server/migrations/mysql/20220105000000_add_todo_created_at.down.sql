@@ -0,0 +1,2 @@
+DROP INDEX todo_user_created_at ON todo;
+ALTER TABLE todo DROP COLUMN created_at;
@@ -0,0 +1,5 @@
+-- Creation time of each todo, used to sort and paginate GetTodos. Existing
+-- rows are stamped with the time of the migration.
+ALTER TABLE todo ADD COLUMN created_at BIGINT NOT NULL DEFAULT 0;
+UPDATE todo SET created_at = UNIX_TIMESTAMP();
+CREATE INDEX todo_user_created_at ON todo (userId, created_at, id);
@@ -0,0 +1,10 @@
+DROP INDEX todo_user_priority ON todo;
+DROP INDEX todo_user_due_at ON todo;
+DROP INDEX todo_user_updated_at ON todo;
+ALTER TABLE todo
+    DROP COLUMN completed_at,
+    DROP COLUMN updated_at,
+    DROP COLUMN due_at,
+    DROP COLUMN priority,
+    DROP COLUMN notes,
+    DROP COLUMN title;
@@ -0,0 +1,15 @@
+-- Title, notes, priority and due date of a todo, along with the time it was
+-- last updated and the time it was completed. Completed todos are considered
+-- completed as of their last update.
+ALTER TABLE todo
+    ADD COLUMN title VARCHAR(255) NOT NULL DEFAULT '',
+    ADD COLUMN notes TEXT NOT NULL,
+    ADD COLUMN priority INT NOT NULL DEFAULT 0,
+    ADD COLUMN due_at BIGINT NULL,
+    ADD COLUMN updated_at BIGINT NOT NULL DEFAULT 0,
+    ADD COLUMN completed_at BIGINT NULL;
+UPDATE todo SET updated_at = created_at;
+UPDATE todo SET completed_at = updated_at WHERE status = 1;
+CREATE INDEX todo_user_updated_at ON todo (userId, updated_at, id);
+CREATE INDEX todo_user_due_at ON todo (userId, due_at, id);
+CREATE INDEX todo_user_priority ON todo (userId, priority, id);
@@ -0,0 +1,12 @@
+-- Every status change of a todo, with the user who made it.
+CREATE TABLE IF NOT EXISTS todo_transition (
+    id INT UNSIGNED NOT NULL AUTO_INCREMENT,
+    todoId INT UNSIGNED NOT NULL,
+    from_status INT NOT NULL,
+    to_status INT NOT NULL,
+    changed_by VARCHAR(255) NOT NULL,
+    changed_at BIGINT NOT NULL,
+    PRIMARY KEY (id),
+    KEY todo_transition_todo_id (todoId, changed_at),
+    CONSTRAINT todo_transition_todo_fk FOREIGN KEY (todoId) REFERENCES todo (id) ON DELETE CASCADE
+);
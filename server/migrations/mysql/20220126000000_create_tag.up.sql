@@ -0,0 +1,18 @@
+-- Tags are owned by a user and linked to any number of their todos.
+CREATE TABLE IF NOT EXISTS tag (
+    id INT UNSIGNED NOT NULL AUTO_INCREMENT,
+    userId INT UNSIGNED NOT NULL,
+    name VARCHAR(64) NOT NULL,
+    PRIMARY KEY (id),
+    UNIQUE KEY tag_user_name_unique (userId, name),
+    CONSTRAINT tag_user_fk FOREIGN KEY (userId) REFERENCES user (id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS todo_tag (
+    todoId INT UNSIGNED NOT NULL,
+    tagId INT UNSIGNED NOT NULL,
+    PRIMARY KEY (todoId, tagId),
+    KEY todo_tag_tag_id (tagId),
+    CONSTRAINT todo_tag_todo_fk FOREIGN KEY (todoId) REFERENCES todo (id) ON DELETE CASCADE,
+    CONSTRAINT todo_tag_tag_fk FOREIGN KEY (tagId) REFERENCES tag (id) ON DELETE CASCADE
+);
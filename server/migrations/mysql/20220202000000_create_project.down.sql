@@ -0,0 +1,6 @@
+DROP INDEX todo_user_project_position ON todo;
+ALTER TABLE todo DROP FOREIGN KEY todo_project_fk;
+ALTER TABLE todo
+    DROP COLUMN position,
+    DROP COLUMN projectId;
+DROP TABLE IF EXISTS project;
@@ -0,0 +1,19 @@
+-- Projects group the todos of a user into separate lists. Todos without a
+-- project make up the user's inbox. `position` orders the todos of a list.
+CREATE TABLE IF NOT EXISTS project (
+    id INT UNSIGNED NOT NULL AUTO_INCREMENT,
+    userId INT UNSIGNED NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    created_at BIGINT NOT NULL,
+    updated_at BIGINT NOT NULL,
+    PRIMARY KEY (id),
+    UNIQUE KEY project_user_name_unique (userId, name),
+    CONSTRAINT project_user_fk FOREIGN KEY (userId) REFERENCES user (id) ON DELETE CASCADE
+);
+
+ALTER TABLE todo
+    ADD COLUMN projectId INT UNSIGNED NULL,
+    ADD COLUMN position BIGINT NOT NULL DEFAULT 0,
+    ADD CONSTRAINT todo_project_fk FOREIGN KEY (projectId) REFERENCES project (id) ON DELETE SET NULL;
+UPDATE todo SET position = id;
+CREATE INDEX todo_user_project_position ON todo (userId, projectId, position, id);
@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS todo_dependency;
+ALTER TABLE todo DROP FOREIGN KEY todo_parent_fk;
+ALTER TABLE todo DROP COLUMN parentId;
@@ -0,0 +1,14 @@
+-- Subtasks point at their parent todo, and `todo_dependency` records which
+-- todos block which. Deleting a todo turns its subtasks into top-level todos.
+ALTER TABLE todo
+    ADD COLUMN parentId INT UNSIGNED NULL,
+    ADD CONSTRAINT todo_parent_fk FOREIGN KEY (parentId) REFERENCES todo (id) ON DELETE SET NULL;
+
+CREATE TABLE IF NOT EXISTS todo_dependency (
+    todoId INT UNSIGNED NOT NULL,
+    blockedById INT UNSIGNED NOT NULL,
+    PRIMARY KEY (todoId, blockedById),
+    KEY todo_dependency_blocked_by_id (blockedById),
+    CONSTRAINT todo_dependency_todo_fk FOREIGN KEY (todoId) REFERENCES todo (id) ON DELETE CASCADE,
+    CONSTRAINT todo_dependency_blocked_by_fk FOREIGN KEY (blockedById) REFERENCES todo (id) ON DELETE CASCADE
+);
@@ -0,0 +1,28 @@
+-- Members of shared projects, with their role: 0 viewer, 1 editor, 2 owner.
+-- The user a project belongs to is one of its owners.
+CREATE TABLE IF NOT EXISTS project_member (
+    projectId INT UNSIGNED NOT NULL,
+    userId INT UNSIGNED NOT NULL,
+    role INT NOT NULL,
+    PRIMARY KEY (projectId, userId),
+    KEY project_member_user_id (userId),
+    CONSTRAINT project_member_project_fk FOREIGN KEY (projectId) REFERENCES project (id) ON DELETE CASCADE,
+    CONSTRAINT project_member_user_fk FOREIGN KEY (userId) REFERENCES user (id) ON DELETE CASCADE
+);
+INSERT INTO project_member (projectId, userId, role) SELECT id, userId, 2 FROM project;
+
+-- Invitations waiting to be accepted or declined by `userId`.
+CREATE TABLE IF NOT EXISTS project_invitation (
+    id INT UNSIGNED NOT NULL AUTO_INCREMENT,
+    projectId INT UNSIGNED NOT NULL,
+    userId INT UNSIGNED NOT NULL,
+    role INT NOT NULL,
+    invitedById INT UNSIGNED NOT NULL,
+    created_at BIGINT NOT NULL,
+    PRIMARY KEY (id),
+    UNIQUE KEY project_invitation_unique (projectId, userId),
+    KEY project_invitation_user_id (userId),
+    CONSTRAINT project_invitation_project_fk FOREIGN KEY (projectId) REFERENCES project (id) ON DELETE CASCADE,
+    CONSTRAINT project_invitation_user_fk FOREIGN KEY (userId) REFERENCES user (id) ON DELETE CASCADE,
+    CONSTRAINT project_invitation_invited_by_fk FOREIGN KEY (invitedById) REFERENCES user (id) ON DELETE CASCADE
+);
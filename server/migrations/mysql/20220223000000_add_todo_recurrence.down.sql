@@ -0,0 +1,2 @@
+ALTER TABLE todo DROP KEY todo_recurrence_due_at;
+ALTER TABLE todo DROP COLUMN recurrence;
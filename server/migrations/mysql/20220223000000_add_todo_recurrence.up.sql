@@ -0,0 +1,5 @@
+-- RFC 5545 recurrence rule of a todo, moved to the next occurrence once the
+-- todo is completed or overdue.
+ALTER TABLE todo
+    ADD COLUMN recurrence VARCHAR(255) NULL,
+    ADD KEY todo_recurrence_due_at (recurrence, due_at);
@@ -0,0 +1,3 @@
+ALTER TABLE todo DROP KEY todo_reminder_at;
+ALTER TABLE todo DROP COLUMN delivered_reminder_at;
+ALTER TABLE todo DROP COLUMN reminder_at;
@@ -0,0 +1,7 @@
+-- `delivered_reminder_at` is the `reminder_at` a reminder was last sent for,
+-- so that a reminder is sent once even across restarts, and again when
+-- `reminder_at` changes.
+ALTER TABLE todo
+    ADD COLUMN reminder_at BIGINT NULL,
+    ADD COLUMN delivered_reminder_at BIGINT NULL,
+    ADD KEY todo_reminder_at (reminder_at);
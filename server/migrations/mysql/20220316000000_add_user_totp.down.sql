@@ -0,0 +1,6 @@
+DROP TABLE IF EXISTS sign_in_challenge;
+DROP TABLE IF EXISTS recovery_code;
+ALTER TABLE user
+    DROP COLUMN totp_last_step,
+    DROP COLUMN totp_enabled,
+    DROP COLUMN totp_secret;
@@ -0,0 +1,29 @@
+-- `totp_secret` is set on enrollment and only used for sign in once
+-- `totp_enabled`. `totp_last_step` is the time step of the last code accepted,
+-- so that a code cannot be replayed.
+ALTER TABLE user
+    ADD COLUMN totp_secret VARCHAR(64) NULL,
+    ADD COLUMN totp_enabled BOOLEAN NOT NULL DEFAULT false,
+    ADD COLUMN totp_last_step BIGINT NULL;
+
+CREATE TABLE IF NOT EXISTS recovery_code (
+    id INT UNSIGNED NOT NULL AUTO_INCREMENT,
+    userId INT UNSIGNED NOT NULL,
+    code_hash CHAR(64) NOT NULL,
+    used_at BIGINT NULL,
+    PRIMARY KEY (id),
+    UNIQUE KEY recovery_code_hash_unique (userId, code_hash),
+    CONSTRAINT recovery_code_user_fk FOREIGN KEY (userId) REFERENCES user (id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS sign_in_challenge (
+    id INT UNSIGNED NOT NULL AUTO_INCREMENT,
+    userId INT UNSIGNED NOT NULL,
+    token_hash CHAR(64) NOT NULL,
+    expires_at BIGINT NOT NULL,
+    attempts INT NOT NULL DEFAULT 0,
+    consumed_at BIGINT NULL,
+    PRIMARY KEY (id),
+    UNIQUE KEY sign_in_challenge_hash_unique (token_hash),
+    CONSTRAINT sign_in_challenge_user_fk FOREIGN KEY (userId) REFERENCES user (id) ON DELETE CASCADE
+);
@@ -0,0 +1,4 @@
+DROP TABLE IF EXISTS access_token;
+DROP TABLE IF EXISTS refresh_token;
+DROP TABLE IF EXISTS todo;
+DROP TABLE IF EXISTS user;
@@ -0,0 +1,31 @@
+CREATE TABLE IF NOT EXISTS user (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    username TEXT NOT NULL UNIQUE,
+    pin TEXT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS todo (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    description TEXT NOT NULL,
+    status INTEGER NOT NULL DEFAULT 0,
+    userId INTEGER NOT NULL REFERENCES user (id) ON DELETE CASCADE
+);
+CREATE INDEX IF NOT EXISTS todo_user_id ON todo (userId);
+
+CREATE TABLE IF NOT EXISTS refresh_token (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    userId INTEGER NOT NULL REFERENCES user (id) ON DELETE CASCADE,
+    token_hash TEXT NOT NULL UNIQUE,
+    created_at INTEGER NOT NULL,
+    expires_at INTEGER NOT NULL,
+    revoked_at INTEGER NULL
+);
+
+CREATE TABLE IF NOT EXISTS access_token (
+    jti TEXT PRIMARY KEY NOT NULL,
+    userId INTEGER NOT NULL REFERENCES user (id) ON DELETE CASCADE,
+    expires_at INTEGER NOT NULL,
+    revoked_at INTEGER NULL
+);
+CREATE INDEX IF NOT EXISTS access_token_user_id ON access_token (userId);
+CREATE INDEX IF NOT EXISTS access_token_revoked ON access_token (revoked_at, expires_at);
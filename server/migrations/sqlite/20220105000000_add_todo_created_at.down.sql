@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS todo_user_created_at;
+ALTER TABLE todo DROP COLUMN created_at;
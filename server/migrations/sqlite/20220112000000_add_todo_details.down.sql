@@ -0,0 +1,9 @@
+DROP INDEX IF EXISTS todo_user_priority;
+DROP INDEX IF EXISTS todo_user_due_at;
+DROP INDEX IF EXISTS todo_user_updated_at;
+ALTER TABLE todo DROP COLUMN completed_at;
+ALTER TABLE todo DROP COLUMN updated_at;
+ALTER TABLE todo DROP COLUMN due_at;
+ALTER TABLE todo DROP COLUMN priority;
+ALTER TABLE todo DROP COLUMN notes;
+ALTER TABLE todo DROP COLUMN title;
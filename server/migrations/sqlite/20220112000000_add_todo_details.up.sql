@@ -0,0 +1,14 @@
+-- Title, notes, priority and due date of a todo, along with the time it was
+-- last updated and the time it was completed. Completed todos are considered
+-- completed as of their last update.
+ALTER TABLE todo ADD COLUMN title TEXT NOT NULL DEFAULT '';
+ALTER TABLE todo ADD COLUMN notes TEXT NOT NULL DEFAULT '';
+ALTER TABLE todo ADD COLUMN priority INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE todo ADD COLUMN due_at INTEGER NULL;
+ALTER TABLE todo ADD COLUMN updated_at INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE todo ADD COLUMN completed_at INTEGER NULL;
+UPDATE todo SET updated_at = created_at;
+UPDATE todo SET completed_at = updated_at WHERE status = 1;
+CREATE INDEX IF NOT EXISTS todo_user_updated_at ON todo (userId, updated_at, id);
+CREATE INDEX IF NOT EXISTS todo_user_due_at ON todo (userId, due_at, id);
+CREATE INDEX IF NOT EXISTS todo_user_priority ON todo (userId, priority, id);
@@ -0,0 +1,10 @@
+-- Every status change of a todo, with the user who made it.
+CREATE TABLE IF NOT EXISTS todo_transition (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    todoId INTEGER NOT NULL REFERENCES todo (id) ON DELETE CASCADE,
+    from_status INTEGER NOT NULL,
+    to_status INTEGER NOT NULL,
+    changed_by TEXT NOT NULL,
+    changed_at INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS todo_transition_todo_id ON todo_transition (todoId, changed_at);
@@ -0,0 +1,14 @@
+-- Tags are owned by a user and linked to any number of their todos.
+CREATE TABLE IF NOT EXISTS tag (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    userId INTEGER NOT NULL REFERENCES user (id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    UNIQUE (userId, name)
+);
+
+CREATE TABLE IF NOT EXISTS todo_tag (
+    todoId INTEGER NOT NULL REFERENCES todo (id) ON DELETE CASCADE,
+    tagId INTEGER NOT NULL REFERENCES tag (id) ON DELETE CASCADE,
+    PRIMARY KEY (todoId, tagId)
+);
+CREATE INDEX IF NOT EXISTS todo_tag_tag_id ON todo_tag (tagId);
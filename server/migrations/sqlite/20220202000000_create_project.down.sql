@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS todo_user_project_position;
+ALTER TABLE todo DROP COLUMN position;
+ALTER TABLE todo DROP COLUMN projectId;
+DROP TABLE IF EXISTS project;
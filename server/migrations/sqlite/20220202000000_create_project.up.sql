@@ -0,0 +1,17 @@
+-- Projects group the todos of a user into separate lists. Todos without a
+-- project make up the user's inbox. `position` orders the todos of a list.
+CREATE TABLE IF NOT EXISTS project (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    userId INTEGER NOT NULL REFERENCES user (id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL,
+    UNIQUE (userId, name)
+);
+
+-- No foreign key, so that the column can be dropped again: deleting a project
+-- moves its todos to the inbox explicitly.
+ALTER TABLE todo ADD COLUMN projectId INTEGER NULL;
+ALTER TABLE todo ADD COLUMN position INTEGER NOT NULL DEFAULT 0;
+UPDATE todo SET position = id;
+CREATE INDEX IF NOT EXISTS todo_user_project_position ON todo (userId, projectId, position, id);
@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS todo_dependency;
+DROP INDEX IF EXISTS todo_parent_id;
+ALTER TABLE todo DROP COLUMN parentId;
@@ -0,0 +1,12 @@
+-- Subtasks point at their parent todo, and `todo_dependency` records which
+-- todos block which. Like `projectId`, `parentId` has no foreign key so that
+-- it can be dropped again; deleting a todo detaches its subtasks explicitly.
+ALTER TABLE todo ADD COLUMN parentId INTEGER NULL;
+CREATE INDEX IF NOT EXISTS todo_parent_id ON todo (parentId);
+
+CREATE TABLE IF NOT EXISTS todo_dependency (
+    todoId INTEGER NOT NULL REFERENCES todo (id) ON DELETE CASCADE,
+    blockedById INTEGER NOT NULL REFERENCES todo (id) ON DELETE CASCADE,
+    PRIMARY KEY (todoId, blockedById)
+);
+CREATE INDEX IF NOT EXISTS todo_dependency_blocked_by_id ON todo_dependency (blockedById);
@@ -0,0 +1,22 @@
+-- Members of shared projects, with their role: 0 viewer, 1 editor, 2 owner.
+-- The user a project belongs to is one of its owners.
+CREATE TABLE IF NOT EXISTS project_member (
+    projectId INTEGER NOT NULL REFERENCES project (id) ON DELETE CASCADE,
+    userId INTEGER NOT NULL REFERENCES user (id) ON DELETE CASCADE,
+    role INTEGER NOT NULL,
+    PRIMARY KEY (projectId, userId)
+);
+CREATE INDEX IF NOT EXISTS project_member_user_id ON project_member (userId);
+INSERT INTO project_member (projectId, userId, role) SELECT id, userId, 2 FROM project;
+
+-- Invitations waiting to be accepted or declined by `userId`.
+CREATE TABLE IF NOT EXISTS project_invitation (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    projectId INTEGER NOT NULL REFERENCES project (id) ON DELETE CASCADE,
+    userId INTEGER NOT NULL REFERENCES user (id) ON DELETE CASCADE,
+    role INTEGER NOT NULL,
+    invitedById INTEGER NOT NULL REFERENCES user (id) ON DELETE CASCADE,
+    created_at INTEGER NOT NULL,
+    UNIQUE (projectId, userId)
+);
+CREATE INDEX IF NOT EXISTS project_invitation_user_id ON project_invitation (userId);
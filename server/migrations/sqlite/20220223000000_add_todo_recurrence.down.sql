@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS todo_recurrence_due_at;
+ALTER TABLE todo DROP COLUMN recurrence;
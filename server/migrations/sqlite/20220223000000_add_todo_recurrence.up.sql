@@ -0,0 +1,4 @@
+-- RFC 5545 recurrence rule of a todo, moved to the next occurrence once the
+-- todo is completed or overdue.
+ALTER TABLE todo ADD COLUMN recurrence TEXT NULL;
+CREATE INDEX IF NOT EXISTS todo_recurrence_due_at ON todo (recurrence, due_at);
@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS todo_reminder_at;
+ALTER TABLE todo DROP COLUMN delivered_reminder_at;
+ALTER TABLE todo DROP COLUMN reminder_at;
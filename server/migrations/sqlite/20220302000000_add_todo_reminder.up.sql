@@ -0,0 +1,6 @@
+-- `delivered_reminder_at` is the `reminder_at` a reminder was last sent for,
+-- so that a reminder is sent once even across restarts, and again when
+-- `reminder_at` changes.
+ALTER TABLE todo ADD COLUMN reminder_at INTEGER NULL;
+ALTER TABLE todo ADD COLUMN delivered_reminder_at INTEGER NULL;
+CREATE INDEX IF NOT EXISTS todo_reminder_at ON todo (reminder_at);
@@ -0,0 +1,4 @@
+-- Passwords are stored as argon2 PHC strings. Users created before passwords
+-- have none and keep signing in with their PIN, which becomes an optional
+-- second factor: an empty `pin` means the user has no PIN.
+ALTER TABLE user ADD COLUMN password TEXT NULL;
@@ -0,0 +1,5 @@
+DROP TABLE IF EXISTS sign_in_challenge;
+DROP TABLE IF EXISTS recovery_code;
+ALTER TABLE user DROP COLUMN totp_last_step;
+ALTER TABLE user DROP COLUMN totp_enabled;
+ALTER TABLE user DROP COLUMN totp_secret;
@@ -0,0 +1,23 @@
+-- `totp_secret` is set on enrollment and only used for sign in once
+-- `totp_enabled`. `totp_last_step` is the time step of the last code accepted,
+-- so that a code cannot be replayed.
+ALTER TABLE user ADD COLUMN totp_secret TEXT NULL;
+ALTER TABLE user ADD COLUMN totp_enabled BOOLEAN NOT NULL DEFAULT false;
+ALTER TABLE user ADD COLUMN totp_last_step INTEGER NULL;
+
+CREATE TABLE IF NOT EXISTS recovery_code (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    userId INTEGER NOT NULL REFERENCES user (id) ON DELETE CASCADE,
+    code_hash TEXT NOT NULL,
+    used_at INTEGER NULL,
+    UNIQUE (userId, code_hash)
+);
+
+CREATE TABLE IF NOT EXISTS sign_in_challenge (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    userId INTEGER NOT NULL REFERENCES user (id) ON DELETE CASCADE,
+    token_hash TEXT NOT NULL UNIQUE,
+    expires_at INTEGER NOT NULL,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    consumed_at INTEGER NULL
+);
@@ -0,0 +1,26 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[cfg(test)]
+thread_local! {
+    static FROZEN_AT: std::cell::Cell<Option<i64>> =
+        const { std::cell::Cell::new(None) };
+}
+
+/// Seconds since the Unix epoch, the unit used for every timestamp column.
+pub fn now() -> i64 {
+    #[cfg(test)]
+    if let Some(now) = FROZEN_AT.with(|frozen_at| frozen_at.get()) {
+        return now;
+    }
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs() as i64)
+        .unwrap_or_default()
+}
+
+/// Makes `now` return `now` on the current thread, so that tests control the
+/// time.
+#[cfg(test)]
+pub fn freeze(now: i64) {
+    FROZEN_AT.with(|frozen_at| frozen_at.set(Some(now)));
+}
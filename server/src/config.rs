@@ -0,0 +1,11 @@
+use std::env;
+use std::str::FromStr;
+
+/// Value of the environment variable `name`, or `default` when it is unset or
+/// does not parse.
+pub fn env_or<T: FromStr>(name: &str, default: T) -> T {
+    env::var(name)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(default)
+}
@@ -1,7 +1,30 @@
 use sqlx::FromRow;
+use std::fmt;
 
-#[derive(Debug, FromRow, Clone)]
+#[derive(FromRow, Clone)]
 pub struct User {
     pub username: String,
-    pub pin: i32,
+    /// argon2 hash of the PIN, or the plaintext PIN for rows created before
+    /// hashing. Empty when the user has no PIN.
+    pub pin: String,
+    /// argon2 hash of the password, `None` for users created before passwords,
+    /// who sign in with their PIN alone.
+    pub password: Option<String>,
+    /// Base32 TOTP secret, pending until `totp_enabled`.
+    pub totp_secret: Option<String>,
+    pub totp_enabled: bool,
+}
+
+impl fmt::Debug for User {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("User")
+            .field("username", &self.username)
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Debug, FromRow, Clone)]
+pub struct RevokedTokenDb {
+    pub jti: String,
+    pub expires_at: i64,
 }
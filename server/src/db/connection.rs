@@ -1,12 +1,45 @@
-use sqlx::mysql::MySqlPoolOptions;
-use sqlx::{MySql, Pool};
+use crate::config::env_or;
+use crate::db::store::{MemoryStore, MySqlStore, SqliteStore, Store};
 use std::env;
+use std::sync::Arc;
+use std::time::Duration;
 
-pub async fn get_connection_pool() -> Result<Pool<MySql>, sqlx::Error> {
+/// Size of the connection pool, `DATABASE_MAX_CONNECTIONS` (5 by default).
+pub fn max_connections() -> u32 {
+    env_or("DATABASE_MAX_CONNECTIONS", 5)
+}
+
+/// Number of requests allowed to use the database at once,
+/// `DATABASE_MAX_CONCURRENCY` (the pool size by default).
+pub fn max_concurrency() -> usize {
+    env_or("DATABASE_MAX_CONCURRENCY", max_connections() as usize)
+}
+
+/// How long a request waits for a free slot before it is rejected,
+/// `DATABASE_ACQUIRE_TIMEOUT_MS` (1 second by default).
+pub fn acquire_timeout() -> Duration {
+    Duration::from_millis(env_or("DATABASE_ACQUIRE_TIMEOUT_MS", 1000))
+}
+
+/// Opens the backend named by the scheme of `DATABASE_URL`: `mysql://`,
+/// `sqlite:` or `memory://`.
+pub async fn get_store() -> Result<Arc<dyn Store>, sqlx::Error> {
     let database_uri = env::var("DATABASE_URL").expect("DATABASE_URL is missing");
-    let pool = MySqlPoolOptions::new()
-        .max_connections(5)
-        .connect(&database_uri)
-        .await;
-    return pool;
+    let scheme = database_uri.split(':').next().unwrap_or_default();
+    match scheme {
+        "mysql" => Ok(Arc::new(MySqlStore::connect(&database_uri).await?)),
+        "sqlite" => Ok(Arc::new(SqliteStore::connect(&database_uri).await?)),
+        "memory" => Ok(Arc::new(MemoryStore::default())),
+        _ => Err(sqlx::Error::Configuration(
+            format!("Unsupported DATABASE_URL scheme '{}'", scheme).into(),
+        )),
+    }
+}
+
+/// Migrations run on startup unless `SKIP_MIGRATIONS` is set to `true` or `1`.
+pub fn migrations_enabled() -> bool {
+    match env::var("SKIP_MIGRATIONS") {
+        Ok(value) => !matches!(value.as_str(), "true" | "1"),
+        Err(_) => true,
+    }
 }
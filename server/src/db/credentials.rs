@@ -0,0 +1,119 @@
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use argon2::Argon2;
+use rand_core::OsRng;
+use sha2::{Digest, Sha256};
+use tracing::log::error;
+
+/// Outcome of checking a PIN against the value stored in the `user` table.
+#[derive(Debug, PartialEq, Eq)]
+pub enum PinMatch {
+    /// The PIN matches an argon2 hash.
+    Hashed,
+    /// The PIN matches a legacy plaintext value that should be rehashed.
+    Legacy,
+    Mismatch,
+}
+
+fn is_hashed(stored: &str) -> bool {
+    stored.starts_with('$')
+}
+
+/// Hashes `secret` with argon2 and a random salt, returning the PHC string.
+///
+/// Hashing is deliberately slow, so it runs on the blocking thread pool.
+async fn hash_secret(secret: String, name: &str) -> Result<String, String> {
+    let hashed = tokio::task::spawn_blocking(move || {
+        let salt = SaltString::generate(&mut OsRng);
+        Argon2::default()
+            .hash_password(secret.as_bytes(), &salt)
+            .map(|hash| hash.to_string())
+    })
+    .await;
+    match hashed {
+        Ok(Ok(hash)) => Ok(hash),
+        Ok(Err(e)) => {
+            error!("Unable to hash {} {:?}", name, e);
+            Err(format!("Error while hashing {}", name))
+        }
+        Err(e) => {
+            error!("{} hashing task failed {:?}", name, e);
+            Err(format!("Error while hashing {}", name))
+        }
+    }
+}
+
+/// Checks `secret` against the argon2 hash `stored`, on the blocking thread
+/// pool as well.
+async fn verify_secret(stored: String, secret: String, name: &str) -> bool {
+    let verified = tokio::task::spawn_blocking(move || match PasswordHash::new(&stored) {
+        Ok(hash) => Argon2::default()
+            .verify_password(secret.as_bytes(), &hash)
+            .is_ok(),
+        Err(e) => {
+            error!("Stored hash is not valid {:?}", e);
+            false
+        }
+    })
+    .await;
+    match verified {
+        Ok(verified) => verified,
+        Err(e) => {
+            error!("{} verification task failed {:?}", name, e);
+            false
+        }
+    }
+}
+
+/// Hash of a random secret, that credentials of unknown users are checked
+/// against so that they take as long to reject as wrong credentials. Hashing
+/// blocks the caller, so this is meant to be called once on startup.
+pub fn decoy_hash() -> Result<String, String> {
+    let salt = SaltString::generate(&mut OsRng);
+    let secret = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(secret.as_str().as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| format!("Unable to hash decoy secret {:?}", e))
+}
+
+pub async fn hash_pin(pin: String) -> Result<String, String> {
+    hash_secret(pin, "PIN").await
+}
+
+pub async fn hash_password(password: String) -> Result<String, String> {
+    hash_secret(password, "password").await
+}
+
+/// Whether `a` and `b` are equal, in a time that does not depend on where
+/// they differ: their digests have the same length and every byte is compared.
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    Sha256::digest(a.as_bytes())
+        .iter()
+        .zip(Sha256::digest(b.as_bytes()).iter())
+        .fold(0, |difference, (a, b)| difference | (a ^ b))
+        == 0
+}
+
+/// Checks `pin` against `stored`, which is either an argon2 hash or a legacy plaintext PIN.
+/// Legacy PINs are checked after verifying `pin` against `decoy_hash`, so that they take
+/// as long as hashed ones.
+pub async fn verify_pin(stored: String, pin: String, decoy_hash: String) -> PinMatch {
+    if !is_hashed(&stored) {
+        verify_secret(decoy_hash, pin.clone(), "PIN").await;
+        return if constant_time_eq(&stored, &pin) {
+            PinMatch::Legacy
+        } else {
+            PinMatch::Mismatch
+        };
+    }
+    if verify_secret(stored, pin, "PIN").await {
+        PinMatch::Hashed
+    } else {
+        PinMatch::Mismatch
+    }
+}
+
+/// Checks `password` against the argon2 hash `stored`.
+pub async fn verify_password(stored: String, password: String) -> bool {
+    verify_secret(stored, password, "Password").await
+}
@@ -0,0 +1,79 @@
+//! Fixtures shared by the unit tests of the database layer.
+
+use crate::db::models::ProjectDb;
+use crate::db::repository::{Repository, RepositoryHandle};
+use crate::db::store::MemoryStore;
+use crate::events::TodoEvents;
+use proto::service::auth::SignUpRequest;
+use proto::service::todo::{CreateTodoRequest, ProjectRole, TodoItem};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// A repository backed by an empty in-memory store.
+pub fn repository() -> Repository {
+    Repository::new(
+        Arc::new(MemoryStore::default()),
+        TodoEvents::new(16),
+        4,
+        Duration::from_secs(1),
+    )
+    .unwrap()
+}
+
+pub async fn sign_up(conn: &RepositoryHandle, usernames: &[&str]) {
+    for username in usernames {
+        conn.sign_up(SignUpRequest {
+            username: username.to_string(),
+            pin: 1234,
+            password: "correct horse battery staple".to_string(),
+        })
+        .await
+        .unwrap();
+    }
+}
+
+/// Creates a todo of `username` in `project_id`, or in the inbox when 0.
+pub async fn create_todo(
+    conn: &RepositoryHandle,
+    username: &str,
+    title: &str,
+    project_id: u32,
+) -> TodoItem {
+    conn.create_todo(
+        username.to_string(),
+        CreateTodoRequest {
+            title: title.to_string(),
+            project_id,
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap()
+}
+
+/// Creates a project of `owner` that `member` joins with `role`.
+pub async fn share_project(
+    conn: &RepositoryHandle,
+    owner: &str,
+    member: &str,
+    role: ProjectRole,
+) -> ProjectDb {
+    let project = conn
+        .create_project(owner.to_string(), "Shared".to_string())
+        .await
+        .unwrap();
+    let invitation = conn
+        .invite_member(
+            owner.to_string(),
+            project.id,
+            member.to_string(),
+            role as i32,
+        )
+        .await
+        .unwrap();
+    conn.accept_invitation(member.to_string(), invitation.id)
+        .await
+        .unwrap()
+        .unwrap();
+    project
+}
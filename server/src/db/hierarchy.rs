@@ -0,0 +1,195 @@
+use crate::error::ServiceError;
+use proto::service::todo::{TodoItem, TodoTree};
+use std::collections::{HashMap, HashSet};
+
+/// Checks that `id` may become a subtask of `parent_id`, given the
+/// `(id, parent_id)` links of the user's subtasks.
+pub fn check_parent(id: u32, parent_id: u32, parents: &[(u32, u32)]) -> Result<(), ServiceError> {
+    let parents: HashMap<u32, u32> = parents.iter().copied().collect();
+    let mut seen = HashSet::new();
+    let mut ancestor = Some(parent_id);
+    while let Some(todo_id) = ancestor {
+        if todo_id == id {
+            return Err(ServiceError::Cycle {
+                relation: "subtasks",
+                id,
+                other: parent_id,
+            });
+        }
+        if !seen.insert(todo_id) {
+            break;
+        }
+        ancestor = parents.get(&todo_id).copied();
+    }
+    Ok(())
+}
+
+/// Checks that `id` may be blocked by `blocked_by_id`, given the
+/// `(id, blocked_by_id)` dependencies of the user's todos.
+pub fn check_dependency(
+    id: u32,
+    blocked_by_id: u32,
+    dependencies: &[(u32, u32)],
+) -> Result<(), ServiceError> {
+    let mut blockers: HashMap<u32, Vec<u32>> = HashMap::new();
+    for (todo_id, blocker_id) in dependencies {
+        blockers.entry(*todo_id).or_default().push(*blocker_id);
+    }
+    let mut seen = HashSet::new();
+    let mut pending = vec![blocked_by_id];
+    while let Some(todo_id) = pending.pop() {
+        if todo_id == id {
+            return Err(ServiceError::Cycle {
+                relation: "dependencies",
+                id,
+                other: blocked_by_id,
+            });
+        }
+        if seen.insert(todo_id) {
+            pending.extend(blockers.get(&todo_id).into_iter().flatten());
+        }
+    }
+    Ok(())
+}
+
+/// Nests `todo` and its descendants, taking the subtasks of every todo from
+/// `subtasks`.
+pub fn todo_tree(todo: TodoItem, subtasks: &mut HashMap<u32, Vec<TodoItem>>) -> TodoTree {
+    let children = subtasks.remove(&todo.id).unwrap_or_default();
+    TodoTree {
+        subtasks: children
+            .into_iter()
+            .map(|child| todo_tree(child, subtasks))
+            .collect(),
+        todo: Some(todo),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::fixtures::{create_todo, repository, share_project, sign_up};
+    use proto::service::todo::ProjectRole;
+
+    #[test]
+    fn rejects_a_todo_as_its_own_parent() {
+        assert!(matches!(
+            check_parent(1, 1, &[]),
+            Err(ServiceError::Cycle { .. })
+        ));
+    }
+
+    #[test]
+    fn rejects_a_parent_among_the_descendants() {
+        // 3 is a subtask of 2, which is a subtask of 1.
+        let parents = [(2, 1), (3, 2)];
+        assert!(matches!(
+            check_parent(1, 3, &parents),
+            Err(ServiceError::Cycle {
+                id: 1,
+                other: 3,
+                ..
+            })
+        ));
+        assert!(check_parent(3, 1, &parents).is_ok());
+        assert!(check_parent(4, 3, &parents).is_ok());
+    }
+
+    #[test]
+    fn rejects_a_todo_blocking_itself() {
+        assert!(matches!(
+            check_dependency(1, 1, &[]),
+            Err(ServiceError::Cycle { .. })
+        ));
+    }
+
+    #[test]
+    fn rejects_a_blocker_blocked_through_other_todos() {
+        // 3 is blocked by 2, which is blocked by 1 and 4.
+        let dependencies = [(3, 2), (2, 1), (2, 4)];
+        assert!(matches!(
+            check_dependency(1, 3, &dependencies),
+            Err(ServiceError::Cycle {
+                id: 1,
+                other: 3,
+                ..
+            })
+        ));
+        assert!(matches!(
+            check_dependency(4, 3, &dependencies),
+            Err(ServiceError::Cycle { .. })
+        ));
+        assert!(check_dependency(3, 1, &dependencies).is_ok());
+        assert!(check_dependency(5, 3, &dependencies).is_ok());
+    }
+
+    #[tokio::test]
+    async fn rejects_a_dependency_on_a_todo_of_another_owner() {
+        let repository = repository();
+        let conn = repository.acquire().await.unwrap();
+        sign_up(&conn, &["alice", "bob"]).await;
+        let theirs = create_todo(&conn, "alice", "Theirs", 0).await;
+        let own = create_todo(&conn, "bob", "Own", 0).await;
+
+        let result = conn.link_todos("bob".to_string(), own.id, theirs.id).await;
+        assert!(matches!(result, Err(ServiceError::NotFound(_))));
+        let result = conn.set_parent("bob".to_string(), own.id, theirs.id).await;
+        assert!(matches!(result, Err(ServiceError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn rejects_a_dependency_on_a_shared_todo_of_another_owner() {
+        let repository = repository();
+        let conn = repository.acquire().await.unwrap();
+        sign_up(&conn, &["alice", "bob"]).await;
+        let project = share_project(&conn, "alice", "bob", ProjectRole::Editor).await;
+        let shared = create_todo(&conn, "bob", "Shared", project.id).await;
+        let own = create_todo(&conn, "bob", "Own", 0).await;
+
+        let result = conn.link_todos("bob".to_string(), shared.id, own.id).await;
+        assert!(matches!(
+            result,
+            Err(ServiceError::InvalidArgument { ref field, .. }) if field == "blocked_by_id"
+        ));
+        let result = conn.set_parent("bob".to_string(), shared.id, own.id).await;
+        assert!(matches!(
+            result,
+            Err(ServiceError::InvalidArgument { ref field, .. }) if field == "parent_id"
+        ));
+    }
+
+    #[tokio::test]
+    async fn hides_the_subtasks_outside_a_shared_project_from_its_members() {
+        let repository = repository();
+        let conn = repository.acquire().await.unwrap();
+        sign_up(&conn, &["alice", "bob"]).await;
+        let project = share_project(&conn, "alice", "bob", ProjectRole::Viewer).await;
+        let root = create_todo(&conn, "alice", "Shared", project.id).await;
+        let shared = create_todo(&conn, "alice", "Shared subtask", project.id).await;
+        let private = create_todo(&conn, "alice", "Private subtask", 0).await;
+        for subtask in [&shared, &private] {
+            conn.set_parent("alice".to_string(), subtask.id, root.id)
+                .await
+                .unwrap();
+        }
+        let subtasks = |username: &'static str| {
+            let conn = &conn;
+            async move {
+                let tree = conn
+                    .get_todo_tree(username.to_string(), root.id)
+                    .await
+                    .unwrap()
+                    .unwrap();
+                tree.subtasks
+                    .iter()
+                    .map(|subtask| subtask.todo.as_ref().unwrap().id)
+                    .collect::<Vec<_>>()
+            }
+        };
+
+        let mut own = subtasks("alice").await;
+        own.sort_unstable();
+        assert_eq!(own, vec![shared.id, private.id]);
+        assert_eq!(subtasks("bob").await, vec![shared.id]);
+    }
+}
@@ -0,0 +1,118 @@
+use crate::error::ServiceError;
+use proto::service::todo::TodoStatus;
+
+/// Statuses a todo in `from` may move to.
+///
+/// Completed and cancelled todos can be reopened or archived; archived todos
+/// are final.
+fn next_statuses(from: TodoStatus) -> &'static [TodoStatus] {
+    use TodoStatus::*;
+    match from {
+        Active => &[InProgress, Blocked, Completed, Cancelled],
+        InProgress => &[Active, Blocked, Completed, Cancelled],
+        Blocked => &[Active, InProgress, Cancelled],
+        Completed => &[Active, Archived],
+        Cancelled => &[Active, Archived],
+        Archived => &[],
+    }
+}
+
+/// Whether a todo in `status` no longer needs work, which is what completes
+/// its parent once every subtask is done.
+pub fn is_done(status: i32) -> bool {
+    matches!(
+        TodoStatus::from_i32(status),
+        Some(TodoStatus::Completed | TodoStatus::Cancelled | TodoStatus::Archived)
+    )
+}
+
+fn status_name(status: i32) -> String {
+    match TodoStatus::from_i32(status) {
+        Some(status) => format!("{:?}", status),
+        None => status.to_string(),
+    }
+}
+
+/// Checks that a todo may move from status `from` to status `to`.
+pub fn check_transition(from: i32, to: i32) -> Result<(), ServiceError> {
+    let allowed = match (TodoStatus::from_i32(from), TodoStatus::from_i32(to)) {
+        (Some(from), Some(to)) => next_statuses(from).contains(&to),
+        _ => false,
+    };
+    if allowed {
+        Ok(())
+    } else {
+        Err(ServiceError::InvalidTransition {
+            from: status_name(from),
+            to: status_name(to),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use TodoStatus::*;
+
+    fn transition(from: TodoStatus, to: TodoStatus) -> Result<(), ServiceError> {
+        check_transition(from as i32, to as i32)
+    }
+
+    #[test]
+    fn allows_the_transitions_of_the_table() {
+        for (from, to) in [
+            (Active, InProgress),
+            (Active, Completed),
+            (InProgress, Blocked),
+            (Blocked, Active),
+            (Completed, Active),
+            (Completed, Archived),
+            (Cancelled, Active),
+            (Cancelled, Archived),
+        ] {
+            assert!(transition(from, to).is_ok(), "{:?} to {:?}", from, to);
+        }
+    }
+
+    #[test]
+    fn rejects_the_transitions_missing_from_the_table() {
+        for (from, to) in [
+            (Active, Active),
+            (Active, Archived),
+            (Blocked, Completed),
+            (Completed, Cancelled),
+            (Archived, Active),
+            (Archived, Completed),
+        ] {
+            assert!(
+                matches!(
+                    transition(from, to),
+                    Err(ServiceError::InvalidTransition { .. })
+                ),
+                "{:?} to {:?}",
+                from,
+                to
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_statuses() {
+        match check_transition(Active as i32, 42) {
+            Err(ServiceError::InvalidTransition { from, to }) => {
+                assert_eq!(from, "Active");
+                assert_eq!(to, "42");
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn done_statuses() {
+        assert!(is_done(Completed as i32));
+        assert!(is_done(Cancelled as i32));
+        assert!(is_done(Archived as i32));
+        assert!(!is_done(Active as i32));
+        assert!(!is_done(Blocked as i32));
+    }
+}
@@ -1,11 +1,28 @@
 mod auth;
 mod connection;
-mod manager;
+mod credentials;
+#[cfg(test)]
+mod fixtures;
+mod hierarchy;
+mod lifecycle;
+mod pagination;
+mod project;
+mod recurrence;
+mod repository;
+mod sharing;
+mod store;
+mod tags;
 mod todo;
 
-pub use crate::db::connection::get_connection_pool;
-pub use crate::db::manager::{Manager, Message};
+pub use crate::db::connection::{acquire_timeout, get_store, max_concurrency, migrations_enabled};
+pub use crate::db::pagination::TodoQuery;
+pub use crate::db::recurrence::{recurrence_interval, Recurrence};
+pub use crate::db::repository::Repository;
+pub use crate::db::tags::normalize_tags;
 pub mod models {
-    pub use crate::db::auth::User;
-    pub use crate::db::todo::TodoItemDb;
+    pub use crate::db::auth::{RevokedTokenDb, User};
+    pub use crate::db::project::ProjectDb;
+    pub use crate::db::sharing::{AccessDb, InvitationDb, MemberDb};
+    pub use crate::db::tags::TagDb;
+    pub use crate::db::todo::{TodoItemDb, TodoTransitionDb};
 }
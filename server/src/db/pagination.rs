@@ -0,0 +1,525 @@
+use crate::db::tags::normalize_tags;
+use crate::db::todo::{to_seconds, TODO_COLUMNS};
+use crate::error::ServiceError;
+use proto::service::todo::{
+    GetTodoRequest, TagMatch, TodoItem, TodoPriority, TodoSortField, TodoStatus,
+};
+use sha2::{Digest, Sha256};
+
+const DEFAULT_PAGE_SIZE: u32 = 50;
+const MAX_PAGE_SIZE: u32 = 100;
+
+/// Sort value of todos without a due date, which puts them last.
+const NO_DUE_DATE: i64 = i64::MAX;
+
+/// Position of the last todo of a page. Todos are ordered by the sort field and
+/// then by id, so these two values are enough to resume after it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TodoCursor {
+    pub value: i64,
+    pub id: u32,
+}
+
+/// A value bound to one of the placeholders of `TodoQuery::to_sql`.
+#[derive(Debug, Clone)]
+pub enum SqlArg {
+    Int(i64),
+    Text(String),
+}
+
+/// Filters, ordering and page of a GetTodos request.
+#[derive(Debug, Clone)]
+pub struct TodoQuery {
+    pub statuses: Vec<i32>,
+    pub priorities: Vec<i32>,
+    pub search: Option<String>,
+    pub due_after: Option<i64>,
+    pub due_before: Option<i64>,
+    pub tags: Vec<String>,
+    pub tag_match: TagMatch,
+    /// Project of the todos, 0 for the inbox.
+    pub project_id: Option<u32>,
+    pub sort_by: TodoSortField,
+    pub descending: bool,
+    pub page_size: u32,
+    pub after: Option<TodoCursor>,
+}
+
+impl TodoQuery {
+    pub fn from_request(req: GetTodoRequest) -> Result<Self, ServiceError> {
+        if let Some(status) = req
+            .statuses
+            .iter()
+            .find(|status| TodoStatus::from_i32(**status).is_none())
+        {
+            return Err(ServiceError::invalid_argument(
+                "statuses",
+                format!("Unknown todo status {}", status),
+            ));
+        }
+        if let Some(priority) = req
+            .priorities
+            .iter()
+            .find(|priority| TodoPriority::from_i32(**priority).is_none())
+        {
+            return Err(ServiceError::invalid_argument(
+                "priorities",
+                format!("Unknown todo priority {}", priority),
+            ));
+        }
+        let sort_by = match TodoSortField::from_i32(req.sort_by) {
+            Some(sort_by) => sort_by,
+            None => {
+                return Err(ServiceError::invalid_argument(
+                    "sort_by",
+                    format!("Unknown sort field {}", req.sort_by),
+                ))
+            }
+        };
+        let tag_match = match TagMatch::from_i32(req.tag_match) {
+            Some(tag_match) => tag_match,
+            None => {
+                return Err(ServiceError::invalid_argument(
+                    "tag_match",
+                    format!("Unknown tag match {}", req.tag_match),
+                ))
+            }
+        };
+        let page_size = match req.page_size {
+            0 => DEFAULT_PAGE_SIZE,
+            page_size => page_size.min(MAX_PAGE_SIZE),
+        };
+        let search = Some(req.search.trim().to_string()).filter(|search| !search.is_empty());
+        let mut query = Self {
+            statuses: req.statuses,
+            priorities: req.priorities,
+            search,
+            due_after: to_seconds(&req.due_after),
+            due_before: to_seconds(&req.due_before),
+            tags: normalize_tags("tags", req.tags)?,
+            tag_match,
+            project_id: req.project_id,
+            sort_by,
+            descending: req.descending,
+            page_size,
+            after: None,
+        };
+        if !req.page_token.is_empty() {
+            query.after = Some(query.decode_page_token(&req.page_token)?);
+        }
+        Ok(query)
+    }
+
+    /// The page token is the cursor prefixed with the ordering and a digest of
+    /// the filters it was issued for, so it cannot be replayed against a
+    /// different query.
+    fn token_prefix(&self) -> String {
+        format!("{}:{}", self.sort_by as i32, self.descending)
+    }
+
+    /// Digest of the filters, independent of the order values are given in.
+    fn filters_digest(&self) -> String {
+        let sorted = |values: &[i32]| {
+            let mut values = values.to_vec();
+            values.sort_unstable();
+            values.dedup();
+            values
+        };
+        let mut tags = self.tags.clone();
+        tags.sort();
+        let filters = format!(
+            "{:?}",
+            (
+                sorted(&self.statuses),
+                sorted(&self.priorities),
+                &self.search,
+                self.due_after,
+                self.due_before,
+                tags,
+                self.tag_match as i32,
+                self.project_id,
+            )
+        );
+        base64::encode_config(
+            &Sha256::digest(filters.as_bytes())[..12],
+            base64::URL_SAFE_NO_PAD,
+        )
+    }
+
+    pub fn page_token(&self, last: &TodoItem) -> String {
+        let token = format!(
+            "{}:{}:{}:{}",
+            self.token_prefix(),
+            self.filters_digest(),
+            self.sort_value(last),
+            last.id
+        );
+        base64::encode_config(token, base64::URL_SAFE_NO_PAD)
+    }
+
+    fn decode_page_token(&self, page_token: &str) -> Result<TodoCursor, ServiceError> {
+        let invalid = || ServiceError::invalid_argument("page_token", "Invalid page token");
+        let token = base64::decode_config(page_token, base64::URL_SAFE_NO_PAD)
+            .ok()
+            .and_then(|token| String::from_utf8(token).ok())
+            .ok_or_else(invalid)?;
+        let parts = token.split(':').collect::<Vec<_>>();
+        if parts.len() != 5 {
+            return Err(invalid());
+        }
+        if format!("{}:{}", parts[0], parts[1]) != self.token_prefix() {
+            return Err(ServiceError::invalid_argument(
+                "page_token",
+                "Page token was issued for a different sort order",
+            ));
+        }
+        if parts[2] != self.filters_digest() {
+            return Err(ServiceError::invalid_argument(
+                "page_token",
+                "Page token was issued for different filters",
+            ));
+        }
+        Ok(TodoCursor {
+            value: parts[3].parse().map_err(|_| invalid())?,
+            id: parts[4].parse().map_err(|_| invalid())?,
+        })
+    }
+
+    /// SQL expression of the sort field, `None` when sorting by id alone.
+    fn sort_column(&self) -> Option<String> {
+        match self.sort_by {
+            TodoSortField::Id => None,
+            TodoSortField::CreatedAt => Some(String::from("t.created_at")),
+            TodoSortField::UpdatedAt => Some(String::from("t.updated_at")),
+            TodoSortField::DueAt => Some(format!("coalesce(t.due_at, {})", NO_DUE_DATE)),
+            TodoSortField::Priority => Some(String::from("t.priority")),
+            TodoSortField::Position => Some(String::from("t.position")),
+        }
+    }
+
+    /// Value of the sort field of `todo`, matching `sort_column`.
+    fn sort_value(&self, todo: &TodoItem) -> i64 {
+        match self.sort_by {
+            TodoSortField::Id => 0,
+            TodoSortField::CreatedAt => to_seconds(&todo.created_at).unwrap_or_default(),
+            TodoSortField::UpdatedAt => to_seconds(&todo.updated_at).unwrap_or_default(),
+            TodoSortField::DueAt => to_seconds(&todo.due_at).unwrap_or(NO_DUE_DATE),
+            TodoSortField::Priority => todo.priority as i64,
+            TodoSortField::Position => todo.position,
+        }
+    }
+
+    /// Builds the keyset query for one page of the todos `username` owns or
+    /// can see through a project, together with its arguments in placeholder
+    /// order. One row more than the page size is fetched to tell whether
+    /// another page follows.
+    pub fn to_sql(&self, username: &str) -> (String, Vec<SqlArg>) {
+        let mut sql = format!(
+            "select {} from todo t INNER JOIN user u on t.userId = u.id where (u.username = ? or t.projectId in (select m.projectId from project_member m INNER JOIN user mu on m.userId = mu.id where mu.username = ?))",
+            TODO_COLUMNS
+        );
+        let mut args = vec![
+            SqlArg::Text(username.to_string()),
+            SqlArg::Text(username.to_string()),
+        ];
+        for (column, values) in [
+            ("t.status", &self.statuses),
+            ("t.priority", &self.priorities),
+        ] {
+            if !values.is_empty() {
+                let placeholders = vec!["?"; values.len()].join(", ");
+                sql.push_str(&format!(" and {} in ({})", column, placeholders));
+                args.extend(values.iter().map(|value| SqlArg::Int(*value as i64)));
+            }
+        }
+        if let Some(search) = &self.search {
+            sql.push_str(" and (t.title like ? escape '!' or t.description like ? escape '!' or t.notes like ? escape '!')");
+            let pattern = format!("%{}%", escape_like(search));
+            args.extend((0..3).map(|_| SqlArg::Text(pattern.clone())));
+        }
+        if let Some(due_after) = self.due_after {
+            sql.push_str(" and t.due_at >= ?");
+            args.push(SqlArg::Int(due_after));
+        }
+        if let Some(due_before) = self.due_before {
+            sql.push_str(" and t.due_at < ?");
+            args.push(SqlArg::Int(due_before));
+        }
+        match self.project_id {
+            Some(0) => sql.push_str(" and t.projectId is null"),
+            Some(project_id) => {
+                sql.push_str(" and t.projectId = ?");
+                args.push(SqlArg::Int(project_id as i64));
+            }
+            None => {}
+        }
+        if !self.tags.is_empty() {
+            let tagged = format!(
+                "from todo_tag tt INNER JOIN tag g on tt.tagId = g.id where tt.todoId = t.id and g.name in ({})",
+                vec!["?"; self.tags.len()].join(", ")
+            );
+            match self.tag_match {
+                TagMatch::Any => sql.push_str(&format!(" and exists (select 1 {})", tagged)),
+                TagMatch::All => {
+                    sql.push_str(&format!(" and (select count(*) {}) = ?", tagged));
+                }
+            }
+            args.extend(self.tags.iter().map(|tag| SqlArg::Text(tag.clone())));
+            if self.tag_match == TagMatch::All {
+                args.push(SqlArg::Int(self.tags.len() as i64));
+            }
+        }
+        let (cmp, direction) = if self.descending {
+            ("<", "desc")
+        } else {
+            (">", "asc")
+        };
+        match self.sort_column() {
+            None => {
+                if let Some(after) = &self.after {
+                    sql.push_str(&format!(" and t.id {} ?", cmp));
+                    args.push(SqlArg::Int(after.id as i64));
+                }
+                sql.push_str(&format!(" order by t.id {}", direction));
+            }
+            Some(column) => {
+                if let Some(after) = &self.after {
+                    sql.push_str(&format!(
+                        " and ({column} {cmp} ? or ({column} = ? and t.id {cmp} ?))",
+                        column = column,
+                        cmp = cmp
+                    ));
+                    args.push(SqlArg::Int(after.value));
+                    args.push(SqlArg::Int(after.value));
+                    args.push(SqlArg::Int(after.id as i64));
+                }
+                sql.push_str(&format!(
+                    " order by {column} {direction}, t.id {direction}",
+                    column = column,
+                    direction = direction
+                ));
+            }
+        }
+        sql.push_str(" limit ?");
+        args.push(SqlArg::Int(self.page_size as i64 + 1));
+        (sql, args)
+    }
+
+    /// Whether `todo` passes the filters and lies after the cursor. Used by
+    /// backends that cannot run `to_sql`.
+    pub fn matches(&self, todo: &TodoItem) -> bool {
+        if !self.statuses.is_empty() && !self.statuses.contains(&todo.status) {
+            return false;
+        }
+        if !self.priorities.is_empty() && !self.priorities.contains(&todo.priority) {
+            return false;
+        }
+        if let Some(search) = &self.search {
+            let search = search.to_lowercase();
+            if ![&todo.title, &todo.description, &todo.notes]
+                .iter()
+                .any(|text| text.to_lowercase().contains(&search))
+            {
+                return false;
+            }
+        }
+        let due_at = to_seconds(&todo.due_at);
+        if let Some(due_after) = self.due_after {
+            if !matches!(due_at, Some(due_at) if due_at >= due_after) {
+                return false;
+            }
+        }
+        if let Some(due_before) = self.due_before {
+            if !matches!(due_at, Some(due_at) if due_at < due_before) {
+                return false;
+            }
+        }
+        if matches!(self.project_id, Some(project_id) if project_id != todo.project_id) {
+            return false;
+        }
+        if !self.tags.is_empty() {
+            let tagged = |tag: &String| todo.tags.contains(tag);
+            let matched = match self.tag_match {
+                TagMatch::Any => self.tags.iter().any(tagged),
+                TagMatch::All => self.tags.iter().all(tagged),
+            };
+            if !matched {
+                return false;
+            }
+        }
+        match &self.after {
+            Some(after) => {
+                let position = self.sort_key(todo);
+                let after = (after.value, after.id);
+                if self.descending {
+                    position < after
+                } else {
+                    position > after
+                }
+            }
+            None => true,
+        }
+    }
+
+    /// Key todos are ordered by, before applying `descending`.
+    pub fn sort_key(&self, todo: &TodoItem) -> (i64, u32) {
+        (self.sort_value(todo), todo.id)
+    }
+}
+
+fn escape_like(search: &str) -> String {
+    let mut escaped = String::with_capacity(search.len());
+    for c in search.chars() {
+        if matches!(c, '!' | '%' | '_') {
+            escaped.push('!');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query(req: GetTodoRequest) -> TodoQuery {
+        TodoQuery::from_request(req).unwrap()
+    }
+
+    fn todo(id: u32, priority: TodoPriority) -> TodoItem {
+        TodoItem {
+            id,
+            priority: priority as i32,
+            status: TodoStatus::Active as i32,
+            tags: vec!["work".to_string()],
+            ..Default::default()
+        }
+    }
+
+    fn by_priority(descending: bool) -> GetTodoRequest {
+        GetTodoRequest {
+            sort_by: TodoSortField::Priority as i32,
+            descending,
+            statuses: vec![TodoStatus::Active as i32],
+            tags: vec!["work".to_string()],
+            ..Default::default()
+        }
+    }
+
+    fn next_page(req: GetTodoRequest, last: &TodoItem) -> Result<TodoQuery, ServiceError> {
+        let page_token = query(req.clone()).page_token(last);
+        TodoQuery::from_request(GetTodoRequest { page_token, ..req })
+    }
+
+    fn invalid_page_token(result: Result<TodoQuery, ServiceError>) -> bool {
+        matches!(
+            result,
+            Err(ServiceError::InvalidArgument { ref field, .. }) if field == "page_token"
+        )
+    }
+
+    #[test]
+    fn resumes_after_the_last_todo_of_a_page() {
+        let last = todo(7, TodoPriority::High);
+        let next = next_page(by_priority(false), &last).unwrap();
+        assert_eq!(
+            next.after,
+            Some(TodoCursor {
+                value: TodoPriority::High as i64,
+                id: 7
+            })
+        );
+    }
+
+    #[test]
+    fn orders_ties_on_the_sort_field_by_id() {
+        let next = next_page(by_priority(false), &todo(7, TodoPriority::High)).unwrap();
+        assert!(!next.matches(&todo(6, TodoPriority::High)));
+        assert!(!next.matches(&todo(7, TodoPriority::High)));
+        assert!(next.matches(&todo(8, TodoPriority::High)));
+        assert!(!next.matches(&todo(9, TodoPriority::Low)));
+    }
+
+    #[test]
+    fn resumes_descending_pages_before_the_last_todo() {
+        let next = next_page(by_priority(true), &todo(7, TodoPriority::High)).unwrap();
+        assert!(next.matches(&todo(6, TodoPriority::High)));
+        assert!(!next.matches(&todo(8, TodoPriority::High)));
+        assert!(next.matches(&todo(9, TodoPriority::Low)));
+    }
+
+    #[test]
+    fn accepts_the_same_filters_in_another_order() {
+        let req = GetTodoRequest {
+            statuses: vec![TodoStatus::Active as i32, TodoStatus::Blocked as i32],
+            ..Default::default()
+        };
+        let page_token = query(req.clone()).page_token(&todo(7, TodoPriority::Low));
+        let reordered = GetTodoRequest {
+            statuses: vec![TodoStatus::Blocked as i32, TodoStatus::Active as i32],
+            page_token,
+            ..req
+        };
+        assert!(TodoQuery::from_request(reordered).is_ok());
+    }
+
+    #[test]
+    fn rejects_a_page_token_of_another_query() {
+        let page_token = query(by_priority(false)).page_token(&todo(7, TodoPriority::High));
+        let other_sort = GetTodoRequest {
+            page_token: page_token.clone(),
+            ..by_priority(true)
+        };
+        assert!(invalid_page_token(TodoQuery::from_request(other_sort)));
+        let other_filters = [
+            GetTodoRequest {
+                statuses: vec![TodoStatus::Completed as i32],
+                ..by_priority(false)
+            },
+            GetTodoRequest {
+                tags: vec![],
+                ..by_priority(false)
+            },
+            GetTodoRequest {
+                project_id: Some(0),
+                ..by_priority(false)
+            },
+            GetTodoRequest {
+                search: "milk".to_string(),
+                ..by_priority(false)
+            },
+        ];
+        for req in other_filters {
+            assert!(invalid_page_token(TodoQuery::from_request(
+                GetTodoRequest {
+                    page_token: page_token.clone(),
+                    ..req
+                }
+            )));
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_and_tampered_page_tokens() {
+        let page_token = query(by_priority(false)).page_token(&todo(7, TodoPriority::High));
+        let token =
+            String::from_utf8(base64::decode_config(&page_token, base64::URL_SAFE_NO_PAD).unwrap())
+                .unwrap();
+        let mut parts = token.split(':').map(String::from).collect::<Vec<_>>();
+        parts[2] = "forged".to_string();
+        let tampered = base64::encode_config(parts.join(":"), base64::URL_SAFE_NO_PAD);
+        for page_token in [
+            "not base64!".to_string(),
+            base64::encode_config("1:false:7", base64::URL_SAFE_NO_PAD),
+            base64::encode_config(format!("{}:x", token), base64::URL_SAFE_NO_PAD),
+            tampered,
+        ] {
+            assert!(invalid_page_token(TodoQuery::from_request(
+                GetTodoRequest {
+                    page_token,
+                    ..by_priority(false)
+                }
+            )));
+        }
+    }
+}
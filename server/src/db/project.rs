@@ -0,0 +1,48 @@
+use crate::db::todo::to_timestamp;
+use proto::service::todo::{Project, ProjectRole};
+use sqlx::FromRow;
+
+/// Selects a `ProjectDb` as seen by one of the members of the project, for
+/// `project_member` aliased `m` joined to that member as `mu`.
+pub const PROJECT_SELECT: &str = "select p.id, p.name, p.created_at, p.updated_at, o.username as owner, m.role from project p INNER JOIN user o on p.userId = o.id INNER JOIN project_member m on m.projectId = p.id INNER JOIN user mu on m.userId = mu.id";
+
+/// A list of todos. Todos without a project are in the user's inbox.
+#[derive(Debug, FromRow, Clone)]
+pub struct ProjectDb {
+    pub id: u32,
+    pub name: String,
+    pub created_at: i64,
+    pub updated_at: i64,
+    /// User the project and its todos belong to.
+    pub owner: String,
+    /// Role of the user the project was read for.
+    pub role: i32,
+}
+
+impl ProjectDb {
+    /// Row of a project created by `owner` at `now`. The id is assigned on
+    /// insert.
+    pub fn new(name: String, owner: String, now: i64) -> Self {
+        Self {
+            id: 0,
+            name,
+            created_at: now,
+            updated_at: now,
+            owner,
+            role: ProjectRole::Owner as i32,
+        }
+    }
+}
+
+impl From<ProjectDb> for Project {
+    fn from(project: ProjectDb) -> Self {
+        Project {
+            id: project.id,
+            name: project.name,
+            created_at: Some(to_timestamp(project.created_at)),
+            updated_at: Some(to_timestamp(project.updated_at)),
+            owner: project.owner,
+            role: project.role,
+        }
+    }
+}
@@ -0,0 +1,521 @@
+use crate::config::env_or;
+use crate::error::ServiceError;
+use std::fmt;
+use std::time::Duration;
+
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/// Occurrences looked at before giving up on finding the next one, which
+/// bounds rules that rarely or never match such as a yearly 29 February.
+const MAX_OCCURRENCES: u32 = 100_000;
+
+const WEEKDAYS: [&str; 7] = ["MO", "TU", "WE", "TH", "FR", "SA", "SU"];
+
+/// How often overdue recurring todos are materialised,
+/// `TODO_RECURRENCE_INTERVAL_SECS` (60 seconds by default).
+pub fn recurrence_interval() -> Duration {
+    Duration::from_secs(env_or("TODO_RECURRENCE_INTERVAL_SECS", 60).max(1))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum End {
+    Never,
+    /// Occurrences left in the series, counting the current one.
+    Count(u32),
+    /// Last time an occurrence may fall on, in seconds since the Unix epoch.
+    Until(i64),
+}
+
+/// A recurrence rule, the subset of an RFC 5545 RRULE made of `FREQ`
+/// (`DAILY`, `WEEKLY`, `MONTHLY` or `YEARLY`), `INTERVAL`, `COUNT`, `UNTIL`
+/// and, for weekly rules, `BYDAY`.
+///
+/// The rule of a todo starts at its due date, which plays the part of
+/// `DTSTART`. Times are in UTC. Monthly and yearly rules skip the months and
+/// years without the day of the due date, as RFC 5545 does.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Recurrence {
+    frequency: Frequency,
+    interval: u32,
+    /// Days of the week as 0 for Monday to 6 for Sunday, sorted.
+    weekdays: Vec<u32>,
+    end: End,
+}
+
+fn invalid(description: impl Into<String>) -> ServiceError {
+    ServiceError::invalid_argument("recurrence", description)
+}
+
+impl Recurrence {
+    /// Parses a rule such as `FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,TH`, with or
+    /// without the `RRULE:` prefix.
+    pub fn parse(rule: &str) -> Result<Self, ServiceError> {
+        let rule = rule.trim();
+        let rule = rule
+            .strip_prefix("RRULE:")
+            .or_else(|| rule.strip_prefix("rrule:"))
+            .unwrap_or(rule);
+        let mut frequency = None;
+        let mut interval = 1;
+        let mut weekdays = vec![];
+        let mut end = End::Never;
+        for part in rule.split(';').filter(|part| !part.is_empty()) {
+            let (name, value) = part
+                .split_once('=')
+                .ok_or_else(|| invalid(format!("Invalid rule part '{}'", part)))?;
+            let value = value.to_uppercase();
+            match name.to_uppercase().as_str() {
+                "FREQ" => {
+                    frequency = Some(match value.as_str() {
+                        "DAILY" => Frequency::Daily,
+                        "WEEKLY" => Frequency::Weekly,
+                        "MONTHLY" => Frequency::Monthly,
+                        "YEARLY" => Frequency::Yearly,
+                        _ => return Err(invalid(format!("Unsupported frequency '{}'", value))),
+                    })
+                }
+                "INTERVAL" => {
+                    interval = value
+                        .parse()
+                        .ok()
+                        .filter(|interval| *interval > 0)
+                        .ok_or_else(|| invalid("INTERVAL must be a positive number"))?
+                }
+                "COUNT" => {
+                    if end != End::Never {
+                        return Err(invalid("COUNT and UNTIL cannot be combined"));
+                    }
+                    end = End::Count(
+                        value
+                            .parse()
+                            .ok()
+                            .filter(|count| *count > 0)
+                            .ok_or_else(|| invalid("COUNT must be a positive number"))?,
+                    )
+                }
+                "UNTIL" => {
+                    if end != End::Never {
+                        return Err(invalid("COUNT and UNTIL cannot be combined"));
+                    }
+                    end = End::Until(parse_until(&value)?)
+                }
+                "BYDAY" => {
+                    for day in value.split(',') {
+                        let weekday = WEEKDAYS
+                            .iter()
+                            .position(|weekday| *weekday == day)
+                            .ok_or_else(|| invalid(format!("Unknown day '{}'", day)))?;
+                        weekdays.push(weekday as u32);
+                    }
+                    weekdays.sort_unstable();
+                    weekdays.dedup();
+                }
+                name => return Err(invalid(format!("Unsupported rule part '{}'", name))),
+            }
+        }
+        let frequency = frequency.ok_or_else(|| invalid("FREQ is required"))?;
+        if !weekdays.is_empty() && frequency != Frequency::Weekly {
+            return Err(invalid("BYDAY is only supported with FREQ=WEEKLY"));
+        }
+        Ok(Self {
+            frequency,
+            interval,
+            weekdays,
+            end,
+        })
+    }
+
+    /// First occurrence after both `due_at` and `now` of the series starting
+    /// at `due_at`, along with the rule of the series starting there. Missed
+    /// occurrences are skipped, `None` when the series has ended.
+    pub fn next(&self, due_at: i64, now: i64) -> Option<(i64, Recurrence)> {
+        let mut occurrences = 0;
+        let mut last = due_at;
+        // Whole cycles of daily and weekly rules are skipped at once, so that
+        // a due date long past does not run out of MAX_OCCURRENCES.
+        if let Some((cycle, per_cycle)) = self.cycle() {
+            let target = match self.end {
+                End::Until(until) => now.min(until),
+                _ => now,
+            };
+            let cycles = (target - last).div_euclid(cycle).max(0);
+            last += cycles * cycle;
+            occurrences = cycles as u64 * per_cycle;
+        }
+        for _ in 0..MAX_OCCURRENCES {
+            last = self.following(due_at, last)?;
+            occurrences += 1;
+            let rule = match self.end {
+                End::Count(count) if count as u64 <= occurrences => return None,
+                End::Count(count) => Recurrence {
+                    end: End::Count(count - occurrences as u32),
+                    ..self.clone()
+                },
+                End::Until(until) if last > until => return None,
+                _ => self.clone(),
+            };
+            if last > now {
+                return Some((last, rule));
+            }
+        }
+        None
+    }
+
+    /// Seconds after which daily and weekly rules repeat, with the number of
+    /// occurrences in between. `None` for monthly and yearly rules, whose
+    /// months and years differ in length.
+    fn cycle(&self) -> Option<(i64, u64)> {
+        let interval = self.interval as i64;
+        match self.frequency {
+            Frequency::Daily => Some((interval * SECONDS_PER_DAY, 1)),
+            Frequency::Weekly => Some((
+                7 * interval * SECONDS_PER_DAY,
+                self.weekdays.len().max(1) as u64,
+            )),
+            Frequency::Monthly | Frequency::Yearly => None,
+        }
+    }
+
+    /// Occurrence right after `last` of the series starting at `start`.
+    fn following(&self, start: i64, last: i64) -> Option<i64> {
+        let time = start.rem_euclid(SECONDS_PER_DAY);
+        let start_day = start.div_euclid(SECONDS_PER_DAY);
+        let last_day = last.div_euclid(SECONDS_PER_DAY);
+        let interval = self.interval as i64;
+        let day = match self.frequency {
+            Frequency::Daily => last_day + interval,
+            Frequency::Weekly if self.weekdays.is_empty() => last_day + 7 * interval,
+            Frequency::Weekly => {
+                // Weeks start on Monday; the week of `start` is the first one.
+                let first_monday = start_day - weekday(start_day) as i64;
+                let mut monday = last_day - weekday(last_day) as i64;
+                loop {
+                    let week = (monday - first_monday) / 7;
+                    if week % interval == 0 {
+                        if let Some(day) = self
+                            .weekdays
+                            .iter()
+                            .map(|weekday| monday + *weekday as i64)
+                            .find(|day| *day > last_day)
+                        {
+                            break day;
+                        }
+                    }
+                    monday += 7;
+                }
+            }
+            Frequency::Monthly | Frequency::Yearly => {
+                let (start_year, start_month, start_day) = civil_from_days(start_day);
+                let (last_year, last_month, _) = civil_from_days(last_day);
+                let step = match self.frequency {
+                    Frequency::Monthly => interval,
+                    _ => 12 * interval,
+                };
+                let mut months = (last_year - start_year) * 12 + (last_month - start_month);
+                let mut attempts = 0;
+                loop {
+                    months += step;
+                    attempts += 1;
+                    if attempts > MAX_OCCURRENCES {
+                        return None;
+                    }
+                    let total = start_year * 12 + (start_month - 1) + months;
+                    let (year, month) = (total.div_euclid(12), total.rem_euclid(12) + 1);
+                    if start_day <= days_in_month(year, month) {
+                        break days_from_civil(year, month, start_day);
+                    }
+                }
+            }
+        };
+        Some(day * SECONDS_PER_DAY + time)
+    }
+}
+
+impl fmt::Display for Recurrence {
+    /// Formats the rule in a canonical form, which is how rules are stored.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let frequency = match self.frequency {
+            Frequency::Daily => "DAILY",
+            Frequency::Weekly => "WEEKLY",
+            Frequency::Monthly => "MONTHLY",
+            Frequency::Yearly => "YEARLY",
+        };
+        write!(f, "FREQ={}", frequency)?;
+        if self.interval != 1 {
+            write!(f, ";INTERVAL={}", self.interval)?;
+        }
+        if !self.weekdays.is_empty() {
+            let weekdays = self
+                .weekdays
+                .iter()
+                .map(|weekday| WEEKDAYS[*weekday as usize])
+                .collect::<Vec<_>>();
+            write!(f, ";BYDAY={}", weekdays.join(","))?;
+        }
+        match self.end {
+            End::Never => Ok(()),
+            End::Count(count) => write!(f, ";COUNT={}", count),
+            End::Until(until) => {
+                let day = until.div_euclid(SECONDS_PER_DAY);
+                let time = until.rem_euclid(SECONDS_PER_DAY);
+                let (year, month, day) = civil_from_days(day);
+                write!(
+                    f,
+                    ";UNTIL={:04}{:02}{:02}T{:02}{:02}{:02}Z",
+                    year,
+                    month,
+                    day,
+                    time / 3600,
+                    time % 3600 / 60,
+                    time % 60
+                )
+            }
+        }
+    }
+}
+
+/// Parses an `UNTIL` of the form `YYYYMMDD`, which includes the whole day, or
+/// `YYYYMMDDTHHMMSSZ`.
+fn parse_until(value: &str) -> Result<i64, ServiceError> {
+    let digits = |range: std::ops::Range<usize>| -> Result<i64, ServiceError> {
+        value
+            .get(range)
+            .filter(|digits| digits.bytes().all(|b| b.is_ascii_digit()))
+            .and_then(|digits| digits.parse().ok())
+            .ok_or_else(|| invalid("UNTIL must be YYYYMMDD or YYYYMMDDTHHMMSSZ"))
+    };
+    let (year, month, day) = (digits(0..4)?, digits(4..6)?, digits(6..8)?);
+    if !(1..=12).contains(&month) || day < 1 || day > days_in_month(year, month) {
+        return Err(invalid("UNTIL is not a valid date"));
+    }
+    let time = match value.len() {
+        8 => SECONDS_PER_DAY - 1,
+        16 if value.get(8..9) == Some("T") && value.ends_with('Z') => {
+            let (hours, minutes, seconds) = (digits(9..11)?, digits(11..13)?, digits(13..15)?);
+            if hours > 23 || minutes > 59 || seconds > 59 {
+                return Err(invalid("UNTIL is not a valid time"));
+            }
+            hours * 3600 + minutes * 60 + seconds
+        }
+        _ => return Err(invalid("UNTIL must be YYYYMMDD or YYYYMMDDTHHMMSSZ")),
+    };
+    Ok(days_from_civil(year, month, day) * SECONDS_PER_DAY + time)
+}
+
+/// Day of the week of a day since the Unix epoch, 0 for Monday.
+fn weekday(days: i64) -> u32 {
+    // 1 January 1970 was a Thursday.
+    (days + 3).rem_euclid(7) as u32
+}
+
+fn is_leap_year(year: i64) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Days since the Unix epoch of a date of the proleptic Gregorian calendar.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Date of a day since the Unix epoch, the inverse of `days_from_civil`.
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(year: i64, month: i64, day: i64, hour: i64) -> i64 {
+        days_from_civil(year, month, day) * SECONDS_PER_DAY + hour * 3600
+    }
+
+    /// The first `n` occurrences after `due_at`, one after the other.
+    fn occurrences(rule: &str, due_at: i64, n: usize) -> Vec<i64> {
+        let mut rule = Recurrence::parse(rule).unwrap();
+        let mut due_at = due_at;
+        let mut occurrences = vec![];
+        while occurrences.len() < n {
+            match rule.next(due_at, due_at) {
+                Some((next, next_rule)) => {
+                    occurrences.push(next);
+                    due_at = next;
+                    rule = next_rule;
+                }
+                None => break,
+            }
+        }
+        occurrences
+    }
+
+    #[test]
+    fn weekly_by_day_skips_the_weeks_between_intervals() {
+        // 1 January 2026 was a Thursday, in the week starting on 29 December.
+        let next = occurrences("FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,TH", at(2026, 1, 1, 9), 5);
+        assert_eq!(
+            next,
+            vec![
+                at(2026, 1, 12, 9),
+                at(2026, 1, 15, 9),
+                at(2026, 1, 26, 9),
+                at(2026, 1, 29, 9),
+                at(2026, 2, 9, 9),
+            ]
+        );
+    }
+
+    #[test]
+    fn monthly_on_the_31st_skips_shorter_months() {
+        let next = occurrences("FREQ=MONTHLY", at(2026, 1, 31, 9), 4);
+        assert_eq!(
+            next,
+            vec![
+                at(2026, 3, 31, 9),
+                at(2026, 5, 31, 9),
+                at(2026, 7, 31, 9),
+                at(2026, 8, 31, 9),
+            ]
+        );
+    }
+
+    #[test]
+    fn yearly_on_29_february_waits_for_leap_years() {
+        let next = occurrences("FREQ=YEARLY", at(2024, 2, 29, 9), 2);
+        assert_eq!(next, vec![at(2028, 2, 29, 9), at(2032, 2, 29, 9)]);
+    }
+
+    #[test]
+    fn count_ends_the_series() {
+        let next = occurrences("FREQ=DAILY;COUNT=3", at(2026, 1, 1, 9), 10);
+        assert_eq!(next, vec![at(2026, 1, 2, 9), at(2026, 1, 3, 9)]);
+
+        let rule = Recurrence::parse("FREQ=DAILY;COUNT=3").unwrap();
+        let (next, rest) = rule.next(at(2026, 1, 1, 9), at(2026, 1, 2, 12)).unwrap();
+        assert_eq!(next, at(2026, 1, 3, 9));
+        assert_eq!(rest.to_string(), "FREQ=DAILY;COUNT=1");
+        assert_eq!(rule.next(at(2026, 1, 1, 9), at(2026, 1, 5, 0)), None);
+    }
+
+    #[test]
+    fn skips_the_occurrences_of_a_due_date_long_past() {
+        let due_at = at(1026, 1, 1, 9);
+        let now = at(2026, 1, 1, 12);
+        let rule = Recurrence::parse("FREQ=DAILY").unwrap();
+        assert_eq!(rule.next(due_at, now).unwrap().0, at(2026, 1, 2, 9));
+
+        // 1 January 2026 was a Thursday.
+        let rule = Recurrence::parse("FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,TH").unwrap();
+        let start = at(2025, 12, 29, 9);
+        let (next, _) = rule.next(start, now).unwrap();
+        let (stale_next, _) = rule.next(start - 26 * 7 * 86_400 * 1000, now).unwrap();
+        assert_eq!(next, at(2026, 1, 1, 9) + 11 * 86_400);
+        assert_eq!(stale_next, next);
+
+        let rule = Recurrence::parse("FREQ=DAILY;COUNT=400000").unwrap();
+        let (next, rest) = rule.next(at(2025, 12, 31, 9), now).unwrap();
+        assert_eq!(next, at(2026, 1, 2, 9));
+        assert_eq!(rest.to_string(), "FREQ=DAILY;COUNT=399998");
+        let (_, rest) = rule.next(due_at, now).unwrap();
+        assert_eq!(rest.to_string(), "FREQ=DAILY;COUNT=34756");
+        assert_eq!(
+            Recurrence::parse("FREQ=DAILY;COUNT=1000")
+                .unwrap()
+                .next(due_at, now),
+            None
+        );
+        assert_eq!(
+            Recurrence::parse("FREQ=DAILY;UNTIL=20250101")
+                .unwrap()
+                .next(due_at, now),
+            None
+        );
+    }
+
+    #[test]
+    fn until_ends_the_series() {
+        let next = occurrences("FREQ=DAILY;UNTIL=20260103", at(2026, 1, 1, 9), 10);
+        assert_eq!(next, vec![at(2026, 1, 2, 9), at(2026, 1, 3, 9)]);
+
+        let next = occurrences("FREQ=DAILY;UNTIL=20260103T090000Z", at(2026, 1, 1, 9), 10);
+        assert_eq!(next, vec![at(2026, 1, 2, 9), at(2026, 1, 3, 9)]);
+
+        let next = occurrences("FREQ=DAILY;UNTIL=20260103T085959Z", at(2026, 1, 1, 9), 10);
+        assert_eq!(next, vec![at(2026, 1, 2, 9)]);
+    }
+
+    #[test]
+    fn display_round_trips_in_canonical_form() {
+        for (rule, canonical) in [
+            ("FREQ=DAILY", "FREQ=DAILY"),
+            (
+                "rrule:freq=monthly;interval=1;count=5",
+                "FREQ=MONTHLY;COUNT=5",
+            ),
+            (
+                "RRULE:BYDAY=th,MO,mo;FREQ=WEEKLY;INTERVAL=2;UNTIL=20260103",
+                "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,TH;UNTIL=20260103T235959Z",
+            ),
+            (
+                "FREQ=YEARLY;UNTIL=20301231T080910Z",
+                "FREQ=YEARLY;UNTIL=20301231T080910Z",
+            ),
+        ] {
+            let recurrence = Recurrence::parse(rule).unwrap();
+            assert_eq!(recurrence.to_string(), canonical);
+            assert_eq!(Recurrence::parse(canonical).unwrap(), recurrence);
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_rules() {
+        for rule in [
+            "",
+            "INTERVAL=2",
+            "FREQ=HOURLY",
+            "FREQ=DAILY;INTERVAL=0",
+            "FREQ=DAILY;COUNT=2;UNTIL=20260103",
+            "FREQ=MONTHLY;BYDAY=MO",
+            "FREQ=DAILY;UNTIL=20260230",
+            "FREQ=DAILY;UNTIL=20260103T25000Z",
+            "FREQ=DAILY;UNTIL=20260103é090000",
+        ] {
+            assert!(Recurrence::parse(rule).is_err(), "{}", rule);
+        }
+    }
+}
@@ -0,0 +1,1374 @@
+use crate::clock;
+use crate::db::credentials::{
+    decoy_hash, hash_password, hash_pin, verify_password, verify_pin, PinMatch,
+};
+use crate::db::hierarchy::{check_dependency, check_parent, todo_tree};
+use crate::db::lifecycle::{check_transition, is_done};
+use crate::db::models::{
+    InvitationDb, MemberDb, ProjectDb, RevokedTokenDb, TagDb, TodoItemDb, TodoTransitionDb, User,
+};
+use crate::db::pagination::TodoQuery;
+use crate::db::recurrence::Recurrence;
+use crate::db::sharing::check_role;
+use crate::db::store::Store;
+use crate::db::todo::{to_seconds, to_timestamp};
+use crate::error::ServiceError;
+use crate::events::TodoEvents;
+use crate::reminders::DueReminder;
+use crate::tokens::{
+    access_token_ttl, generate_recovery_codes, generate_refresh_token, generate_totp_secret,
+    hash_recovery_code, hash_refresh_token, is_totp_code, issue_access_token, otpauth_uri,
+    refresh_token_ttl, sign_in_challenge_ttl, verify_totp_code, SignInOutcome, TokenPair,
+    LEEWAY_SECS,
+};
+use proto::service::auth::{SignInRequest, SignUpRequest};
+use proto::service::todo::{
+    CreateTodoRequest, ProjectRole, Reminder, TodoEventKind, TodoItem, TodoStatus, TodoTree,
+    UpdateTodoRequest,
+};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc::Sender as MpscSender;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio::time::{interval, timeout};
+use tracing::info;
+use tracing::log::error;
+use uuid::Uuid;
+
+/// Codes that can be tried against a single sign in challenge.
+const MAX_CHALLENGE_ATTEMPTS: i32 = 5;
+
+/// Entry point to the database. Requests run concurrently against the
+/// configured `Store` up to `max_concurrency`. Todo changes made through it
+/// are published to `events`.
+#[derive(Debug, Clone)]
+pub struct Repository {
+    store: Arc<dyn Store>,
+    events: TodoEvents,
+    limiter: Arc<Semaphore>,
+    acquire_timeout: Duration,
+    decoy_hash: Arc<str>,
+}
+
+/// Access to the store reserved for a single request. The concurrency permit
+/// is released when this is dropped.
+pub struct RepositoryHandle {
+    store: Arc<dyn Store>,
+    events: TodoEvents,
+    decoy_hash: Arc<str>,
+    _permit: OwnedSemaphorePermit,
+}
+
+impl Repository {
+    pub fn new(
+        store: Arc<dyn Store>,
+        events: TodoEvents,
+        max_concurrency: usize,
+        acquire_timeout: Duration,
+    ) -> Result<Self, String> {
+        Ok(Self {
+            store,
+            events,
+            limiter: Arc::new(Semaphore::new(max_concurrency)),
+            acquire_timeout,
+            decoy_hash: decoy_hash()?.into(),
+        })
+    }
+
+    /// Waits up to `acquire_timeout` for a free slot, failing with
+    /// `ResourceExhausted` when the server is saturated.
+    pub async fn acquire(&self) -> Result<RepositoryHandle, ServiceError> {
+        match timeout(self.acquire_timeout, self.limiter.clone().acquire_owned()).await {
+            Ok(Ok(permit)) => Ok(RepositoryHandle {
+                store: self.store.clone(),
+                events: self.events.clone(),
+                decoy_hash: self.decoy_hash.clone(),
+                _permit: permit,
+            }),
+            Ok(Err(e)) => {
+                error!("Database limiter closed {:?}", e);
+                Err(ServiceError::Unavailable)
+            }
+            Err(_) => {
+                error!("Timed out waiting for a database slot");
+                Err(ServiceError::ResourceExhausted)
+            }
+        }
+    }
+
+    /// Materializes the next occurrence of overdue recurring todos every
+    /// `period`, for as long as the server runs.
+    pub async fn run_recurrences(self, period: Duration) {
+        let mut ticks = interval(period);
+        loop {
+            ticks.tick().await;
+            let result = match self.acquire().await {
+                Ok(conn) => conn.materialize_recurrences().await,
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(0) => {}
+                Ok(count) => info!("Materialized {} overdue recurring todos", count),
+                Err(e) => error!("Unable to materialize recurring todos {:?}", e),
+            }
+        }
+    }
+
+    /// Loads the revoked access tokens that are still accepted, expiry leeway
+    /// included, used to seed the revocation cache on startup.
+    pub async fn revoked_tokens(&self) -> Result<Vec<RevokedTokenDb>, ServiceError> {
+        self.store.revoked_tokens(clock::now() - LEEWAY_SECS).await
+    }
+}
+
+impl RepositoryHandle {
+    /// Checks the credentials of a user, issuing their tokens right away or,
+    /// when they have enabled TOTP, a challenge to answer with
+    /// `verify_second_factor`.
+    pub async fn sign_in(&self, req: SignInRequest) -> Result<SignInOutcome, ServiceError> {
+        let user = self
+            .check_credentials(&req.username, &req.password, req.pin)
+            .await?;
+        if !user.totp_enabled {
+            return Ok(SignInOutcome::Signed(
+                self.issue_tokens(&user.username).await?,
+            ));
+        }
+        let challenge_token = generate_refresh_token();
+        let expires_in = sign_in_challenge_ttl();
+        self.store
+            .insert_challenge(
+                &user.username,
+                &hash_refresh_token(&challenge_token),
+                clock::now() + expires_in,
+            )
+            .await?;
+        Ok(SignInOutcome::SecondFactorRequired {
+            challenge_token,
+            expires_in,
+        })
+    }
+
+    /// Exchanges a sign in challenge and a TOTP or recovery code of its user
+    /// for tokens, returning the user along with them. Each challenge can be
+    /// tried `MAX_CHALLENGE_ATTEMPTS` times and exchanged once.
+    pub async fn verify_second_factor(
+        &self,
+        challenge_token: String,
+        code: String,
+    ) -> Result<(String, TokenPair), ServiceError> {
+        let token_hash = hash_refresh_token(&challenge_token);
+        let username = self
+            .store
+            .attempt_challenge(&token_hash, clock::now(), MAX_CHALLENGE_ATTEMPTS)
+            .await?
+            .ok_or(ServiceError::InvalidSecondFactor)?;
+        self.check_second_factor(&username, &code).await?;
+        if !self
+            .store
+            .consume_challenge(&token_hash, clock::now())
+            .await?
+        {
+            return Err(ServiceError::InvalidSecondFactor);
+        }
+        let tokens = self.issue_tokens(&username).await?;
+        Ok((username, tokens))
+    }
+
+    /// Fails with `InvalidSecondFactor` unless `code` is a TOTP code of
+    /// `username` that was not used yet, or one of their unused recovery
+    /// codes. The code is used up on success.
+    async fn check_second_factor(&self, username: &str, code: &str) -> Result<(), ServiceError> {
+        let secret = match self.store.find_user(username).await? {
+            Some(User {
+                totp_secret: Some(secret),
+                totp_enabled: true,
+                ..
+            }) => secret,
+            _ => {
+                info!(
+                    "Rejected second factor of {}, TOTP is not enabled",
+                    username
+                );
+                return Err(ServiceError::InvalidSecondFactor);
+            }
+        };
+        let now = clock::now();
+        if is_totp_code(code) {
+            let step = match verify_totp_code(&secret, code, now) {
+                Some(step) => step,
+                None => {
+                    info!("Rejected second factor of {}, wrong TOTP code", username);
+                    return Err(ServiceError::InvalidSecondFactor);
+                }
+            };
+            if !self.store.use_totp_step(username, step).await? {
+                info!("Rejected second factor of {}, replayed TOTP code", username);
+                return Err(ServiceError::InvalidSecondFactor);
+            }
+        } else if self
+            .store
+            .use_recovery_code(username, &hash_recovery_code(code), now)
+            .await?
+        {
+            info!("{} used a recovery code", username);
+        } else {
+            info!(
+                "Rejected second factor of {}, wrong recovery code",
+                username
+            );
+            return Err(ServiceError::InvalidSecondFactor);
+        }
+        Ok(())
+    }
+
+    /// The user `username` when `password` and `pin` are theirs, failing with
+    /// `InvalidCredentials` otherwise. Users with a password give it along with
+    /// their PIN when they have one, users created before passwords give their
+    /// PIN alone. Legacy plaintext PINs are rehashed.
+    async fn check_credentials(
+        &self,
+        username: &str,
+        password: &str,
+        pin: i32,
+    ) -> Result<User, ServiceError> {
+        let user = match self.store.find_user(username).await? {
+            Some(user) => user,
+            None => {
+                // Checking against the decoy takes as long as a wrong
+                // password, so unknown usernames cannot be told apart.
+                verify_password(self.decoy_hash.to_string(), password.to_string()).await;
+                info!("Rejected credentials of {}, no such user", username);
+                return Err(ServiceError::InvalidCredentials);
+            }
+        };
+        if let Some(hash) = &user.password {
+            if !verify_password(hash.clone(), password.to_string()).await {
+                info!("Rejected credentials of {}, wrong password", username);
+                return Err(ServiceError::InvalidCredentials);
+            }
+            if user.pin.is_empty() {
+                return Ok(user);
+            }
+        }
+        let pin = pin.to_string();
+        match verify_pin(user.pin.clone(), pin.clone(), self.decoy_hash.to_string()).await {
+            PinMatch::Hashed => {}
+            PinMatch::Legacy => self.rehash_pin(&user.username, pin).await,
+            PinMatch::Mismatch => {
+                info!("Rejected credentials of {}, wrong PIN", username);
+                return Err(ServiceError::InvalidCredentials);
+            }
+        }
+        Ok(user)
+    }
+
+    /// Signs an access token and persists it along with a new refresh token for `username`.
+    async fn issue_tokens(&self, username: &str) -> Result<TokenPair, ServiceError> {
+        let (access_token, claims) =
+            issue_access_token(username).map_err(ServiceError::Internal)?;
+        self.store
+            .insert_access_token(username, &claims.jti, claims.expires_at)
+            .await?;
+
+        let refresh_token = generate_refresh_token();
+        let now = clock::now();
+        self.store
+            .insert_refresh_token(
+                username,
+                &hash_refresh_token(&refresh_token),
+                now,
+                now + refresh_token_ttl(),
+            )
+            .await?;
+        Ok(TokenPair {
+            access_token,
+            refresh_token,
+            expires_in: access_token_ttl(),
+        })
+    }
+
+    /// Rotates a refresh token: the presented token is revoked and a new pair is issued.
+    /// Fails with `InvalidToken` when the token is unknown, expired or already revoked.
+    pub async fn refresh_token(&self, refresh_token: String) -> Result<TokenPair, ServiceError> {
+        let token_hash = hash_refresh_token(&refresh_token);
+        match self
+            .store
+            .consume_refresh_token(&token_hash, clock::now())
+            .await?
+        {
+            Some(username) => self.issue_tokens(&username).await,
+            None => Err(ServiceError::InvalidToken),
+        }
+    }
+
+    /// Replaces a legacy plaintext PIN with its hash. On failure the PIN stays
+    /// in plaintext until the next sign in tries again.
+    async fn rehash_pin(&self, username: &str, pin: String) {
+        let hash = match hash_pin(pin).await {
+            Ok(hash) => hash,
+            Err(e) => {
+                error!("Unable to rehash legacy PIN for {}: {}", username, e);
+                return;
+            }
+        };
+        match self.store.update_pin(username, &hash).await {
+            Ok(_) => info!("Rehashed legacy PIN for {}", username),
+            Err(e) => error!("Unable to store rehashed PIN for {} {:?}", username, e),
+        }
+    }
+
+    /// Signs up a user with a password and, unless `pin` is 0, a PIN.
+    pub async fn sign_up(&self, req: SignUpRequest) -> Result<User, ServiceError> {
+        let pin_hash = match req.pin {
+            0 => String::new(),
+            pin => hash_pin(pin.to_string())
+                .await
+                .map_err(ServiceError::Internal)?,
+        };
+        let password_hash = hash_password(req.password)
+            .await
+            .map_err(ServiceError::Internal)?;
+        self.store
+            .create_user(&req.username, &pin_hash, Some(&password_hash))
+            .await
+    }
+
+    /// Revokes the access token `jti` and, when given, the caller's refresh token.
+    pub async fn sign_out(
+        &self,
+        username: String,
+        jti: String,
+        refresh_token: Option<String>,
+    ) -> Result<(), ServiceError> {
+        let now = clock::now();
+        self.store.revoke_access_token(&username, &jti, now).await?;
+        if let Some(refresh_token) = refresh_token {
+            self.store
+                .revoke_refresh_token(&username, &hash_refresh_token(&refresh_token), now)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Revokes every live access and refresh token of `username`, returning the
+    /// revoked access tokens so the caller can update the revocation cache.
+    pub async fn sign_out_everywhere(
+        &self,
+        username: String,
+    ) -> Result<Vec<RevokedTokenDb>, ServiceError> {
+        self.store.revoke_all_tokens(&username, clock::now()).await
+    }
+
+    /// Revokes every token of `username` and issues them a new pair, returning
+    /// it along with the revoked access tokens.
+    async fn reissue_tokens(
+        &self,
+        username: &str,
+    ) -> Result<(TokenPair, Vec<RevokedTokenDb>), ServiceError> {
+        let revoked = self.store.revoke_all_tokens(username, clock::now()).await?;
+        Ok((self.issue_tokens(username).await?, revoked))
+    }
+
+    /// Replaces the PIN of `username` once their credentials are checked,
+    /// signing them out everywhere. A `new_pin` of 0 removes the PIN of users
+    /// with a password.
+    pub async fn change_pin(
+        &self,
+        username: String,
+        password: String,
+        current_pin: i32,
+        new_pin: i32,
+    ) -> Result<(TokenPair, Vec<RevokedTokenDb>), ServiceError> {
+        let user = self
+            .check_credentials(&username, &password, current_pin)
+            .await?;
+        let hash = match new_pin {
+            0 if user.password.is_none() => {
+                return Err(ServiceError::invalid_argument(
+                    "new_pin",
+                    "Accounts without a password need a PIN, set a password first",
+                ))
+            }
+            0 => String::new(),
+            new_pin => hash_pin(new_pin.to_string())
+                .await
+                .map_err(ServiceError::Internal)?,
+        };
+        self.store.update_pin(&username, &hash).await?;
+        self.reissue_tokens(&username).await
+    }
+
+    /// Replaces the password of `username` once their credentials are
+    /// checked, signing them out everywhere. Users created before passwords
+    /// set their first one this way.
+    pub async fn change_password(
+        &self,
+        username: String,
+        current_password: String,
+        pin: i32,
+        new_password: String,
+    ) -> Result<(TokenPair, Vec<RevokedTokenDb>), ServiceError> {
+        self.check_credentials(&username, &current_password, pin)
+            .await?;
+        let hash = hash_password(new_password)
+            .await
+            .map_err(ServiceError::Internal)?;
+        self.store.update_password(&username, &hash).await?;
+        self.reissue_tokens(&username).await
+    }
+
+    /// Renames `username`, signing them out everywhere. Returns a token pair
+    /// for the new name along with the revoked access tokens.
+    pub async fn change_username(
+        &self,
+        username: String,
+        new_username: String,
+    ) -> Result<(TokenPair, Vec<RevokedTokenDb>), ServiceError> {
+        let revoked = self
+            .store
+            .rename_user(&username, &new_username, clock::now())
+            .await?;
+        Ok((self.issue_tokens(&new_username).await?, revoked))
+    }
+
+    /// Deletes the account of `username` once their credentials are checked. Their todos,
+    /// projects and tags are deleted, and the history they left on the todos
+    /// of other users is anonymised. Returns the revoked access tokens.
+    pub async fn delete_account(
+        &self,
+        username: String,
+        password: String,
+        pin: i32,
+    ) -> Result<Vec<RevokedTokenDb>, ServiceError> {
+        self.check_credentials(&username, &password, pin).await?;
+        // The members of the projects go along with them, so they are loaded
+        // first to tell them about the deleted todos.
+        let mut members = HashMap::new();
+        for project in self.store.get_projects(&username).await? {
+            if project.owner == username {
+                members.insert(project.id, self.store.get_members(project.id).await?);
+            }
+        }
+        let tombstone = format!("deleted:{}", Uuid::new_v4().to_simple());
+        let (revoked, deleted) = self
+            .store
+            .delete_user(&username, &tombstone, clock::now())
+            .await?;
+        for todo_item in deleted {
+            let project_members = members.get(&todo_item.project_id).into_iter().flatten();
+            for member in project_members.filter(|member| member.username != username) {
+                self.events
+                    .publish(&member.username, TodoEventKind::Deleted, todo_item.clone());
+            }
+            self.events
+                .publish(&username, TodoEventKind::Deleted, todo_item);
+        }
+        Ok(revoked)
+    }
+
+    /// Starts the TOTP enrollment of `username` once their credentials are
+    /// checked, returning the new secret and its `otpauth://` URI. The secret
+    /// is only used for sign in once confirmed with `confirm_totp`; enrolling
+    /// again before that replaces it.
+    pub async fn enroll_totp(
+        &self,
+        username: String,
+        password: String,
+        pin: i32,
+    ) -> Result<(String, String), ServiceError> {
+        let user = self.check_credentials(&username, &password, pin).await?;
+        let already_enabled =
+            || ServiceError::AlreadyExists(String::from("Two-factor authentication"));
+        if user.totp_enabled {
+            return Err(already_enabled());
+        }
+        let secret = generate_totp_secret();
+        if !self.store.set_totp_secret(&username, &secret).await? {
+            return Err(already_enabled());
+        }
+        let uri = otpauth_uri(&secret, &username);
+        Ok((secret, uri))
+    }
+
+    /// Enables the pending TOTP of `username` once `code` proves their
+    /// authenticator has the secret, returning their new recovery codes. The
+    /// codes are only stored hashed and cannot be shown again.
+    pub async fn confirm_totp(
+        &self,
+        username: String,
+        code: String,
+    ) -> Result<Vec<String>, ServiceError> {
+        let pending = || ServiceError::NotFound(String::from("Pending two-factor enrollment"));
+        let secret = match self.store.find_user(&username).await? {
+            Some(User {
+                totp_secret: Some(secret),
+                totp_enabled: false,
+                ..
+            }) => secret,
+            _ => return Err(pending()),
+        };
+        let step = verify_totp_code(&secret, &code, clock::now())
+            .ok_or_else(|| ServiceError::invalid_argument("code", "Invalid TOTP code"))?;
+        let recovery_codes = generate_recovery_codes();
+        let hashes = recovery_codes
+            .iter()
+            .map(|code| hash_recovery_code(code))
+            .collect::<Vec<_>>();
+        if !self.store.enable_totp(&username, step, &hashes).await? {
+            return Err(pending());
+        }
+        info!("Enabled two-factor authentication of {}", username);
+        Ok(recovery_codes)
+    }
+
+    /// Disables the TOTP of `username` once their credentials and a TOTP or
+    /// recovery code are checked, dropping their recovery codes.
+    pub async fn disable_totp(
+        &self,
+        username: String,
+        password: String,
+        pin: i32,
+        code: String,
+    ) -> Result<(), ServiceError> {
+        let user = self.check_credentials(&username, &password, pin).await?;
+        if !user.totp_enabled {
+            return Err(ServiceError::NotFound(String::from(
+                "Two-factor authentication",
+            )));
+        }
+        self.check_second_factor(&username, &code).await?;
+        self.store.disable_totp(&username).await?;
+        info!("Disabled two-factor authentication of {}", username);
+        Ok(())
+    }
+
+    /// Todos `username` owns or can see through a project.
+    pub async fn get_todos(
+        &self,
+        username: String,
+        query: TodoQuery,
+        resp: MpscSender<Result<TodoItem, ServiceError>>,
+    ) {
+        self.store.get_todos(&username, &query, resp).await
+    }
+
+    /// Owner of a todo `username` has at least the `required` role on, or
+    /// `None` when they cannot see it at all.
+    async fn todo_owner(
+        &self,
+        username: &str,
+        id: u32,
+        required: ProjectRole,
+    ) -> Result<Option<String>, ServiceError> {
+        let access = match self.store.todo_access(username, id).await? {
+            Some(access) => access,
+            None => return Ok(None),
+        };
+        check_role(
+            access.role(username),
+            required,
+            &format!("Todo item {}", id),
+        )?;
+        Ok(Some(access.owner))
+    }
+
+    /// Like `todo_owner`, failing with `NotFound` when the todo cannot be seen
+    /// and with `InvalidArgument` when it belongs to another owner than `owner`.
+    async fn check_related_todo(
+        &self,
+        username: &str,
+        owner: &str,
+        field: &str,
+        id: u32,
+    ) -> Result<(), ServiceError> {
+        match self.todo_owner(username, id, ProjectRole::Viewer).await? {
+            Some(related_owner) if related_owner == owner => Ok(()),
+            Some(_) => Err(ServiceError::invalid_argument(
+                field,
+                "Todos of different owners cannot be related",
+            )),
+            None => Err(ServiceError::NotFound(format!("Todo item {}", id))),
+        }
+    }
+
+    /// A project `username` has at least the `required` role in.
+    async fn project_access(
+        &self,
+        username: &str,
+        project_id: u32,
+        required: ProjectRole,
+    ) -> Result<ProjectDb, ServiceError> {
+        let project = self
+            .store
+            .get_project(username, project_id)
+            .await?
+            .ok_or_else(|| ServiceError::NotFound(format!("Project {}", project_id)))?;
+        check_role(
+            Some(project.role),
+            required,
+            &format!("Project {}", project_id),
+        )?;
+        Ok(project)
+    }
+
+    /// Users who see a todo of `owner`: they and, for todos of a shared
+    /// project, its other members.
+    async fn audience(&self, owner: &str, todo_item: &TodoItem) -> Vec<String> {
+        let mut usernames = vec![owner.to_string()];
+        if todo_item.project_id != 0 {
+            match self.store.get_members(todo_item.project_id).await {
+                Ok(members) => usernames.extend(
+                    members
+                        .into_iter()
+                        .map(|member| member.username)
+                        .filter(|username| username != owner),
+                ),
+                Err(e) => error!(
+                    "Unable to load members of project {} {:?}",
+                    todo_item.project_id, e
+                ),
+            }
+        }
+        usernames
+    }
+
+    /// Publishes a change of a todo of `owner` to its audience.
+    async fn publish(&self, owner: &str, kind: TodoEventKind, todo_item: &TodoItem) {
+        for username in self.audience(owner, todo_item).await {
+            self.events.publish(&username, kind, todo_item.clone());
+        }
+    }
+
+    pub async fn get_todo(
+        &self,
+        username: String,
+        id: u32,
+    ) -> Result<Option<TodoItem>, ServiceError> {
+        match self.todo_owner(&username, id, ProjectRole::Viewer).await? {
+            Some(owner) => self.store.get_todo(&owner, id).await,
+            None => Ok(None),
+        }
+    }
+
+    /// Creates a todo. Todos created in a shared project belong to the owner
+    /// of the project.
+    pub async fn create_todo(
+        &self,
+        username: String,
+        req: CreateTodoRequest,
+    ) -> Result<TodoItem, ServiceError> {
+        let owner = if req.project_id != 0 {
+            self.project_access(&username, req.project_id, ProjectRole::Editor)
+                .await?
+                .owner
+        } else {
+            username.clone()
+        };
+        if req.parent_id != 0 {
+            self.check_related_todo(&username, &owner, "parent_id", req.parent_id)
+                .await?;
+        }
+        let todo_item = self
+            .store
+            .create_todo(&owner, TodoItemDb::new(req, clock::now()))
+            .await?;
+        self.publish(&owner, TodoEventKind::Created, &todo_item)
+            .await;
+        self.roll_up(&owner, &username, todo_item.parent_id).await;
+        Ok(todo_item)
+    }
+
+    /// Imports `todos` in one transaction.
+    pub async fn import_todos(
+        &self,
+        username: String,
+        todos: Vec<TodoItem>,
+    ) -> Result<Vec<TodoItem>, ServiceError> {
+        let now = clock::now();
+        let todos = todos
+            .into_iter()
+            .map(|todo| TodoItemDb::imported(todo, now))
+            .collect();
+        let imported = self.store.import_todos(&username, todos).await?;
+        for todo_item in &imported {
+            self.publish(&username, TodoEventKind::Created, todo_item)
+                .await;
+        }
+        Ok(imported)
+    }
+
+    /// Replaces the fields of a todo. A change of status must be allowed by
+    /// the todo lifecycle.
+    pub async fn update_todo(
+        &self,
+        username: String,
+        req: UpdateTodoRequest,
+    ) -> Result<Option<TodoItem>, ServiceError> {
+        let id = req.id;
+        let owner = match self.todo_owner(&username, id, ProjectRole::Editor).await? {
+            Some(owner) => owner,
+            None => return Ok(None),
+        };
+        let current = match self.store.get_todo(&owner, id).await? {
+            Some(current) => current,
+            None => return Ok(None),
+        };
+        if req.status != current.status {
+            check_transition(current.status, req.status)?;
+        }
+        let todo_item = self
+            .store
+            .update_todo(&owner, req, current.status, clock::now())
+            .await?
+            .ok_or_else(|| ServiceError::Conflict(format!("Todo item {}", id)))?;
+        let todo_item = if todo_item.status == TodoStatus::Completed as i32
+            && current.status != todo_item.status
+        {
+            self.recur(&owner, todo_item).await
+        } else {
+            todo_item
+        };
+        self.publish(&owner, TodoEventKind::Updated, &todo_item)
+            .await;
+        self.roll_up(&owner, &username, todo_item.parent_id).await;
+        Ok(Some(todo_item))
+    }
+
+    /// Moves a todo to `status` on behalf of `username`, returning it along
+    /// with the recorded transition.
+    pub async fn transition_todo(
+        &self,
+        username: String,
+        id: u32,
+        status: i32,
+    ) -> Result<Option<(TodoItem, TodoTransitionDb)>, ServiceError> {
+        let owner = match self.todo_owner(&username, id, ProjectRole::Editor).await? {
+            Some(owner) => owner,
+            None => return Ok(None),
+        };
+        let current = match self.store.get_todo(&owner, id).await? {
+            Some(current) => current,
+            None => return Ok(None),
+        };
+        check_transition(current.status, status)?;
+        let transition = TodoTransitionDb {
+            todo_id: id,
+            from_status: current.status,
+            to_status: status,
+            changed_by: username.clone(),
+            changed_at: clock::now(),
+        };
+        let todo_item = self
+            .store
+            .transition_todo(&owner, &transition)
+            .await?
+            .ok_or_else(|| ServiceError::Conflict(format!("Todo item {}", id)))?;
+        let todo_item = if status == TodoStatus::Completed as i32 {
+            self.recur(&owner, todo_item).await
+        } else {
+            todo_item
+        };
+        self.publish(&owner, TodoEventKind::Updated, &todo_item)
+            .await;
+        self.roll_up(&owner, &username, todo_item.parent_id).await;
+        Ok(Some((todo_item, transition)))
+    }
+
+    /// Deletes a todo. Its subtasks become top-level todos.
+    pub async fn delete_todo(&self, username: String, id: u32) -> Result<bool, ServiceError> {
+        let owner = match self.todo_owner(&username, id, ProjectRole::Editor).await? {
+            Some(owner) => owner,
+            None => return Ok(false),
+        };
+        let current = match self.store.get_todo(&owner, id).await? {
+            Some(current) => current,
+            None => return Ok(false),
+        };
+        let deleted = self.store.delete_todo(&owner, id).await?;
+        if deleted {
+            let todo_item = TodoItem {
+                id,
+                project_id: current.project_id,
+                ..Default::default()
+            };
+            self.publish(&owner, TodoEventKind::Deleted, &todo_item)
+                .await;
+            self.roll_up(&owner, &username, current.parent_id).await;
+        }
+        Ok(deleted)
+    }
+
+    /// Adds normalized `tags` to a todo. Tags belong to the owner of the todo.
+    pub async fn add_tags(
+        &self,
+        username: String,
+        id: u32,
+        tags: Vec<String>,
+    ) -> Result<Option<TodoItem>, ServiceError> {
+        let owner = match self.todo_owner(&username, id, ProjectRole::Editor).await? {
+            Some(owner) => owner,
+            None => return Ok(None),
+        };
+        let todo_item = self.store.add_tags(&owner, id, &tags, clock::now()).await?;
+        if let Some(todo_item) = &todo_item {
+            self.publish(&owner, TodoEventKind::Updated, todo_item)
+                .await;
+        }
+        Ok(todo_item)
+    }
+
+    /// Removes normalized `tags` from a todo.
+    pub async fn remove_tags(
+        &self,
+        username: String,
+        id: u32,
+        tags: Vec<String>,
+    ) -> Result<Option<TodoItem>, ServiceError> {
+        let owner = match self.todo_owner(&username, id, ProjectRole::Editor).await? {
+            Some(owner) => owner,
+            None => return Ok(None),
+        };
+        let todo_item = self
+            .store
+            .remove_tags(&owner, id, &tags, clock::now())
+            .await?;
+        if let Some(todo_item) = &todo_item {
+            self.publish(&owner, TodoEventKind::Updated, todo_item)
+                .await;
+        }
+        Ok(todo_item)
+    }
+
+    pub async fn list_tags(&self, username: String) -> Result<Vec<TagDb>, ServiceError> {
+        self.store.list_tags(&username).await
+    }
+
+    /// Moves a todo to `position` within a project, or within the inbox when
+    /// `project_id` is 0. Only the owner of a todo can move it to their inbox,
+    /// and todos cannot move to a project of another owner.
+    pub async fn move_todo(
+        &self,
+        username: String,
+        id: u32,
+        project_id: u32,
+        position: u32,
+    ) -> Result<Option<TodoItem>, ServiceError> {
+        let owner = match self.todo_owner(&username, id, ProjectRole::Editor).await? {
+            Some(owner) => owner,
+            None => return Ok(None),
+        };
+        if project_id == 0 {
+            if owner != username {
+                return Err(ServiceError::PermissionDenied {
+                    resource: format!("Todo item {}", id),
+                    role: String::from("Owner"),
+                });
+            }
+        } else {
+            let project = self
+                .project_access(&username, project_id, ProjectRole::Editor)
+                .await?;
+            if project.owner != owner {
+                return Err(ServiceError::invalid_argument(
+                    "project_id",
+                    "Todos cannot be moved to a project of another owner",
+                ));
+            }
+        }
+        let project_id = Some(project_id).filter(|project_id| *project_id != 0);
+        let todo_item = self
+            .store
+            .move_todo(&owner, id, project_id, position, clock::now())
+            .await?;
+        if let Some(todo_item) = &todo_item {
+            self.publish(&owner, TodoEventKind::Updated, todo_item)
+                .await;
+        }
+        Ok(todo_item)
+    }
+
+    pub async fn create_project(
+        &self,
+        username: String,
+        name: String,
+    ) -> Result<ProjectDb, ServiceError> {
+        self.store
+            .create_project(
+                &username,
+                ProjectDb::new(name, username.clone(), clock::now()),
+            )
+            .await
+    }
+
+    /// Projects `username` is a member of, including their own.
+    pub async fn get_projects(&self, username: String) -> Result<Vec<ProjectDb>, ServiceError> {
+        self.store.get_projects(&username).await
+    }
+
+    pub async fn rename_project(
+        &self,
+        username: String,
+        id: u32,
+        name: String,
+    ) -> Result<Option<ProjectDb>, ServiceError> {
+        let project = self
+            .project_access(&username, id, ProjectRole::Owner)
+            .await?;
+        match self
+            .store
+            .rename_project(&project.owner, id, &name, clock::now())
+            .await?
+        {
+            Some(_) => self.store.get_project(&username, id).await,
+            None => Ok(None),
+        }
+    }
+
+    /// Deletes a project, moving its todos to the inbox of its owner. The
+    /// other members see the todos deleted.
+    pub async fn delete_project(&self, username: String, id: u32) -> Result<bool, ServiceError> {
+        let project = match self.store.get_project(&username, id).await? {
+            Some(project) => project,
+            None => return Ok(false),
+        };
+        check_role(
+            Some(project.role),
+            ProjectRole::Owner,
+            &format!("Project {}", id),
+        )?;
+        // Members lose sight of the todos along with the project, so they are
+        // loaded before it goes.
+        let members = self.store.get_members(id).await?;
+        let moved = match self
+            .store
+            .delete_project(&project.owner, id, clock::now())
+            .await?
+        {
+            Some(moved) => moved,
+            None => return Ok(false),
+        };
+        for todo_item in &moved {
+            self.publish(&project.owner, TodoEventKind::Updated, todo_item)
+                .await;
+            let deleted = TodoItem {
+                id: todo_item.id,
+                project_id: id,
+                ..Default::default()
+            };
+            for member in members
+                .iter()
+                .filter(|member| member.username != project.owner)
+            {
+                self.events
+                    .publish(&member.username, TodoEventKind::Deleted, deleted.clone());
+            }
+        }
+        Ok(true)
+    }
+
+    /// Members of a project `username` is a member of.
+    pub async fn get_members(
+        &self,
+        username: String,
+        project_id: u32,
+    ) -> Result<Vec<MemberDb>, ServiceError> {
+        self.project_access(&username, project_id, ProjectRole::Viewer)
+            .await?;
+        self.store.get_members(project_id).await
+    }
+
+    /// Invites `invitee` to a project `username` owns.
+    pub async fn invite_member(
+        &self,
+        username: String,
+        project_id: u32,
+        invitee: String,
+        role: i32,
+    ) -> Result<InvitationDb, ServiceError> {
+        self.project_access(&username, project_id, ProjectRole::Owner)
+            .await?;
+        let members = self.store.get_members(project_id).await?;
+        if members.iter().any(|member| member.username == invitee) {
+            return Err(ServiceError::AlreadyExists(format!("Member {}", invitee)));
+        }
+        self.store
+            .create_invitation(project_id, &invitee, role, &username, clock::now())
+            .await
+    }
+
+    pub async fn get_invitations(
+        &self,
+        username: String,
+    ) -> Result<Vec<InvitationDb>, ServiceError> {
+        self.store.get_invitations(&username).await
+    }
+
+    /// Joins the project of an invitation of `username`, returning it.
+    pub async fn accept_invitation(
+        &self,
+        username: String,
+        id: u32,
+    ) -> Result<Option<ProjectDb>, ServiceError> {
+        match self.store.accept_invitation(&username, id).await? {
+            Some(project_id) => self.store.get_project(&username, project_id).await,
+            None => Ok(None),
+        }
+    }
+
+    pub async fn decline_invitation(
+        &self,
+        username: String,
+        id: u32,
+    ) -> Result<bool, ServiceError> {
+        self.store.decline_invitation(&username, id).await
+    }
+
+    /// Changes the role of a member of a project `username` owns. The owner
+    /// of the project always keeps the owner role.
+    pub async fn set_member_role(
+        &self,
+        username: String,
+        project_id: u32,
+        member: String,
+        role: i32,
+    ) -> Result<Option<MemberDb>, ServiceError> {
+        let project = self
+            .project_access(&username, project_id, ProjectRole::Owner)
+            .await?;
+        if member == project.owner {
+            return Err(ServiceError::invalid_argument(
+                "username",
+                "The role of the project owner cannot be changed",
+            ));
+        }
+        if !self
+            .store
+            .set_member_role(project_id, &member, role)
+            .await?
+        {
+            return Ok(None);
+        }
+        Ok(Some(MemberDb {
+            username: member,
+            role,
+        }))
+    }
+
+    /// Removes a member from a project. Owners can remove anyone but the
+    /// owner of the project, and every member can leave. The removed member
+    /// sees the todos of the project deleted.
+    pub async fn remove_member(
+        &self,
+        username: String,
+        project_id: u32,
+        member: String,
+    ) -> Result<bool, ServiceError> {
+        let required = if member == username {
+            ProjectRole::Viewer
+        } else {
+            ProjectRole::Owner
+        };
+        let project = self.project_access(&username, project_id, required).await?;
+        if member == project.owner {
+            return Err(ServiceError::invalid_argument(
+                "username",
+                "The project owner cannot be removed",
+            ));
+        }
+        let todo_ids = match self.store.remove_member(project_id, &member).await? {
+            Some(todo_ids) => todo_ids,
+            None => return Ok(false),
+        };
+        for id in todo_ids {
+            let todo_item = TodoItem {
+                id,
+                project_id,
+                ..Default::default()
+            };
+            self.events
+                .publish(&member, TodoEventKind::Deleted, todo_item);
+        }
+        Ok(true)
+    }
+
+    /// Completes `parent_id` once all of its subtasks are done and reopens it
+    /// when one of them is not, then does the same for its own parent.
+    /// Changes the lifecycle does not allow are skipped. On failure the parents
+    /// keep their status, the subtask change being saved already.
+    async fn roll_up(&self, owner: &str, changed_by: &str, parent_id: u32) {
+        if let Err(e) = self.try_roll_up(owner, changed_by, parent_id).await {
+            error!("Unable to roll up subtasks of todo {} {:?}", parent_id, e);
+        }
+    }
+
+    async fn try_roll_up(
+        &self,
+        owner: &str,
+        changed_by: &str,
+        mut parent_id: u32,
+    ) -> Result<(), ServiceError> {
+        let mut seen = HashSet::new();
+        while parent_id != 0 && seen.insert(parent_id) {
+            let parent = match self.store.get_todo(owner, parent_id).await? {
+                Some(parent) => parent,
+                None => break,
+            };
+            let subtasks = self.store.get_subtasks(owner, parent_id).await?;
+            if subtasks.is_empty() {
+                break;
+            }
+            let all_done = subtasks.iter().all(|subtask| is_done(subtask.status));
+            let status = if all_done && !is_done(parent.status) {
+                TodoStatus::Completed as i32
+            } else if !all_done && parent.status == TodoStatus::Completed as i32 {
+                TodoStatus::Active as i32
+            } else {
+                break;
+            };
+            if check_transition(parent.status, status).is_err() {
+                break;
+            }
+            let transition = TodoTransitionDb {
+                todo_id: parent_id,
+                from_status: parent.status,
+                to_status: status,
+                changed_by: changed_by.to_string(),
+                changed_at: clock::now(),
+            };
+            let todo_item = match self.store.transition_todo(owner, &transition).await? {
+                Some(todo_item) if status == TodoStatus::Completed as i32 => {
+                    self.recur(owner, todo_item).await
+                }
+                Some(todo_item) => todo_item,
+                None => break,
+            };
+            self.publish(owner, TodoEventKind::Updated, &todo_item)
+                .await;
+            parent_id = todo_item.parent_id;
+        }
+        Ok(())
+    }
+
+    /// Creates the next occurrence of a recurring todo of `owner`, moving the
+    /// rule to it, and returns the todo as it is afterwards. On failure the todo
+    /// keeps its rule, which the recurrence sweep retries while it is open.
+    async fn recur(&self, owner: &str, todo_item: TodoItem) -> TodoItem {
+        if todo_item.recurrence.is_empty() {
+            return todo_item;
+        }
+        match self.try_recur(owner, &todo_item).await {
+            Ok(Some(current)) => current,
+            Ok(None) => todo_item,
+            Err(e) => {
+                error!(
+                    "Unable to create next occurrence of todo {} {:?}",
+                    todo_item.id, e
+                );
+                todo_item
+            }
+        }
+    }
+
+    async fn try_recur(
+        &self,
+        owner: &str,
+        todo_item: &TodoItem,
+    ) -> Result<Option<TodoItem>, ServiceError> {
+        let now = clock::now();
+        let rule = Recurrence::parse(&todo_item.recurrence)?;
+        let due_at = to_seconds(&todo_item.due_at);
+        let next = due_at
+            .and_then(|due_at| rule.next(due_at, now))
+            .map(|(next_due_at, rule)| {
+                // Keeps the reminder as long before the due date.
+                let reminder_at = to_seconds(&todo_item.reminder_at)
+                    .zip(due_at)
+                    .map(|(reminder_at, due_at)| next_due_at - (due_at - reminder_at));
+                let req = CreateTodoRequest {
+                    title: todo_item.title.clone(),
+                    description: todo_item.description.clone(),
+                    notes: todo_item.notes.clone(),
+                    status: TodoStatus::Active as i32,
+                    priority: todo_item.priority,
+                    due_at: Some(to_timestamp(next_due_at)),
+                    project_id: todo_item.project_id,
+                    parent_id: todo_item.parent_id,
+                    recurrence: rule.to_string(),
+                    reminder_at: reminder_at.map(to_timestamp),
+                };
+                TodoItemDb::new(req, now)
+            });
+        let created = self
+            .store
+            .recur_todo(owner, todo_item.id, &todo_item.recurrence, next, now)
+            .await?;
+        if let Some(created) = &created {
+            self.publish(owner, TodoEventKind::Created, created).await;
+        }
+        self.store.get_todo(owner, todo_item.id).await
+    }
+
+    /// Creates the next occurrence of every open recurring todo that is
+    /// overdue, returning how many of them there were. The overdue todos stay
+    /// open and the occurrences they missed are skipped.
+    pub async fn materialize_recurrences(&self) -> Result<usize, ServiceError> {
+        let overdue = self.store.get_overdue_recurrences(clock::now()).await?;
+        for (owner, id) in &overdue {
+            if let Some(todo_item) = self.store.get_todo(owner, *id).await? {
+                let todo_item = self.recur(owner, todo_item).await;
+                self.publish(owner, TodoEventKind::Updated, &todo_item)
+                    .await;
+            }
+        }
+        Ok(overdue.len())
+    }
+
+    /// The reminders that are due and have not been delivered, along with the
+    /// users to remind. They stay due until `mark_reminder_delivered`.
+    pub async fn due_reminders(&self) -> Result<Vec<DueReminder>, ServiceError> {
+        let mut due = vec![];
+        for (owner, id, reminder_at) in self.store.get_due_reminders(clock::now()).await? {
+            if let Some(todo_item) = self.store.get_todo(&owner, id).await? {
+                due.push(DueReminder {
+                    usernames: self.audience(&owner, &todo_item).await,
+                    owner,
+                    reminder: Reminder {
+                        todo: Some(todo_item),
+                        reminder_at: Some(to_timestamp(reminder_at)),
+                    },
+                });
+            }
+        }
+        Ok(due)
+    }
+
+    /// Records that a due reminder has been delivered. Returns `false` when it
+    /// already was, or when the reminder of the todo has changed since.
+    pub async fn mark_reminder_delivered(&self, due: &DueReminder) -> Result<bool, ServiceError> {
+        let id = due.reminder.todo.as_ref().map_or(0, |todo| todo.id);
+        let reminder_at = to_seconds(&due.reminder.reminder_at).unwrap_or_default();
+        self.store
+            .mark_reminder_delivered(&due.owner, id, reminder_at)
+            .await
+    }
+
+    /// Makes a todo a subtask of `parent_id`, or a top-level todo when 0, and
+    /// rolls the change up to its previous and new parents.
+    pub async fn set_parent(
+        &self,
+        username: String,
+        id: u32,
+        parent_id: u32,
+    ) -> Result<Option<TodoItem>, ServiceError> {
+        let owner = match self.todo_owner(&username, id, ProjectRole::Editor).await? {
+            Some(owner) => owner,
+            None => return Ok(None),
+        };
+        let current = match self.store.get_todo(&owner, id).await? {
+            Some(current) => current,
+            None => return Ok(None),
+        };
+        if parent_id != 0 {
+            self.check_related_todo(&username, &owner, "parent_id", parent_id)
+                .await?;
+            let parents = self.store.get_parent_links(&owner).await?;
+            check_parent(id, parent_id, &parents)?;
+        }
+        let todo_item = self
+            .store
+            .set_parent(
+                &owner,
+                id,
+                Some(parent_id).filter(|parent_id| *parent_id != 0),
+                clock::now(),
+            )
+            .await?;
+        if let Some(todo_item) = &todo_item {
+            self.publish(&owner, TodoEventKind::Updated, todo_item)
+                .await;
+            self.roll_up(&owner, &username, current.parent_id).await;
+            self.roll_up(&owner, &username, parent_id).await;
+        }
+        Ok(todo_item)
+    }
+
+    /// Records that a todo is blocked by another todo of the same owner.
+    pub async fn link_todos(
+        &self,
+        username: String,
+        id: u32,
+        blocked_by_id: u32,
+    ) -> Result<Option<TodoItem>, ServiceError> {
+        let owner = match self.todo_owner(&username, id, ProjectRole::Editor).await? {
+            Some(owner) => owner,
+            None => return Ok(None),
+        };
+        self.check_related_todo(&username, &owner, "blocked_by_id", blocked_by_id)
+            .await?;
+        let dependencies = self.store.get_dependency_links(&owner).await?;
+        check_dependency(id, blocked_by_id, &dependencies)?;
+        let todo_item = self
+            .store
+            .link_todos(&owner, id, blocked_by_id, clock::now())
+            .await?;
+        if let Some(todo_item) = &todo_item {
+            self.publish(&owner, TodoEventKind::Updated, todo_item)
+                .await;
+        }
+        Ok(todo_item)
+    }
+
+    pub async fn unlink_todos(
+        &self,
+        username: String,
+        id: u32,
+        blocked_by_id: u32,
+    ) -> Result<Option<TodoItem>, ServiceError> {
+        let owner = match self.todo_owner(&username, id, ProjectRole::Editor).await? {
+            Some(owner) => owner,
+            None => return Ok(None),
+        };
+        let todo_item = self
+            .store
+            .unlink_todos(&owner, id, blocked_by_id, clock::now())
+            .await?;
+        if let Some(todo_item) = &todo_item {
+            self.publish(&owner, TodoEventKind::Updated, todo_item)
+                .await;
+        }
+        Ok(todo_item)
+    }
+
+    /// A todo with all of its descendants. Members of a shared project only see
+    /// the descendants in that project, not those the owner kept elsewhere.
+    pub async fn get_todo_tree(
+        &self,
+        username: String,
+        id: u32,
+    ) -> Result<Option<TodoTree>, ServiceError> {
+        let owner = match self.todo_owner(&username, id, ProjectRole::Viewer).await? {
+            Some(owner) => owner,
+            None => return Ok(None),
+        };
+        let root = match self.store.get_todo(&owner, id).await? {
+            Some(root) => root,
+            None => return Ok(None),
+        };
+        let mut subtasks: HashMap<u32, Vec<TodoItem>> = HashMap::new();
+        let mut pending = vec![root.id];
+        while let Some(parent_id) = pending.pop() {
+            if subtasks.contains_key(&parent_id) {
+                continue;
+            }
+            let mut children = self.store.get_subtasks(&owner, parent_id).await?;
+            if owner != username {
+                children.retain(|child| child.project_id == root.project_id);
+            }
+            pending.extend(children.iter().map(|child| child.id));
+            subtasks.insert(parent_id, children);
+        }
+        Ok(Some(todo_tree(root, &mut subtasks)))
+    }
+}
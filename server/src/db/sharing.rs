@@ -0,0 +1,219 @@
+use crate::db::todo::to_timestamp;
+use crate::error::ServiceError;
+use proto::service::todo::{Invitation, ProjectMember, ProjectRole};
+use sqlx::FromRow;
+
+/// Selects an `InvitationDb` from `project_invitation` aliased `i`.
+pub const INVITATION_SELECT: &str = "select i.id, i.projectId as project_id, p.name as project_name, b.username as invited_by, i.role, i.created_at from project_invitation i INNER JOIN project p on i.projectId = p.id INNER JOIN user b on i.invitedById = b.id";
+
+/// Who a todo belongs to, and the role a user has in its project when they
+/// are a member of it.
+#[derive(Debug, FromRow, Clone)]
+pub struct AccessDb {
+    pub owner: String,
+    pub role: Option<i32>,
+}
+
+impl AccessDb {
+    /// Role of `username` on the todo, `None` when they neither own it nor are
+    /// a member of its project. Owners of a todo have every right on it.
+    pub fn role(&self, username: &str) -> Option<i32> {
+        if self.owner == username {
+            Some(ProjectRole::Owner as i32)
+        } else {
+            self.role
+        }
+    }
+}
+
+#[derive(Debug, FromRow, Clone)]
+pub struct MemberDb {
+    pub username: String,
+    pub role: i32,
+}
+
+impl From<MemberDb> for ProjectMember {
+    fn from(member: MemberDb) -> Self {
+        ProjectMember {
+            username: member.username,
+            role: member.role,
+        }
+    }
+}
+
+#[derive(Debug, FromRow, Clone)]
+pub struct InvitationDb {
+    pub id: u32,
+    pub project_id: u32,
+    pub project_name: String,
+    pub invited_by: String,
+    pub role: i32,
+    pub created_at: i64,
+}
+
+impl From<InvitationDb> for Invitation {
+    fn from(invitation: InvitationDb) -> Self {
+        Invitation {
+            id: invitation.id,
+            project_id: invitation.project_id,
+            project_name: invitation.project_name,
+            invited_by: invitation.invited_by,
+            role: invitation.role,
+            created_at: Some(to_timestamp(invitation.created_at)),
+        }
+    }
+}
+
+fn role_name(role: ProjectRole) -> &'static str {
+    match role {
+        ProjectRole::Viewer => "Viewer",
+        ProjectRole::Editor => "Editor",
+        ProjectRole::Owner => "Owner",
+    }
+}
+
+/// Checks that `role` grants at least the rights of `required` on `resource`.
+/// Roles are ordered, each one including the rights of the previous ones, and
+/// having no role grants none.
+pub fn check_role(
+    role: Option<i32>,
+    required: ProjectRole,
+    resource: &str,
+) -> Result<(), ServiceError> {
+    if matches!(role, Some(role) if role >= required as i32) {
+        Ok(())
+    } else {
+        Err(ServiceError::PermissionDenied {
+            resource: resource.to_string(),
+            role: role_name(required).to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::fixtures::{create_todo, repository, share_project, sign_up};
+    use proto::service::todo::UpdateTodoRequest;
+
+    fn denied<T>(result: Result<T, ServiceError>, required: &str) -> bool {
+        matches!(result, Err(ServiceError::PermissionDenied { ref role, .. }) if role == required)
+    }
+
+    #[test]
+    fn roles_include_the_rights_of_lower_ones() {
+        let viewer = Some(ProjectRole::Viewer as i32);
+        let editor = Some(ProjectRole::Editor as i32);
+        let owner = Some(ProjectRole::Owner as i32);
+        assert!(check_role(viewer, ProjectRole::Viewer, "Project 1").is_ok());
+        assert!(denied(
+            check_role(viewer, ProjectRole::Editor, "Project 1"),
+            "Editor"
+        ));
+        assert!(check_role(editor, ProjectRole::Editor, "Project 1").is_ok());
+        assert!(denied(
+            check_role(editor, ProjectRole::Owner, "Project 1"),
+            "Owner"
+        ));
+        assert!(check_role(owner, ProjectRole::Editor, "Project 1").is_ok());
+        assert!(denied(
+            check_role(None, ProjectRole::Viewer, "Project 1"),
+            "Viewer"
+        ));
+    }
+
+    #[test]
+    fn owners_of_a_todo_have_every_right_on_it() {
+        let access = AccessDb {
+            owner: "alice".to_string(),
+            role: Some(ProjectRole::Viewer as i32),
+        };
+        assert_eq!(access.role("alice"), Some(ProjectRole::Owner as i32));
+        assert_eq!(access.role("bob"), Some(ProjectRole::Viewer as i32));
+    }
+
+    #[tokio::test]
+    async fn viewers_read_the_todos_of_a_project_but_cannot_change_them() {
+        let repository = repository();
+        let conn = repository.acquire().await.unwrap();
+        sign_up(&conn, &["alice", "bob", "carol"]).await;
+        let project = share_project(&conn, "alice", "bob", ProjectRole::Viewer).await;
+        let todo_item = create_todo(&conn, "alice", "Shared", project.id).await;
+        let update = || UpdateTodoRequest {
+            id: todo_item.id,
+            title: "Renamed".to_string(),
+            ..Default::default()
+        };
+
+        assert!(conn
+            .get_todo("bob".to_string(), todo_item.id)
+            .await
+            .unwrap()
+            .is_some());
+        assert!(denied(
+            conn.update_todo("bob".to_string(), update()).await,
+            "Editor"
+        ));
+        assert!(conn
+            .get_todo("carol".to_string(), todo_item.id)
+            .await
+            .unwrap()
+            .is_none());
+
+        conn.set_member_role(
+            "alice".to_string(),
+            project.id,
+            "bob".to_string(),
+            ProjectRole::Editor as i32,
+        )
+        .await
+        .unwrap();
+        assert!(conn
+            .update_todo("bob".to_string(), update())
+            .await
+            .unwrap()
+            .is_some());
+    }
+
+    #[tokio::test]
+    async fn only_owners_manage_a_project() {
+        let repository = repository();
+        let conn = repository.acquire().await.unwrap();
+        sign_up(&conn, &["alice", "bob", "carol"]).await;
+        let project = share_project(&conn, "alice", "bob", ProjectRole::Editor).await;
+
+        assert!(denied(
+            conn.rename_project("bob".to_string(), project.id, "Mine".to_string())
+                .await,
+            "Owner"
+        ));
+        assert!(denied(
+            conn.invite_member(
+                "bob".to_string(),
+                project.id,
+                "carol".to_string(),
+                ProjectRole::Viewer as i32,
+            )
+            .await,
+            "Owner"
+        ));
+        assert!(!conn
+            .delete_project("carol".to_string(), project.id)
+            .await
+            .unwrap());
+        assert!(matches!(
+            conn.remove_member("alice".to_string(), project.id, "alice".to_string())
+                .await,
+            Err(ServiceError::InvalidArgument { .. })
+        ));
+        // Every member can leave.
+        assert!(conn
+            .remove_member("bob".to_string(), project.id, "bob".to_string())
+            .await
+            .unwrap());
+        assert!(matches!(
+            conn.get_members("bob".to_string(), project.id).await,
+            Err(ServiceError::NotFound(_))
+        ));
+    }
+}
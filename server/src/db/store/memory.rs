@@ -0,0 +1,1350 @@
+use crate::db::models::{
+    AccessDb, InvitationDb, MemberDb, ProjectDb, RevokedTokenDb, TagDb, TodoItemDb,
+    TodoTransitionDb, User,
+};
+use crate::db::pagination::TodoQuery;
+use crate::db::store::{
+    MemberStore, ProjectStore, SecondFactorStore, Store, TodoStore, UserStore, DELETED_USER_PIN,
+};
+use crate::db::todo::{completed_at, to_seconds};
+use crate::error::ServiceError;
+use proto::service::todo::{ProjectRole, TodoItem, TodoStatus, UpdateTodoRequest};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc::Sender;
+use tracing::log::error;
+
+#[derive(Debug)]
+struct MemoryUser {
+    username: String,
+    pin: String,
+    password: Option<String>,
+    totp_secret: Option<String>,
+    totp_enabled: bool,
+    totp_last_step: Option<i64>,
+    /// When each recovery code was used, keyed on its hash.
+    recovery_codes: HashMap<String, Option<i64>>,
+}
+
+impl MemoryUser {
+    fn new(username: &str, pin_hash: &str, password_hash: Option<&str>) -> Self {
+        Self {
+            username: username.to_string(),
+            pin: pin_hash.to_string(),
+            password: password_hash.map(String::from),
+            totp_secret: None,
+            totp_enabled: false,
+            totp_last_step: None,
+            recovery_codes: HashMap::new(),
+        }
+    }
+
+    /// Forgets the TOTP secret and the recovery codes.
+    fn clear_totp(&mut self) {
+        self.totp_secret = None;
+        self.totp_enabled = false;
+        self.totp_last_step = None;
+        self.recovery_codes.clear();
+    }
+}
+
+#[derive(Debug)]
+struct MemoryTodo {
+    user_id: u32,
+    row: TodoItemDb,
+    tags: BTreeSet<String>,
+    blocked_by: BTreeSet<u32>,
+    delivered_reminder_at: Option<i64>,
+}
+
+#[derive(Debug)]
+struct MemoryProject {
+    user_id: u32,
+    row: ProjectDb,
+}
+
+#[derive(Debug)]
+struct MemoryInvitation {
+    project_id: u32,
+    user_id: u32,
+    role: i32,
+    invited_by: u32,
+    created_at: i64,
+}
+
+#[derive(Debug)]
+struct MemoryToken {
+    user_id: u32,
+    expires_at: i64,
+    revoked_at: Option<i64>,
+}
+
+#[derive(Debug)]
+struct MemoryChallenge {
+    user_id: u32,
+    expires_at: i64,
+    attempts: i32,
+    consumed_at: Option<i64>,
+}
+
+#[derive(Debug, Default)]
+struct MemoryState {
+    next_user_id: u32,
+    users: BTreeMap<u32, MemoryUser>,
+    next_todo_id: u32,
+    todos: BTreeMap<u32, MemoryTodo>,
+    transitions: Vec<TodoTransitionDb>,
+    next_project_id: u32,
+    projects: BTreeMap<u32, MemoryProject>,
+    /// Roles keyed on project and user ids.
+    members: BTreeMap<(u32, u32), i32>,
+    next_invitation_id: u32,
+    invitations: BTreeMap<u32, MemoryInvitation>,
+    /// Access tokens keyed on `jti`.
+    access_tokens: HashMap<String, MemoryToken>,
+    /// Refresh tokens keyed on their hash.
+    refresh_tokens: HashMap<String, MemoryToken>,
+    /// Sign in challenges keyed on their hash.
+    challenges: HashMap<String, MemoryChallenge>,
+}
+
+impl MemoryState {
+    fn user_id(&self, username: &str) -> Option<u32> {
+        self.users
+            .iter()
+            .find(|(_, user)| user.username == username)
+            .map(|(id, _)| *id)
+    }
+
+    fn username(&self, user_id: u32) -> String {
+        self.users
+            .get(&user_id)
+            .map(|user| user.username.clone())
+            .unwrap_or_default()
+    }
+
+    /// Role of a user in a project, `None` when they are not a member.
+    fn member_role(&self, project_id: u32, user_id: u32) -> Option<i32> {
+        self.members.get(&(project_id, user_id)).copied()
+    }
+
+    /// A project as seen by one of its members.
+    fn project_view(&self, project_id: u32, user_id: u32) -> Option<ProjectDb> {
+        let role = self.member_role(project_id, user_id)?;
+        self.projects.get(&project_id).map(|project| ProjectDb {
+            role,
+            ..project.row.clone()
+        })
+    }
+
+    fn invitation(&self, id: u32) -> Option<InvitationDb> {
+        let invitation = self.invitations.get(&id)?;
+        let project = self.projects.get(&invitation.project_id)?;
+        Some(InvitationDb {
+            id,
+            project_id: invitation.project_id,
+            project_name: project.row.name.clone(),
+            invited_by: self.username(invitation.invited_by),
+            role: invitation.role,
+            created_at: invitation.created_at,
+        })
+    }
+
+    /// Position after the last todo of `project_id`, or of the inbox when `None`.
+    fn next_position(&self, user_id: u32, project_id: Option<u32>) -> i64 {
+        self.todos
+            .values()
+            .filter(|todo| todo.user_id == user_id && todo.row.project_id == project_id)
+            .map(|todo| todo.row.position + 1)
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn insert_todo(&mut self, user_id: u32, mut row: TodoItemDb) -> TodoItem {
+        self.next_todo_id += 1;
+        row.id = self.next_todo_id;
+        row.position = self.next_position(user_id, row.project_id);
+        let todo_item = TodoItem::from(row.clone());
+        self.todos.insert(
+            row.id,
+            MemoryTodo {
+                user_id,
+                row,
+                tags: BTreeSet::new(),
+                blocked_by: BTreeSet::new(),
+                delivered_reminder_at: None,
+            },
+        );
+        todo_item
+    }
+
+    /// Revokes every live token of a user, returning the access tokens that
+    /// were revoked.
+    fn revoke_tokens(&mut self, user_id: u32, now: i64) -> Vec<RevokedTokenDb> {
+        let mut revoked = vec![];
+        for (jti, token) in self.access_tokens.iter_mut() {
+            if token.user_id == user_id && token.revoked_at.is_none() {
+                token.revoked_at = Some(now);
+                if token.expires_at > now {
+                    revoked.push(RevokedTokenDb {
+                        jti: jti.clone(),
+                        expires_at: token.expires_at,
+                    });
+                }
+            }
+        }
+        for token in self.refresh_tokens.values_mut() {
+            if token.user_id == user_id && token.revoked_at.is_none() {
+                token.revoked_at = Some(now);
+            }
+        }
+        revoked
+    }
+
+    /// Replaces `username` with `new_username` in the history of transitions.
+    fn rename_transitions(&mut self, username: &str, new_username: &str) {
+        for transition in self.transitions.iter_mut() {
+            if transition.changed_by == username {
+                transition.changed_by = new_username.to_string();
+            }
+        }
+    }
+
+    fn user_mut(&mut self, username: &str) -> Option<&mut MemoryUser> {
+        self.users
+            .values_mut()
+            .find(|user| user.username == username)
+    }
+
+    fn owned_todo(&mut self, username: &str, id: u32) -> Option<&mut MemoryTodo> {
+        let user_id = self.user_id(username)?;
+        self.todos
+            .get_mut(&id)
+            .filter(|todo| todo.user_id == user_id)
+    }
+}
+
+fn name_taken(state: &MemoryState, user_id: u32, name: &str, except: u32) -> bool {
+    state.projects.iter().any(|(id, project)| {
+        *id != except && project.user_id == user_id && project.row.name == name
+    })
+}
+
+fn to_todo_item(todo: &MemoryTodo) -> TodoItem {
+    TodoItem {
+        tags: todo.tags.iter().cloned().collect(),
+        blocked_by: todo.blocked_by.iter().copied().collect(),
+        ..TodoItem::from(todo.row.clone())
+    }
+}
+
+/// Keeps everything in process memory. Data is lost on restart, which makes it
+/// suited to local development and tests; select it with `DATABASE_URL=memory://`.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryStore {
+    state: Arc<Mutex<MemoryState>>,
+}
+
+#[cfg(test)]
+impl MemoryStore {
+    /// Status changes recorded for a todo, oldest first.
+    pub fn transitions(&self, id: u32) -> Vec<TodoTransitionDb> {
+        let state = self.state.lock().unwrap();
+        state
+            .transitions
+            .iter()
+            .filter(|transition| transition.todo_id == id)
+            .cloned()
+            .collect()
+    }
+}
+
+#[tonic::async_trait]
+impl Store for MemoryStore {
+    async fn migrate(&self) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+#[tonic::async_trait]
+impl UserStore for MemoryStore {
+    async fn create_user(
+        &self,
+        username: &str,
+        pin_hash: &str,
+        password_hash: Option<&str>,
+    ) -> Result<User, ServiceError> {
+        let mut state = self.state.lock().unwrap();
+        if state.user_id(username).is_some() {
+            return Err(ServiceError::AlreadyExists(format!("User {}", username)));
+        }
+        state.next_user_id += 1;
+        let id = state.next_user_id;
+        state
+            .users
+            .insert(id, MemoryUser::new(username, pin_hash, password_hash));
+        Ok(User {
+            username: username.to_string(),
+            pin: pin_hash.to_string(),
+            password: password_hash.map(String::from),
+            totp_secret: None,
+            totp_enabled: false,
+        })
+    }
+
+    async fn find_user(&self, username: &str) -> Result<Option<User>, ServiceError> {
+        let state = self.state.lock().unwrap();
+        Ok(state
+            .users
+            .values()
+            .find(|user| user.username == username)
+            .map(|user| User {
+                username: user.username.clone(),
+                pin: user.pin.clone(),
+                password: user.password.clone(),
+                totp_secret: user.totp_secret.clone(),
+                totp_enabled: user.totp_enabled,
+            }))
+    }
+
+    async fn update_pin(&self, username: &str, pin_hash: &str) -> Result<(), ServiceError> {
+        let mut state = self.state.lock().unwrap();
+        if let Some(user) = state
+            .users
+            .values_mut()
+            .find(|user| user.username == username)
+        {
+            user.pin = pin_hash.to_string();
+        }
+        Ok(())
+    }
+
+    async fn update_password(
+        &self,
+        username: &str,
+        password_hash: &str,
+    ) -> Result<(), ServiceError> {
+        let mut state = self.state.lock().unwrap();
+        if let Some(user) = state
+            .users
+            .values_mut()
+            .find(|user| user.username == username)
+        {
+            user.password = Some(password_hash.to_string());
+        }
+        Ok(())
+    }
+
+    async fn insert_access_token(
+        &self,
+        username: &str,
+        jti: &str,
+        expires_at: i64,
+    ) -> Result<(), ServiceError> {
+        let mut state = self.state.lock().unwrap();
+        if let Some(user_id) = state.user_id(username) {
+            state.access_tokens.insert(
+                jti.to_string(),
+                MemoryToken {
+                    user_id,
+                    expires_at,
+                    revoked_at: None,
+                },
+            );
+        }
+        Ok(())
+    }
+
+    async fn insert_refresh_token(
+        &self,
+        username: &str,
+        token_hash: &str,
+        _created_at: i64,
+        expires_at: i64,
+    ) -> Result<(), ServiceError> {
+        let mut state = self.state.lock().unwrap();
+        if let Some(user_id) = state.user_id(username) {
+            state.refresh_tokens.insert(
+                token_hash.to_string(),
+                MemoryToken {
+                    user_id,
+                    expires_at,
+                    revoked_at: None,
+                },
+            );
+        }
+        Ok(())
+    }
+
+    async fn consume_refresh_token(
+        &self,
+        token_hash: &str,
+        now: i64,
+    ) -> Result<Option<String>, ServiceError> {
+        let mut state = self.state.lock().unwrap();
+        let user_id = match state.refresh_tokens.get_mut(token_hash) {
+            Some(token) if token.revoked_at.is_none() && token.expires_at > now => {
+                token.revoked_at = Some(now);
+                token.user_id
+            }
+            _ => return Ok(None),
+        };
+        Ok(state.users.get(&user_id).map(|user| user.username.clone()))
+    }
+
+    async fn revoke_access_token(
+        &self,
+        username: &str,
+        jti: &str,
+        now: i64,
+    ) -> Result<(), ServiceError> {
+        let mut state = self.state.lock().unwrap();
+        let user_id = state.user_id(username);
+        if let Some(token) = state.access_tokens.get_mut(jti) {
+            if Some(token.user_id) == user_id && token.revoked_at.is_none() {
+                token.revoked_at = Some(now);
+            }
+        }
+        Ok(())
+    }
+
+    async fn revoke_refresh_token(
+        &self,
+        username: &str,
+        token_hash: &str,
+        now: i64,
+    ) -> Result<(), ServiceError> {
+        let mut state = self.state.lock().unwrap();
+        let user_id = state.user_id(username);
+        if let Some(token) = state.refresh_tokens.get_mut(token_hash) {
+            if Some(token.user_id) == user_id && token.revoked_at.is_none() {
+                token.revoked_at = Some(now);
+            }
+        }
+        Ok(())
+    }
+
+    async fn revoke_all_tokens(
+        &self,
+        username: &str,
+        now: i64,
+    ) -> Result<Vec<RevokedTokenDb>, ServiceError> {
+        let mut state = self.state.lock().unwrap();
+        let user_id = match state.user_id(username) {
+            Some(user_id) => user_id,
+            None => return Ok(vec![]),
+        };
+        Ok(state.revoke_tokens(user_id, now))
+    }
+
+    async fn revoked_tokens(&self, now: i64) -> Result<Vec<RevokedTokenDb>, ServiceError> {
+        let state = self.state.lock().unwrap();
+        Ok(state
+            .access_tokens
+            .iter()
+            .filter(|(_, token)| token.revoked_at.is_some() && token.expires_at > now)
+            .map(|(jti, token)| RevokedTokenDb {
+                jti: jti.clone(),
+                expires_at: token.expires_at,
+            })
+            .collect())
+    }
+
+    async fn rename_user(
+        &self,
+        username: &str,
+        new_username: &str,
+        now: i64,
+    ) -> Result<Vec<RevokedTokenDb>, ServiceError> {
+        let mut state = self.state.lock().unwrap();
+        let user_id = match state.user_id(username) {
+            Some(user_id) => user_id,
+            None => return Err(ServiceError::NotFound(format!("User {}", username))),
+        };
+        if state.user_id(new_username).is_some() {
+            return Err(ServiceError::AlreadyExists(format!(
+                "User {}",
+                new_username
+            )));
+        }
+        let revoked = state.revoke_tokens(user_id, now);
+        if let Some(user) = state.users.get_mut(&user_id) {
+            user.username = new_username.to_string();
+        }
+        state.rename_transitions(username, new_username);
+        Ok(revoked)
+    }
+
+    async fn delete_user(
+        &self,
+        username: &str,
+        tombstone: &str,
+        now: i64,
+    ) -> Result<(Vec<RevokedTokenDb>, Vec<TodoItem>), ServiceError> {
+        let mut state = self.state.lock().unwrap();
+        let user_id = match state.user_id(username) {
+            Some(user_id) => user_id,
+            None => return Err(ServiceError::NotFound(format!("User {}", username))),
+        };
+        let state = &mut *state;
+        let revoked = state.revoke_tokens(user_id, now);
+        let deleted = state
+            .todos
+            .iter()
+            .filter(|(_, todo)| todo.user_id == user_id)
+            .map(|(id, todo)| TodoItem {
+                id: *id,
+                project_id: todo.row.project_id.unwrap_or_default(),
+                ..Default::default()
+            })
+            .collect();
+        // Subtasks and dependencies never cross owners, so removing the todos
+        // of the user leaves no dangling link.
+        state.todos.retain(|_, todo| todo.user_id != user_id);
+        let todos = &state.todos;
+        state
+            .transitions
+            .retain(|transition| todos.contains_key(&transition.todo_id));
+        state
+            .projects
+            .retain(|_, project| project.user_id != user_id);
+        let projects = &state.projects;
+        state.members.retain(|(project_id, member_id), _| {
+            *member_id != user_id && projects.contains_key(project_id)
+        });
+        state.invitations.retain(|_, invitation| {
+            invitation.user_id != user_id
+                && invitation.invited_by != user_id
+                && projects.contains_key(&invitation.project_id)
+        });
+        state
+            .challenges
+            .retain(|_, challenge| challenge.user_id != user_id);
+        state.rename_transitions(username, tombstone);
+        if let Some(user) = state.users.get_mut(&user_id) {
+            user.username = tombstone.to_string();
+            user.pin = DELETED_USER_PIN.to_string();
+            user.password = None;
+            user.clear_totp();
+        }
+        Ok((revoked, deleted))
+    }
+}
+
+#[tonic::async_trait]
+impl SecondFactorStore for MemoryStore {
+    async fn set_totp_secret(&self, username: &str, secret: &str) -> Result<bool, ServiceError> {
+        let mut state = self.state.lock().unwrap();
+        match state.user_mut(username) {
+            Some(user) if !user.totp_enabled => {
+                user.totp_secret = Some(secret.to_string());
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    async fn enable_totp(
+        &self,
+        username: &str,
+        step: i64,
+        recovery_code_hashes: &[String],
+    ) -> Result<bool, ServiceError> {
+        let mut state = self.state.lock().unwrap();
+        let user = match state.user_mut(username) {
+            Some(user) if !user.totp_enabled && user.totp_secret.is_some() => user,
+            _ => return Ok(false),
+        };
+        user.totp_enabled = true;
+        user.totp_last_step = Some(step);
+        user.recovery_codes = recovery_code_hashes
+            .iter()
+            .map(|code_hash| (code_hash.clone(), None))
+            .collect();
+        Ok(true)
+    }
+
+    async fn disable_totp(&self, username: &str) -> Result<(), ServiceError> {
+        let mut state = self.state.lock().unwrap();
+        let user_id = match state.user_id(username) {
+            Some(user_id) => user_id,
+            None => return Err(ServiceError::NotFound(format!("User {}", username))),
+        };
+        if let Some(user) = state.users.get_mut(&user_id) {
+            user.clear_totp();
+        }
+        state
+            .challenges
+            .retain(|_, challenge| challenge.user_id != user_id);
+        Ok(())
+    }
+
+    async fn use_totp_step(&self, username: &str, step: i64) -> Result<bool, ServiceError> {
+        let mut state = self.state.lock().unwrap();
+        match state.user_mut(username) {
+            Some(user) if !matches!(user.totp_last_step, Some(last) if last >= step) => {
+                user.totp_last_step = Some(step);
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    async fn use_recovery_code(
+        &self,
+        username: &str,
+        code_hash: &str,
+        now: i64,
+    ) -> Result<bool, ServiceError> {
+        let mut state = self.state.lock().unwrap();
+        let used_at = state
+            .user_mut(username)
+            .and_then(|user| user.recovery_codes.get_mut(code_hash));
+        match used_at {
+            Some(used_at) if used_at.is_none() => {
+                *used_at = Some(now);
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    async fn insert_challenge(
+        &self,
+        username: &str,
+        token_hash: &str,
+        expires_at: i64,
+    ) -> Result<(), ServiceError> {
+        let mut state = self.state.lock().unwrap();
+        if let Some(user_id) = state.user_id(username) {
+            state.challenges.insert(
+                token_hash.to_string(),
+                MemoryChallenge {
+                    user_id,
+                    expires_at,
+                    attempts: 0,
+                    consumed_at: None,
+                },
+            );
+        }
+        Ok(())
+    }
+
+    async fn attempt_challenge(
+        &self,
+        token_hash: &str,
+        now: i64,
+        max_attempts: i32,
+    ) -> Result<Option<String>, ServiceError> {
+        let mut state = self.state.lock().unwrap();
+        let user_id = match state.challenges.get_mut(token_hash) {
+            Some(challenge)
+                if challenge.consumed_at.is_none()
+                    && challenge.expires_at > now
+                    && challenge.attempts < max_attempts =>
+            {
+                challenge.attempts += 1;
+                challenge.user_id
+            }
+            _ => return Ok(None),
+        };
+        Ok(state.users.get(&user_id).map(|user| user.username.clone()))
+    }
+
+    async fn consume_challenge(&self, token_hash: &str, now: i64) -> Result<bool, ServiceError> {
+        let mut state = self.state.lock().unwrap();
+        match state.challenges.get_mut(token_hash) {
+            Some(challenge) if challenge.consumed_at.is_none() => {
+                challenge.consumed_at = Some(now);
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl TodoStore for MemoryStore {
+    async fn get_todos(
+        &self,
+        username: &str,
+        query: &TodoQuery,
+        resp: Sender<Result<TodoItem, ServiceError>>,
+    ) {
+        let todo_items: Vec<TodoItem> = {
+            let state = self.state.lock().unwrap();
+            let user_id = state.user_id(username);
+            let mut todo_items: Vec<TodoItem> = state
+                .todos
+                .iter()
+                .filter(|(_, todo)| {
+                    Some(todo.user_id) == user_id
+                        || matches!(
+                            (todo.row.project_id, user_id),
+                            (Some(project_id), Some(user_id))
+                                if state.member_role(project_id, user_id).is_some()
+                        )
+                })
+                .map(|(_, todo)| to_todo_item(todo))
+                .filter(|todo_item| query.matches(todo_item))
+                .collect();
+            todo_items.sort_by_key(|todo_item| query.sort_key(todo_item));
+            if query.descending {
+                todo_items.reverse();
+            }
+            todo_items.truncate(query.page_size as usize + 1);
+            todo_items
+        };
+        for todo_item in todo_items {
+            if let Err(e) = resp.send(Ok(todo_item)).await {
+                error!("Unable to send back from Get todos {:?}", e);
+                break;
+            }
+        }
+    }
+
+    async fn get_todo(&self, username: &str, id: u32) -> Result<Option<TodoItem>, ServiceError> {
+        let mut state = self.state.lock().unwrap();
+        Ok(state
+            .owned_todo(username, id)
+            .map(|todo| to_todo_item(todo)))
+    }
+
+    async fn create_todo(
+        &self,
+        username: &str,
+        todo: TodoItemDb,
+    ) -> Result<TodoItem, ServiceError> {
+        let mut state = self.state.lock().unwrap();
+        match state.user_id(username) {
+            Some(user_id) => Ok(state.insert_todo(user_id, todo)),
+            None => Err(ServiceError::NotFound(format!("User {}", username))),
+        }
+    }
+
+    async fn import_todos(
+        &self,
+        username: &str,
+        todos: Vec<TodoItemDb>,
+    ) -> Result<Vec<TodoItem>, ServiceError> {
+        let mut state = self.state.lock().unwrap();
+        let user_id = match state.user_id(username) {
+            Some(user_id) => user_id,
+            None => return Err(ServiceError::NotFound(format!("User {}", username))),
+        };
+        Ok(todos
+            .into_iter()
+            .map(|todo| state.insert_todo(user_id, todo))
+            .collect())
+    }
+
+    async fn update_todo(
+        &self,
+        username: &str,
+        req: UpdateTodoRequest,
+        expected_status: i32,
+        updated_at: i64,
+    ) -> Result<Option<TodoItem>, ServiceError> {
+        let mut state = self.state.lock().unwrap();
+        let todo = match state.owned_todo(username, req.id) {
+            Some(todo) if todo.row.status == expected_status => todo,
+            _ => return Ok(None),
+        };
+        let row = &mut todo.row;
+        row.title = req.title;
+        row.description = req.description;
+        row.notes = req.notes;
+        row.status = req.status;
+        row.priority = req.priority;
+        row.due_at = to_seconds(&req.due_at);
+        row.recurrence = Some(req.recurrence).filter(|recurrence| !recurrence.is_empty());
+        row.reminder_at = to_seconds(&req.reminder_at);
+        row.updated_at = updated_at;
+        row.completed_at = completed_at(req.status, row.completed_at, updated_at);
+        let todo_item = to_todo_item(todo);
+        if req.status != expected_status {
+            state.transitions.push(TodoTransitionDb {
+                todo_id: req.id,
+                from_status: expected_status,
+                to_status: req.status,
+                changed_by: username.to_string(),
+                changed_at: updated_at,
+            });
+        }
+        Ok(Some(todo_item))
+    }
+
+    async fn transition_todo(
+        &self,
+        username: &str,
+        transition: &TodoTransitionDb,
+    ) -> Result<Option<TodoItem>, ServiceError> {
+        let mut state = self.state.lock().unwrap();
+        let todo = match state.owned_todo(username, transition.todo_id) {
+            Some(todo) if todo.row.status == transition.from_status => todo,
+            _ => return Ok(None),
+        };
+        let row = &mut todo.row;
+        row.status = transition.to_status;
+        row.updated_at = transition.changed_at;
+        row.completed_at = completed_at(row.status, row.completed_at, transition.changed_at);
+        let todo_item = to_todo_item(todo);
+        state.transitions.push(transition.clone());
+        Ok(Some(todo_item))
+    }
+
+    async fn delete_todo(&self, username: &str, id: u32) -> Result<bool, ServiceError> {
+        let mut state = self.state.lock().unwrap();
+        if state.owned_todo(username, id).is_none() {
+            return Ok(false);
+        }
+        state
+            .transitions
+            .retain(|transition| transition.todo_id != id);
+        for todo in state.todos.values_mut() {
+            if todo.row.parent_id == Some(id) {
+                todo.row.parent_id = None;
+            }
+            todo.blocked_by.remove(&id);
+        }
+        Ok(state.todos.remove(&id).is_some())
+    }
+
+    async fn add_tags(
+        &self,
+        username: &str,
+        id: u32,
+        tags: &[String],
+        updated_at: i64,
+    ) -> Result<Option<TodoItem>, ServiceError> {
+        let mut state = self.state.lock().unwrap();
+        Ok(state.owned_todo(username, id).map(|todo| {
+            todo.tags.extend(tags.iter().cloned());
+            todo.row.updated_at = updated_at;
+            to_todo_item(todo)
+        }))
+    }
+
+    async fn remove_tags(
+        &self,
+        username: &str,
+        id: u32,
+        tags: &[String],
+        updated_at: i64,
+    ) -> Result<Option<TodoItem>, ServiceError> {
+        let mut state = self.state.lock().unwrap();
+        Ok(state.owned_todo(username, id).map(|todo| {
+            todo.tags.retain(|tag| !tags.contains(tag));
+            todo.row.updated_at = updated_at;
+            to_todo_item(todo)
+        }))
+    }
+
+    async fn list_tags(&self, username: &str) -> Result<Vec<TagDb>, ServiceError> {
+        let state = self.state.lock().unwrap();
+        let user_id = state.user_id(username);
+        let mut counts: BTreeMap<&str, i64> = BTreeMap::new();
+        for todo in state.todos.values() {
+            if Some(todo.user_id) == user_id {
+                for tag in &todo.tags {
+                    *counts.entry(tag).or_default() += 1;
+                }
+            }
+        }
+        Ok(counts
+            .into_iter()
+            .map(|(name, todo_count)| TagDb {
+                name: name.to_string(),
+                todo_count,
+            })
+            .collect())
+    }
+
+    async fn move_todo(
+        &self,
+        username: &str,
+        id: u32,
+        project_id: Option<u32>,
+        position: u32,
+        updated_at: i64,
+    ) -> Result<Option<TodoItem>, ServiceError> {
+        let mut state = self.state.lock().unwrap();
+        let user_id = match state.owned_todo(username, id) {
+            Some(todo) => todo.user_id,
+            None => return Ok(None),
+        };
+        let mut siblings: Vec<(i64, u32)> = state
+            .todos
+            .values()
+            .filter(|todo| {
+                todo.user_id == user_id && todo.row.project_id == project_id && todo.row.id != id
+            })
+            .map(|todo| (todo.row.position, todo.row.id))
+            .collect();
+        siblings.sort_unstable();
+        let index = (position as usize).min(siblings.len());
+        siblings.insert(index, (-1, id));
+        for (new_position, (_, todo_id)) in siblings.into_iter().enumerate() {
+            if let Some(todo) = state.todos.get_mut(&todo_id) {
+                todo.row.position = new_position as i64;
+            }
+        }
+        let todo = state.todos.get_mut(&id).unwrap();
+        todo.row.project_id = project_id;
+        todo.row.updated_at = updated_at;
+        Ok(Some(to_todo_item(todo)))
+    }
+
+    async fn set_parent(
+        &self,
+        username: &str,
+        id: u32,
+        parent_id: Option<u32>,
+        updated_at: i64,
+    ) -> Result<Option<TodoItem>, ServiceError> {
+        let mut state = self.state.lock().unwrap();
+        Ok(state.owned_todo(username, id).map(|todo| {
+            todo.row.parent_id = parent_id;
+            todo.row.updated_at = updated_at;
+            to_todo_item(todo)
+        }))
+    }
+
+    async fn get_subtasks(
+        &self,
+        username: &str,
+        parent_id: u32,
+    ) -> Result<Vec<TodoItem>, ServiceError> {
+        let state = self.state.lock().unwrap();
+        let user_id = state.user_id(username);
+        let mut subtasks: Vec<&MemoryTodo> = state
+            .todos
+            .values()
+            .filter(|todo| Some(todo.user_id) == user_id && todo.row.parent_id == Some(parent_id))
+            .collect();
+        subtasks.sort_by_key(|todo| (todo.row.position, todo.row.id));
+        Ok(subtasks.into_iter().map(to_todo_item).collect())
+    }
+
+    async fn get_parent_links(&self, username: &str) -> Result<Vec<(u32, u32)>, ServiceError> {
+        let state = self.state.lock().unwrap();
+        let user_id = state.user_id(username);
+        Ok(state
+            .todos
+            .values()
+            .filter(|todo| Some(todo.user_id) == user_id)
+            .filter_map(|todo| todo.row.parent_id.map(|parent_id| (todo.row.id, parent_id)))
+            .collect())
+    }
+
+    async fn get_dependency_links(&self, username: &str) -> Result<Vec<(u32, u32)>, ServiceError> {
+        let state = self.state.lock().unwrap();
+        let user_id = state.user_id(username);
+        Ok(state
+            .todos
+            .values()
+            .filter(|todo| Some(todo.user_id) == user_id)
+            .flat_map(|todo| {
+                todo.blocked_by
+                    .iter()
+                    .map(move |blocked_by_id| (todo.row.id, *blocked_by_id))
+            })
+            .collect())
+    }
+
+    async fn link_todos(
+        &self,
+        username: &str,
+        id: u32,
+        blocked_by_id: u32,
+        updated_at: i64,
+    ) -> Result<Option<TodoItem>, ServiceError> {
+        let mut state = self.state.lock().unwrap();
+        Ok(state.owned_todo(username, id).map(|todo| {
+            todo.blocked_by.insert(blocked_by_id);
+            todo.row.updated_at = updated_at;
+            to_todo_item(todo)
+        }))
+    }
+
+    async fn unlink_todos(
+        &self,
+        username: &str,
+        id: u32,
+        blocked_by_id: u32,
+        updated_at: i64,
+    ) -> Result<Option<TodoItem>, ServiceError> {
+        let mut state = self.state.lock().unwrap();
+        Ok(state.owned_todo(username, id).map(|todo| {
+            todo.blocked_by.remove(&blocked_by_id);
+            todo.row.updated_at = updated_at;
+            to_todo_item(todo)
+        }))
+    }
+
+    async fn recur_todo(
+        &self,
+        username: &str,
+        id: u32,
+        recurrence: &str,
+        next: Option<TodoItemDb>,
+        updated_at: i64,
+    ) -> Result<Option<TodoItem>, ServiceError> {
+        let mut state = self.state.lock().unwrap();
+        let (user_id, tags) = match state.owned_todo(username, id) {
+            Some(todo) if todo.row.recurrence.as_deref() == Some(recurrence) => {
+                todo.row.recurrence = None;
+                todo.row.updated_at = updated_at;
+                (todo.user_id, todo.tags.clone())
+            }
+            _ => return Ok(None),
+        };
+        Ok(next.map(|next| {
+            let todo_item = state.insert_todo(user_id, next);
+            let todo = state.todos.get_mut(&todo_item.id).unwrap();
+            todo.tags = tags;
+            to_todo_item(todo)
+        }))
+    }
+
+    async fn get_overdue_recurrences(&self, now: i64) -> Result<Vec<(String, u32)>, ServiceError> {
+        let state = self.state.lock().unwrap();
+        let open = [
+            TodoStatus::Active as i32,
+            TodoStatus::InProgress as i32,
+            TodoStatus::Blocked as i32,
+        ];
+        Ok(state
+            .todos
+            .iter()
+            .filter(|(_, todo)| {
+                todo.row.recurrence.is_some()
+                    && matches!(todo.row.due_at, Some(due_at) if due_at < now)
+                    && open.contains(&todo.row.status)
+            })
+            .map(|(id, todo)| (state.username(todo.user_id), *id))
+            .collect())
+    }
+
+    async fn get_due_reminders(&self, now: i64) -> Result<Vec<(String, u32, i64)>, ServiceError> {
+        let state = self.state.lock().unwrap();
+        let open = [
+            TodoStatus::Active as i32,
+            TodoStatus::InProgress as i32,
+            TodoStatus::Blocked as i32,
+        ];
+        let mut due = state
+            .todos
+            .iter()
+            .filter(|(_, todo)| open.contains(&todo.row.status))
+            .filter_map(|(id, todo)| match todo.row.reminder_at {
+                Some(reminder_at)
+                    if reminder_at <= now && todo.delivered_reminder_at != Some(reminder_at) =>
+                {
+                    Some((state.username(todo.user_id), *id, reminder_at))
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        due.sort_by_key(|(_, id, reminder_at)| (*reminder_at, *id));
+        Ok(due)
+    }
+
+    async fn mark_reminder_delivered(
+        &self,
+        username: &str,
+        id: u32,
+        reminder_at: i64,
+    ) -> Result<bool, ServiceError> {
+        let mut state = self.state.lock().unwrap();
+        match state.owned_todo(username, id) {
+            Some(todo)
+                if todo.row.reminder_at == Some(reminder_at)
+                    && todo.delivered_reminder_at != Some(reminder_at) =>
+            {
+                todo.delivered_reminder_at = Some(reminder_at);
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl ProjectStore for MemoryStore {
+    async fn create_project(
+        &self,
+        username: &str,
+        mut project: ProjectDb,
+    ) -> Result<ProjectDb, ServiceError> {
+        let mut state = self.state.lock().unwrap();
+        let user_id = match state.user_id(username) {
+            Some(user_id) => user_id,
+            None => return Err(ServiceError::NotFound(format!("User {}", username))),
+        };
+        if name_taken(&state, user_id, &project.name, 0) {
+            return Err(ServiceError::AlreadyExists(format!(
+                "Project {}",
+                project.name
+            )));
+        }
+        state.next_project_id += 1;
+        project.id = state.next_project_id;
+        project.owner = username.to_string();
+        project.role = ProjectRole::Owner as i32;
+        state.projects.insert(
+            project.id,
+            MemoryProject {
+                user_id,
+                row: project.clone(),
+            },
+        );
+        state
+            .members
+            .insert((project.id, user_id), ProjectRole::Owner as i32);
+        Ok(project)
+    }
+
+    async fn get_projects(&self, username: &str) -> Result<Vec<ProjectDb>, ServiceError> {
+        let state = self.state.lock().unwrap();
+        let user_id = match state.user_id(username) {
+            Some(user_id) => user_id,
+            None => return Ok(vec![]),
+        };
+        let mut projects: Vec<ProjectDb> = state
+            .projects
+            .keys()
+            .filter_map(|id| state.project_view(*id, user_id))
+            .collect();
+        projects.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(projects)
+    }
+
+    async fn get_project(
+        &self,
+        username: &str,
+        id: u32,
+    ) -> Result<Option<ProjectDb>, ServiceError> {
+        let state = self.state.lock().unwrap();
+        Ok(state
+            .user_id(username)
+            .and_then(|user_id| state.project_view(id, user_id)))
+    }
+
+    async fn rename_project(
+        &self,
+        username: &str,
+        id: u32,
+        name: &str,
+        updated_at: i64,
+    ) -> Result<Option<ProjectDb>, ServiceError> {
+        let mut state = self.state.lock().unwrap();
+        let user_id = match state.user_id(username) {
+            Some(user_id) => user_id,
+            None => return Ok(None),
+        };
+        if !matches!(state.projects.get(&id), Some(project) if project.user_id == user_id) {
+            return Ok(None);
+        }
+        if name_taken(&state, user_id, name, id) {
+            return Err(ServiceError::AlreadyExists(format!("Project {}", name)));
+        }
+        let project = state.projects.get_mut(&id).unwrap();
+        project.row.name = name.to_string();
+        project.row.updated_at = updated_at;
+        Ok(Some(project.row.clone()))
+    }
+
+    async fn delete_project(
+        &self,
+        username: &str,
+        id: u32,
+        updated_at: i64,
+    ) -> Result<Option<Vec<TodoItem>>, ServiceError> {
+        let mut state = self.state.lock().unwrap();
+        let user_id = match state.user_id(username) {
+            Some(user_id) => user_id,
+            None => return Ok(None),
+        };
+        if !matches!(state.projects.get(&id), Some(project) if project.user_id == user_id) {
+            return Ok(None);
+        }
+        let inbox_end = state.next_position(user_id, None);
+        let mut moved = vec![];
+        for todo in state.todos.values_mut() {
+            if todo.row.project_id == Some(id) {
+                todo.row.project_id = None;
+                todo.row.position += inbox_end;
+                todo.row.updated_at = updated_at;
+                moved.push(to_todo_item(todo));
+            }
+        }
+        state.projects.remove(&id);
+        state.members.retain(|(project_id, _), _| *project_id != id);
+        state
+            .invitations
+            .retain(|_, invitation| invitation.project_id != id);
+        Ok(Some(moved))
+    }
+}
+
+#[tonic::async_trait]
+impl MemberStore for MemoryStore {
+    async fn todo_access(&self, username: &str, id: u32) -> Result<Option<AccessDb>, ServiceError> {
+        let state = self.state.lock().unwrap();
+        let (user_id, todo) = match (state.user_id(username), state.todos.get(&id)) {
+            (Some(user_id), Some(todo)) => (user_id, todo),
+            _ => return Ok(None),
+        };
+        let role = todo
+            .row
+            .project_id
+            .and_then(|project_id| state.member_role(project_id, user_id));
+        if todo.user_id != user_id && role.is_none() {
+            return Ok(None);
+        }
+        Ok(Some(AccessDb {
+            owner: state.username(todo.user_id),
+            role,
+        }))
+    }
+
+    async fn get_members(&self, project_id: u32) -> Result<Vec<MemberDb>, ServiceError> {
+        let state = self.state.lock().unwrap();
+        let mut members: Vec<MemberDb> = state
+            .members
+            .iter()
+            .filter(|((id, _), _)| *id == project_id)
+            .map(|((_, user_id), role)| MemberDb {
+                username: state.username(*user_id),
+                role: *role,
+            })
+            .collect();
+        members.sort_by(|a, b| a.username.cmp(&b.username));
+        Ok(members)
+    }
+
+    async fn set_member_role(
+        &self,
+        project_id: u32,
+        username: &str,
+        role: i32,
+    ) -> Result<bool, ServiceError> {
+        let mut state = self.state.lock().unwrap();
+        let user_id = match state.user_id(username) {
+            Some(user_id) => user_id,
+            None => return Ok(false),
+        };
+        Ok(match state.members.get_mut(&(project_id, user_id)) {
+            Some(member_role) => {
+                *member_role = role;
+                true
+            }
+            None => false,
+        })
+    }
+
+    async fn remove_member(
+        &self,
+        project_id: u32,
+        username: &str,
+    ) -> Result<Option<Vec<u32>>, ServiceError> {
+        let mut state = self.state.lock().unwrap();
+        let removed = match state.user_id(username) {
+            Some(user_id) => state.members.remove(&(project_id, user_id)).is_some(),
+            None => false,
+        };
+        if !removed {
+            return Ok(None);
+        }
+        Ok(Some(
+            state
+                .todos
+                .iter()
+                .filter(|(_, todo)| todo.row.project_id == Some(project_id))
+                .map(|(id, _)| *id)
+                .collect(),
+        ))
+    }
+
+    async fn create_invitation(
+        &self,
+        project_id: u32,
+        username: &str,
+        role: i32,
+        invited_by: &str,
+        created_at: i64,
+    ) -> Result<InvitationDb, ServiceError> {
+        let mut state = self.state.lock().unwrap();
+        let (user_id, invited_by) = match (state.user_id(username), state.user_id(invited_by)) {
+            (Some(user_id), Some(invited_by)) => (user_id, invited_by),
+            _ => return Err(ServiceError::NotFound(format!("User {}", username))),
+        };
+        if state
+            .invitations
+            .values()
+            .any(|invitation| invitation.project_id == project_id && invitation.user_id == user_id)
+        {
+            return Err(ServiceError::AlreadyExists(format!(
+                "Invitation of {}",
+                username
+            )));
+        }
+        state.next_invitation_id += 1;
+        let id = state.next_invitation_id;
+        state.invitations.insert(
+            id,
+            MemoryInvitation {
+                project_id,
+                user_id,
+                role,
+                invited_by,
+                created_at,
+            },
+        );
+        state
+            .invitation(id)
+            .ok_or_else(|| ServiceError::NotFound(format!("Project {}", project_id)))
+    }
+
+    async fn get_invitations(&self, username: &str) -> Result<Vec<InvitationDb>, ServiceError> {
+        let state = self.state.lock().unwrap();
+        let user_id = state.user_id(username);
+        Ok(state
+            .invitations
+            .iter()
+            .filter(|(_, invitation)| Some(invitation.user_id) == user_id)
+            .filter_map(|(id, _)| state.invitation(*id))
+            .collect())
+    }
+
+    async fn accept_invitation(
+        &self,
+        username: &str,
+        id: u32,
+    ) -> Result<Option<u32>, ServiceError> {
+        let mut state = self.state.lock().unwrap();
+        let user_id = state.user_id(username);
+        match state.invitations.get(&id) {
+            Some(invitation) if Some(invitation.user_id) == user_id => {}
+            _ => return Ok(None),
+        }
+        let invitation = state.invitations.remove(&id).unwrap();
+        state
+            .members
+            .insert((invitation.project_id, invitation.user_id), invitation.role);
+        Ok(Some(invitation.project_id))
+    }
+
+    async fn decline_invitation(&self, username: &str, id: u32) -> Result<bool, ServiceError> {
+        let mut state = self.state.lock().unwrap();
+        let user_id = state.user_id(username);
+        match state.invitations.get(&id) {
+            Some(invitation) if Some(invitation.user_id) == user_id => {
+                state.invitations.remove(&id);
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+}
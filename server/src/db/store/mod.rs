@@ -0,0 +1,619 @@
+mod memory;
+mod mysql;
+mod sqlite;
+
+pub use crate::db::store::memory::MemoryStore;
+pub use crate::db::store::mysql::MySqlStore;
+pub use crate::db::store::sqlite::SqliteStore;
+
+use crate::db::models::{
+    AccessDb, InvitationDb, MemberDb, ProjectDb, RevokedTokenDb, TagDb, TodoItemDb,
+    TodoTransitionDb, User,
+};
+use crate::db::pagination::TodoQuery;
+use crate::error::ServiceError;
+use proto::service::todo::{TodoItem, UpdateTodoRequest};
+use sqlx::mysql::MySqlDatabaseError;
+use std::fmt::Debug;
+use tokio::sync::mpsc::Sender;
+
+const ER_DUP_ENTRY: u16 = 1062;
+const SQLITE_CONSTRAINT_PRIMARYKEY: &str = "1555";
+const SQLITE_CONSTRAINT_UNIQUE: &str = "2067";
+
+/// PIN hash stored for deleted users, who have no password. It is not a valid
+/// hash, so no PIN ever matches it.
+const DELETED_USER_PIN: &str = "!";
+
+/// Persistence of users and of the tokens issued to them.
+///
+/// Credential checks and token signing happen in the `Repository`; stores only
+/// read and write rows.
+#[tonic::async_trait]
+pub trait UserStore: Send + Sync {
+    /// Creates a user. `pin_hash` is empty when the user has no PIN.
+    async fn create_user(
+        &self,
+        username: &str,
+        pin_hash: &str,
+        password_hash: Option<&str>,
+    ) -> Result<User, ServiceError>;
+
+    /// Looks a user up by name for sign in.
+    async fn find_user(&self, username: &str) -> Result<Option<User>, ServiceError>;
+
+    async fn update_pin(&self, username: &str, pin_hash: &str) -> Result<(), ServiceError>;
+
+    async fn update_password(
+        &self,
+        username: &str,
+        password_hash: &str,
+    ) -> Result<(), ServiceError>;
+
+    async fn insert_access_token(
+        &self,
+        username: &str,
+        jti: &str,
+        expires_at: i64,
+    ) -> Result<(), ServiceError>;
+
+    async fn insert_refresh_token(
+        &self,
+        username: &str,
+        token_hash: &str,
+        created_at: i64,
+        expires_at: i64,
+    ) -> Result<(), ServiceError>;
+
+    /// Revokes a live refresh token and returns its owner, or `None` when the
+    /// token is unknown, expired or already revoked. Revocation is atomic, so a
+    /// refresh token can only ever be consumed once.
+    async fn consume_refresh_token(
+        &self,
+        token_hash: &str,
+        now: i64,
+    ) -> Result<Option<String>, ServiceError>;
+
+    async fn revoke_access_token(
+        &self,
+        username: &str,
+        jti: &str,
+        now: i64,
+    ) -> Result<(), ServiceError>;
+
+    async fn revoke_refresh_token(
+        &self,
+        username: &str,
+        token_hash: &str,
+        now: i64,
+    ) -> Result<(), ServiceError>;
+
+    /// Revokes every live access and refresh token of `username`, returning the
+    /// access tokens that were revoked.
+    async fn revoke_all_tokens(
+        &self,
+        username: &str,
+        now: i64,
+    ) -> Result<Vec<RevokedTokenDb>, ServiceError>;
+
+    /// Revoked access tokens that have not expired by `now`.
+    async fn revoked_tokens(&self, now: i64) -> Result<Vec<RevokedTokenDb>, ServiceError>;
+
+    /// Renames `username` to `new_username`, revoking every token issued under
+    /// the old name and returning the access tokens that were revoked.
+    async fn rename_user(
+        &self,
+        username: &str,
+        new_username: &str,
+        now: i64,
+    ) -> Result<Vec<RevokedTokenDb>, ServiceError>;
+
+    /// Deletes the todos, projects, memberships, invitations and tags of
+    /// `username` and revokes their tokens, in a single transaction. The user
+    /// row is kept under the `tombstone` name so that the revocations and the
+    /// history of shared todos stay valid, and can no longer be signed in to.
+    /// Returns the revoked access tokens along with the deleted todos, of
+    /// which only the id and the project are set.
+    async fn delete_user(
+        &self,
+        username: &str,
+        tombstone: &str,
+        now: i64,
+    ) -> Result<(Vec<RevokedTokenDb>, Vec<TodoItem>), ServiceError>;
+}
+
+/// Persistence of the TOTP second factor of users, of their recovery codes and
+/// of the challenges of two-step sign ins.
+#[tonic::async_trait]
+pub trait SecondFactorStore: Send + Sync {
+    /// Sets a pending TOTP secret for `username`, returning `false` when they
+    /// have TOTP enabled already.
+    async fn set_totp_secret(&self, username: &str, secret: &str) -> Result<bool, ServiceError>;
+
+    /// Enables the pending TOTP of `username`, accepting the code of `step`,
+    /// and replaces their recovery codes. Returns `false` when there is no
+    /// pending TOTP.
+    async fn enable_totp(
+        &self,
+        username: &str,
+        step: i64,
+        recovery_code_hashes: &[String],
+    ) -> Result<bool, ServiceError>;
+
+    /// Removes the TOTP, the recovery codes and the challenges of `username`.
+    async fn disable_totp(&self, username: &str) -> Result<(), ServiceError>;
+
+    /// Records the code of `step` as used, returning `false` when a code of
+    /// this step or a later one already was, so that codes cannot be replayed.
+    async fn use_totp_step(&self, username: &str, step: i64) -> Result<bool, ServiceError>;
+
+    /// Marks an unused recovery code of `username` as used, returning `false`
+    /// when there is no such code.
+    async fn use_recovery_code(
+        &self,
+        username: &str,
+        code_hash: &str,
+        now: i64,
+    ) -> Result<bool, ServiceError>;
+
+    async fn insert_challenge(
+        &self,
+        username: &str,
+        token_hash: &str,
+        expires_at: i64,
+    ) -> Result<(), ServiceError>;
+
+    /// Counts an attempt at a live challenge and returns the user it was issued
+    /// to, or `None` when the challenge is unknown, expired, consumed or out of
+    /// attempts.
+    async fn attempt_challenge(
+        &self,
+        token_hash: &str,
+        now: i64,
+        max_attempts: i32,
+    ) -> Result<Option<String>, ServiceError>;
+
+    /// Consumes a challenge, returning `false` when it already was, so that a
+    /// challenge is only ever exchanged for tokens once.
+    async fn consume_challenge(&self, token_hash: &str, now: i64) -> Result<bool, ServiceError>;
+}
+
+/// Persistence of todo items. Every operation is scoped to the owning user.
+#[tonic::async_trait]
+pub trait TodoStore: Send + Sync {
+    /// Sends the todos selected by `query`, plus the first todo of the next
+    /// page when there is one.
+    async fn get_todos(
+        &self,
+        username: &str,
+        query: &TodoQuery,
+        resp: Sender<Result<TodoItem, ServiceError>>,
+    );
+
+    async fn get_todo(&self, username: &str, id: u32) -> Result<Option<TodoItem>, ServiceError>;
+
+    /// Inserts `todo` at the end of its project, ignoring its id and position,
+    /// and returns it with the ones it was given.
+    async fn create_todo(&self, username: &str, todo: TodoItemDb)
+        -> Result<TodoItem, ServiceError>;
+
+    /// Inserts `todos` in a single transaction, like `create_todo`.
+    async fn import_todos(
+        &self,
+        username: &str,
+        todos: Vec<TodoItemDb>,
+    ) -> Result<Vec<TodoItem>, ServiceError>;
+
+    /// Replaces the fields of a todo that is still in `expected_status`,
+    /// recording the transition when its status changes. Its completion time
+    /// is set when it gets completed and cleared when it is reopened.
+    ///
+    /// Returns `None` when the todo does not exist, belongs to another user or
+    /// is no longer in `expected_status`.
+    async fn update_todo(
+        &self,
+        username: &str,
+        req: UpdateTodoRequest,
+        expected_status: i32,
+        updated_at: i64,
+    ) -> Result<Option<TodoItem>, ServiceError>;
+
+    /// Moves a todo that is still in `transition.from_status` to
+    /// `transition.to_status` and records the transition.
+    ///
+    /// Returns `None` like `update_todo`.
+    async fn transition_todo(
+        &self,
+        username: &str,
+        transition: &TodoTransitionDb,
+    ) -> Result<Option<TodoItem>, ServiceError>;
+
+    /// Deletes a todo along with its dependencies, turning its subtasks into
+    /// top-level todos.
+    ///
+    /// Returns `false` when the todo does not exist or belongs to another user.
+    async fn delete_todo(&self, username: &str, id: u32) -> Result<bool, ServiceError>;
+
+    /// Tags a todo, creating the tags `username` does not have yet.
+    ///
+    /// Returns `None` when the todo does not exist or belongs to another user.
+    async fn add_tags(
+        &self,
+        username: &str,
+        id: u32,
+        tags: &[String],
+        updated_at: i64,
+    ) -> Result<Option<TodoItem>, ServiceError>;
+
+    /// Untags a todo, ignoring the tags it does not have.
+    ///
+    /// Returns `None` like `add_tags`.
+    async fn remove_tags(
+        &self,
+        username: &str,
+        id: u32,
+        tags: &[String],
+        updated_at: i64,
+    ) -> Result<Option<TodoItem>, ServiceError>;
+
+    /// Tags of `username` that are on at least one todo, ordered by name.
+    async fn list_tags(&self, username: &str) -> Result<Vec<TagDb>, ServiceError>;
+
+    /// Moves a todo to `position` within `project_id`, or within the inbox
+    /// when `None`, renumbering the todos there.
+    ///
+    /// Returns `None` when the todo does not exist or belongs to another user.
+    async fn move_todo(
+        &self,
+        username: &str,
+        id: u32,
+        project_id: Option<u32>,
+        position: u32,
+        updated_at: i64,
+    ) -> Result<Option<TodoItem>, ServiceError>;
+
+    /// Makes a todo a subtask of `parent_id`, or a top-level todo when `None`.
+    ///
+    /// Returns `None` when the todo does not exist or belongs to another user.
+    async fn set_parent(
+        &self,
+        username: &str,
+        id: u32,
+        parent_id: Option<u32>,
+        updated_at: i64,
+    ) -> Result<Option<TodoItem>, ServiceError>;
+
+    /// Subtasks of a todo, ordered by position.
+    async fn get_subtasks(
+        &self,
+        username: &str,
+        parent_id: u32,
+    ) -> Result<Vec<TodoItem>, ServiceError>;
+
+    /// `(id, parent_id)` of every subtask of `username`.
+    async fn get_parent_links(&self, username: &str) -> Result<Vec<(u32, u32)>, ServiceError>;
+
+    /// `(id, blocked_by_id)` of every dependency between the todos of `username`.
+    async fn get_dependency_links(&self, username: &str) -> Result<Vec<(u32, u32)>, ServiceError>;
+
+    /// Records that a todo is blocked by `blocked_by_id`, which must belong to
+    /// the same user.
+    ///
+    /// Returns `None` when the todo does not exist or belongs to another user.
+    async fn link_todos(
+        &self,
+        username: &str,
+        id: u32,
+        blocked_by_id: u32,
+        updated_at: i64,
+    ) -> Result<Option<TodoItem>, ServiceError>;
+
+    /// Removes a dependency recorded by `link_todos`, if any.
+    ///
+    /// Returns `None` like `link_todos`.
+    async fn unlink_todos(
+        &self,
+        username: &str,
+        id: u32,
+        blocked_by_id: u32,
+        updated_at: i64,
+    ) -> Result<Option<TodoItem>, ServiceError>;
+
+    /// Moves the recurrence rule of a todo that still has `recurrence` to
+    /// `next`, its next occurrence, inserted like `create_todo` with the tags
+    /// of the todo. The series ends when `next` is `None`.
+    ///
+    /// Returns the inserted occurrence, or `None` when there is none or when
+    /// the todo does not have `recurrence` anymore, in which case nothing
+    /// changes.
+    async fn recur_todo(
+        &self,
+        username: &str,
+        id: u32,
+        recurrence: &str,
+        next: Option<TodoItemDb>,
+        updated_at: i64,
+    ) -> Result<Option<TodoItem>, ServiceError>;
+
+    /// `(owner, id)` of the recurring todos of every user that are still open
+    /// and were due before `now`.
+    async fn get_overdue_recurrences(&self, now: i64) -> Result<Vec<(String, u32)>, ServiceError>;
+
+    /// `(owner, id, reminder_at)` of the reminders of open todos of every user
+    /// that are due by `now` and have not been delivered, oldest first.
+    async fn get_due_reminders(&self, now: i64) -> Result<Vec<(String, u32, i64)>, ServiceError>;
+
+    /// Records that the reminder of a todo at `reminder_at` has been
+    /// delivered. Returns `false` when it already was, or when the reminder
+    /// of the todo has changed since.
+    async fn mark_reminder_delivered(
+        &self,
+        username: &str,
+        id: u32,
+        reminder_at: i64,
+    ) -> Result<bool, ServiceError>;
+}
+
+/// Persistence of the projects todos are grouped in. Projects are read as seen
+/// by one of their members and changed on behalf of their owner.
+#[tonic::async_trait]
+pub trait ProjectStore: Send + Sync {
+    /// Inserts `project`, ignoring its id, with `username` as its owner and
+    /// first member. Fails with `AlreadyExists` when `username` has a project
+    /// of the same name.
+    async fn create_project(
+        &self,
+        username: &str,
+        project: ProjectDb,
+    ) -> Result<ProjectDb, ServiceError>;
+
+    /// Projects `username` is a member of, ordered by name.
+    async fn get_projects(&self, username: &str) -> Result<Vec<ProjectDb>, ServiceError>;
+
+    /// Returns `None` unless `username` is a member of the project.
+    async fn get_project(&self, username: &str, id: u32)
+        -> Result<Option<ProjectDb>, ServiceError>;
+
+    /// Returns `None` when the project does not exist or belongs to another
+    /// user, and fails like `create_project` on a duplicate name.
+    async fn rename_project(
+        &self,
+        username: &str,
+        id: u32,
+        name: &str,
+        updated_at: i64,
+    ) -> Result<Option<ProjectDb>, ServiceError>;
+
+    /// Deletes a project after moving its todos to the end of the inbox,
+    /// returning the moved todos.
+    ///
+    /// Returns `None` when the project does not exist or belongs to another user.
+    async fn delete_project(
+        &self,
+        username: &str,
+        id: u32,
+        updated_at: i64,
+    ) -> Result<Option<Vec<TodoItem>>, ServiceError>;
+}
+
+/// Persistence of the members of shared projects and of the invitations to
+/// join them. Callers check that the user acting has the rights to do so.
+#[tonic::async_trait]
+pub trait MemberStore: Send + Sync {
+    /// Owner of a todo along with the role of `username` in its project, or
+    /// `None` when `username` neither owns the todo nor is a member of its
+    /// project.
+    async fn todo_access(&self, username: &str, id: u32) -> Result<Option<AccessDb>, ServiceError>;
+
+    /// Members of a project, ordered by name.
+    async fn get_members(&self, project_id: u32) -> Result<Vec<MemberDb>, ServiceError>;
+
+    /// Returns `false` when `username` is not a member of the project.
+    async fn set_member_role(
+        &self,
+        project_id: u32,
+        username: &str,
+        role: i32,
+    ) -> Result<bool, ServiceError>;
+
+    /// Removes `username` from a project, returning the ids of the todos of
+    /// the project they lose sight of, or `None` when they are not a member.
+    async fn remove_member(
+        &self,
+        project_id: u32,
+        username: &str,
+    ) -> Result<Option<Vec<u32>>, ServiceError>;
+
+    /// Invites `username` to a project. Fails with `NotFound` when there is
+    /// no such user and with `AlreadyExists` when they are already invited.
+    async fn create_invitation(
+        &self,
+        project_id: u32,
+        username: &str,
+        role: i32,
+        invited_by: &str,
+        created_at: i64,
+    ) -> Result<InvitationDb, ServiceError>;
+
+    /// Pending invitations of `username`, oldest first.
+    async fn get_invitations(&self, username: &str) -> Result<Vec<InvitationDb>, ServiceError>;
+
+    /// Makes `username` a member of the project they were invited to and
+    /// returns its id, or `None` when the invitation is not theirs.
+    async fn accept_invitation(&self, username: &str, id: u32)
+        -> Result<Option<u32>, ServiceError>;
+
+    /// Returns `false` when the invitation is not for `username`.
+    async fn decline_invitation(&self, username: &str, id: u32) -> Result<bool, ServiceError>;
+}
+
+/// A complete storage backend, selected from the scheme of `DATABASE_URL`.
+#[tonic::async_trait]
+pub trait Store:
+    UserStore + SecondFactorStore + TodoStore + ProjectStore + MemberStore + Debug
+{
+    /// Brings the schema up to date.
+    async fn migrate(&self) -> Result<(), String>;
+}
+
+/// Comma separated placeholders for `count` values.
+fn placeholders(count: usize) -> String {
+    vec!["?"; count].join(", ")
+}
+
+/// Whether `e` is a unique constraint violation, e.g. a duplicate username.
+fn is_unique_violation(e: &sqlx::Error) -> bool {
+    match e {
+        sqlx::Error::Database(db_err) => {
+            if let Some(mysql_err) = db_err.try_downcast_ref::<MySqlDatabaseError>() {
+                return mysql_err.number() == ER_DUP_ENTRY;
+            }
+            matches!(
+                db_err.code().as_deref(),
+                Some(SQLITE_CONSTRAINT_UNIQUE) | Some(SQLITE_CONSTRAINT_PRIMARYKEY)
+            )
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proto::service::todo::TodoStatus;
+
+    /// The backends that run without a server, migrated.
+    async fn stores() -> (MemoryStore, SqliteStore) {
+        let sqlite = SqliteStore::connect("sqlite::memory:").await.unwrap();
+        sqlite.migrate().await.unwrap();
+        (MemoryStore::default(), sqlite)
+    }
+
+    fn todo(recurrence: Option<&str>) -> TodoItemDb {
+        TodoItemDb {
+            id: 0,
+            title: "Water the plants".to_string(),
+            description: String::new(),
+            notes: String::new(),
+            status: TodoStatus::Active as i32,
+            priority: 0,
+            due_at: Some(1_000),
+            created_at: 100,
+            updated_at: 100,
+            completed_at: None,
+            project_id: None,
+            position: 0,
+            parent_id: None,
+            recurrence: recurrence.map(String::from),
+            reminder_at: None,
+        }
+    }
+
+    fn update(todo_item: &TodoItem, status: TodoStatus) -> UpdateTodoRequest {
+        UpdateTodoRequest {
+            id: todo_item.id,
+            title: todo_item.title.clone(),
+            status: status as i32,
+            due_at: todo_item.due_at.clone(),
+            ..Default::default()
+        }
+    }
+
+    fn statuses(transitions: Vec<TodoTransitionDb>) -> Vec<(i32, i32, String, i64)> {
+        transitions
+            .into_iter()
+            .map(|transition| {
+                (
+                    transition.from_status,
+                    transition.to_status,
+                    transition.changed_by,
+                    transition.changed_at,
+                )
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn update_todo_records_status_changes_on_every_backend() {
+        let (memory, sqlite) = stores().await;
+        let mut recorded = vec![];
+        for store in [&memory as &dyn Store, &sqlite] {
+            store.create_user("alice", "", None).await.unwrap();
+            let created = store.create_todo("alice", todo(None)).await.unwrap();
+            let active = TodoStatus::Active as i32;
+            let stale = store
+                .update_todo(
+                    "alice",
+                    update(&created, TodoStatus::Completed),
+                    TodoStatus::InProgress as i32,
+                    200,
+                )
+                .await
+                .unwrap();
+            assert!(stale.is_none());
+            let renamed = store
+                .update_todo("alice", update(&created, TodoStatus::Active), active, 200)
+                .await
+                .unwrap()
+                .unwrap();
+            assert!(renamed.completed_at.is_none());
+            let completed = store
+                .update_todo(
+                    "alice",
+                    update(&created, TodoStatus::Completed),
+                    active,
+                    300,
+                )
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(completed.status, TodoStatus::Completed as i32);
+            assert_eq!(completed.completed_at.map(|at| at.seconds), Some(300));
+            recorded.push(created.id);
+        }
+        let expected = vec![(
+            TodoStatus::Active as i32,
+            TodoStatus::Completed as i32,
+            "alice".to_string(),
+            300,
+        )];
+        assert_eq!(statuses(memory.transitions(recorded[0])), expected);
+        assert_eq!(statuses(sqlite.transitions(recorded[1]).await), expected);
+    }
+
+    #[tokio::test]
+    async fn recur_todo_copies_the_tags_on_every_backend() {
+        let (memory, sqlite) = stores().await;
+        let rule = "FREQ=DAILY";
+        for store in [&memory as &dyn Store, &sqlite] {
+            store.create_user("alice", "", None).await.unwrap();
+            let created = store.create_todo("alice", todo(Some(rule))).await.unwrap();
+            let tags = ["garden".to_string(), "home".to_string()];
+            store
+                .add_tags("alice", created.id, &tags, 100)
+                .await
+                .unwrap();
+            let next = TodoItemDb {
+                due_at: Some(87_400),
+                ..todo(Some(rule))
+            };
+            let next = store
+                .recur_todo("alice", created.id, rule, Some(next), 200)
+                .await
+                .unwrap()
+                .unwrap();
+            let mut next_tags = next.tags.clone();
+            next_tags.sort();
+            assert_eq!(next_tags, tags);
+            assert_eq!(next.recurrence, rule);
+            let previous = store.get_todo("alice", created.id).await.unwrap().unwrap();
+            assert!(previous.recurrence.is_empty());
+            // A todo is only recurred once per rule.
+            let again = store
+                .recur_todo("alice", created.id, rule, Some(todo(Some(rule))), 300)
+                .await
+                .unwrap();
+            assert!(again.is_none());
+        }
+    }
+}
@@ -0,0 +1,1419 @@
+use crate::db::connection::{acquire_timeout, max_connections};
+use crate::db::models::{
+    AccessDb, InvitationDb, MemberDb, ProjectDb, RevokedTokenDb, TagDb, TodoItemDb,
+    TodoTransitionDb, User,
+};
+use crate::db::pagination::{SqlArg, TodoQuery};
+use crate::db::project::PROJECT_SELECT;
+use crate::db::sharing::INVITATION_SELECT;
+use crate::db::store::{
+    is_unique_violation, placeholders, MemberStore, ProjectStore, SecondFactorStore, Store,
+    TodoStore, UserStore, DELETED_USER_PIN,
+};
+use crate::db::todo::{to_seconds, TODO_COLUMNS};
+use crate::error::ServiceError;
+use proto::service::todo::{ProjectRole, TodoItem, TodoStatus, UpdateTodoRequest};
+use sqlx::mysql::MySqlPoolOptions;
+use sqlx::{MySql, Pool, Transaction};
+use tokio::sync::mpsc::Sender;
+use tracing::info;
+use tracing::log::error;
+
+#[derive(Debug, Clone)]
+pub struct MySqlStore {
+    pool: Pool<MySql>,
+}
+
+impl MySqlStore {
+    pub async fn connect(database_uri: &str) -> Result<Self, sqlx::Error> {
+        let pool = MySqlPoolOptions::new()
+            .max_connections(max_connections())
+            .connect_timeout(acquire_timeout())
+            .connect(database_uri)
+            .await?;
+        Ok(Self { pool })
+    }
+
+    /// Todos with the given ids, whoever owns them.
+    async fn todos_by_ids(&self, ids: &[u32]) -> Result<Vec<TodoItem>, sqlx::Error> {
+        if ids.is_empty() {
+            return Ok(vec![]);
+        }
+        let sql = format!(
+            "select {} from todo t where t.id in ({}) order by t.position, t.id",
+            TODO_COLUMNS,
+            placeholders(ids.len())
+        );
+        let mut query = sqlx::query_as::<_, TodoItemDb>(&sql);
+        for id in ids {
+            query = query.bind(id);
+        }
+        let mut todo_items = query
+            .fetch_all(&self.pool)
+            .await?
+            .into_iter()
+            .map(TodoItem::from)
+            .collect::<Vec<_>>();
+        self.load_related(&mut todo_items).await?;
+        Ok(todo_items)
+    }
+
+    /// Fills in the tags of `todo_items`, ordered by name, and the todos they
+    /// are blocked by.
+    async fn load_related(&self, todo_items: &mut [TodoItem]) -> Result<(), sqlx::Error> {
+        if todo_items.is_empty() {
+            return Ok(());
+        }
+        let ids = placeholders(todo_items.len());
+        let sql = format!(
+            "select tt.todoId, g.name from todo_tag tt INNER JOIN tag g on tt.tagId = g.id where tt.todoId in ({}) order by g.name",
+            ids
+        );
+        let mut query = sqlx::query_as::<_, (u32, String)>(&sql);
+        for todo_item in todo_items.iter() {
+            query = query.bind(todo_item.id);
+        }
+        for (todo_id, name) in query.fetch_all(&self.pool).await? {
+            if let Some(todo_item) = todo_items.iter_mut().find(|todo| todo.id == todo_id) {
+                todo_item.tags.push(name);
+            }
+        }
+        let sql = format!(
+            "select todoId, blockedById from todo_dependency where todoId in ({}) order by blockedById",
+            ids
+        );
+        let mut query = sqlx::query_as::<_, (u32, u32)>(&sql);
+        for todo_item in todo_items.iter() {
+            query = query.bind(todo_item.id);
+        }
+        for (todo_id, blocked_by_id) in query.fetch_all(&self.pool).await? {
+            if let Some(todo_item) = todo_items.iter_mut().find(|todo| todo.id == todo_id) {
+                todo_item.blocked_by.push(blocked_by_id);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Bumps `updated_at` of a todo owned by `username`, returning `false` when
+/// there is no such todo.
+async fn touch_todo(
+    tx: &mut Transaction<'_, MySql>,
+    username: &str,
+    id: u32,
+    updated_at: i64,
+) -> Result<bool, sqlx::Error> {
+    let mysql_result = sqlx::query(
+        "UPDATE todo t INNER JOIN user u on t.userId = u.id SET t.updated_at = ? WHERE t.id = ? and u.username = ?",
+    )
+    .bind(updated_at)
+    .bind(id)
+    .bind(username)
+    .execute(tx)
+    .await?;
+    Ok(mysql_result.rows_affected() > 0)
+}
+
+async fn insert_transition(
+    tx: &mut Transaction<'_, MySql>,
+    transition: &TodoTransitionDb,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "INSERT into todo_transition (todoId, from_status, to_status, changed_by, changed_at) VALUES (?, ?, ?, ?, ?)",
+    )
+    .bind(transition.todo_id)
+    .bind(transition.from_status)
+    .bind(transition.to_status)
+    .bind(&transition.changed_by)
+    .bind(transition.changed_at)
+    .execute(tx)
+    .await
+    .map(|_| ())
+}
+
+/// Position after the last todo of `project_id`, or of the inbox when `None`.
+async fn next_position(
+    tx: &mut Transaction<'_, MySql>,
+    user_id: u32,
+    project_id: Option<u32>,
+) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar::<_, i64>(
+        "select coalesce(max(position) + 1, 0) from todo where userId = ? and projectId <=> ?",
+    )
+    .bind(user_id)
+    .bind(project_id)
+    .fetch_one(tx)
+    .await
+}
+
+/// Inserts `todo` at the end of its project.
+async fn insert_todo(
+    tx: &mut Transaction<'_, MySql>,
+    user_id: u32,
+    todo: TodoItemDb,
+) -> Result<TodoItem, sqlx::Error> {
+    let position = next_position(tx, user_id, todo.project_id).await?;
+    let mysql_result = sqlx::query(
+        "INSERT into todo (title, description, notes, status, priority, due_at, created_at, updated_at, completed_at, projectId, position, parentId, recurrence, reminder_at, userId) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&todo.title)
+    .bind(&todo.description)
+    .bind(&todo.notes)
+    .bind(todo.status)
+    .bind(todo.priority)
+    .bind(todo.due_at)
+    .bind(todo.created_at)
+    .bind(todo.updated_at)
+    .bind(todo.completed_at)
+    .bind(todo.project_id)
+    .bind(position)
+    .bind(todo.parent_id)
+    .bind(&todo.recurrence)
+    .bind(todo.reminder_at)
+    .bind(user_id)
+    .execute(tx)
+    .await?;
+    Ok(TodoItem::from(TodoItemDb {
+        id: mysql_result.last_insert_id() as u32,
+        position,
+        ..todo
+    }))
+}
+
+/// Revokes every live access and refresh token of `username`, returning the
+/// access tokens that were revoked.
+async fn revoke_user_tokens(
+    tx: &mut Transaction<'_, MySql>,
+    username: &str,
+    now: i64,
+) -> Result<Vec<RevokedTokenDb>, sqlx::Error> {
+    let revoked = sqlx::query_as::<_, RevokedTokenDb>(
+        "select a.jti, a.expires_at from access_token a INNER JOIN user u on a.userId = u.id where u.username = ? and a.revoked_at is null and a.expires_at > ? FOR UPDATE",
+    )
+    .bind(username)
+    .bind(now)
+    .fetch_all(&mut *tx)
+    .await?;
+    for statement in [
+        "UPDATE access_token a INNER JOIN user u on a.userId = u.id SET a.revoked_at = ? WHERE u.username = ? and a.revoked_at is null",
+        "UPDATE refresh_token r INNER JOIN user u on r.userId = u.id SET r.revoked_at = ? WHERE u.username = ? and r.revoked_at is null",
+    ] {
+        sqlx::query(statement)
+            .bind(now)
+            .bind(username)
+            .execute(&mut *tx)
+            .await?;
+    }
+    Ok(revoked)
+}
+
+/// Id of `username`, failing with `NotFound` when there is no such user.
+async fn user_id(tx: &mut Transaction<'_, MySql>, username: &str) -> Result<u32, ServiceError> {
+    sqlx::query_scalar::<_, u32>("select id from user where username = ?")
+        .bind(username)
+        .fetch_optional(tx)
+        .await?
+        .ok_or_else(|| ServiceError::NotFound(format!("User {}", username)))
+}
+
+#[tonic::async_trait]
+impl Store for MySqlStore {
+    async fn migrate(&self) -> Result<(), String> {
+        sqlx::migrate!("./migrations/mysql")
+            .run(&self.pool)
+            .await
+            .map_err(|e| format!("Unable to run MySQL migrations: {}", e))
+    }
+}
+
+#[tonic::async_trait]
+impl UserStore for MySqlStore {
+    async fn create_user(
+        &self,
+        username: &str,
+        pin_hash: &str,
+        password_hash: Option<&str>,
+    ) -> Result<User, ServiceError> {
+        let result = sqlx::query("INSERT into user (username, pin, password) VALUES (?, ?, ?)")
+            .bind(username)
+            .bind(pin_hash)
+            .bind(password_hash)
+            .execute(&self.pool)
+            .await;
+        match result {
+            Ok(mysql_result) => {
+                info!("Sign up result is {:?}", mysql_result);
+                Ok(User {
+                    username: username.to_string(),
+                    pin: pin_hash.to_string(),
+                    password: password_hash.map(String::from),
+                    totp_secret: None,
+                    totp_enabled: false,
+                })
+            }
+            Err(e) if is_unique_violation(&e) => {
+                Err(ServiceError::AlreadyExists(format!("User {}", username)))
+            }
+            Err(e) => Err(ServiceError::from(e)),
+        }
+    }
+
+    async fn find_user(&self, username: &str) -> Result<Option<User>, ServiceError> {
+        sqlx::query_as::<_, User>(
+            "select username, pin, password, totp_secret, totp_enabled from user where username = ?",
+        )
+        .bind(username)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(ServiceError::from)
+    }
+
+    async fn update_pin(&self, username: &str, pin_hash: &str) -> Result<(), ServiceError> {
+        sqlx::query("UPDATE user SET pin = ? WHERE username = ?")
+            .bind(pin_hash)
+            .bind(username)
+            .execute(&self.pool)
+            .await
+            .map(|_| ())
+            .map_err(ServiceError::from)
+    }
+
+    async fn update_password(
+        &self,
+        username: &str,
+        password_hash: &str,
+    ) -> Result<(), ServiceError> {
+        sqlx::query("UPDATE user SET password = ? WHERE username = ?")
+            .bind(password_hash)
+            .bind(username)
+            .execute(&self.pool)
+            .await
+            .map(|_| ())
+            .map_err(ServiceError::from)
+    }
+
+    async fn insert_access_token(
+        &self,
+        username: &str,
+        jti: &str,
+        expires_at: i64,
+    ) -> Result<(), ServiceError> {
+        sqlx::query(
+            "INSERT into access_token (jti, userId, expires_at) SELECT ?, id, ? FROM user WHERE username = ?",
+        )
+        .bind(jti)
+        .bind(expires_at)
+        .bind(username)
+        .execute(&self.pool)
+        .await
+        .map(|_| ())
+        .map_err(ServiceError::from)
+    }
+
+    async fn insert_refresh_token(
+        &self,
+        username: &str,
+        token_hash: &str,
+        created_at: i64,
+        expires_at: i64,
+    ) -> Result<(), ServiceError> {
+        sqlx::query(
+            "INSERT into refresh_token (userId, token_hash, created_at, expires_at) SELECT id, ?, ?, ? FROM user WHERE username = ?",
+        )
+        .bind(token_hash)
+        .bind(created_at)
+        .bind(expires_at)
+        .bind(username)
+        .execute(&self.pool)
+        .await
+        .map(|_| ())
+        .map_err(ServiceError::from)
+    }
+
+    async fn consume_refresh_token(
+        &self,
+        token_hash: &str,
+        now: i64,
+    ) -> Result<Option<String>, ServiceError> {
+        let revoked = sqlx::query(
+            "UPDATE refresh_token SET revoked_at = ? WHERE token_hash = ? and revoked_at is null and expires_at > ?",
+        )
+        .bind(now)
+        .bind(token_hash)
+        .bind(now)
+        .execute(&self.pool)
+        .await;
+        match revoked {
+            Ok(mysql_result) if mysql_result.rows_affected() == 0 => return Ok(None),
+            Ok(_) => {}
+            Err(e) => return Err(ServiceError::from(e)),
+        }
+        sqlx::query_scalar::<_, String>(
+            "select u.username from refresh_token r INNER JOIN user u on r.userId = u.id where r.token_hash = ?",
+        )
+        .bind(token_hash)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(ServiceError::from)
+    }
+
+    async fn revoke_access_token(
+        &self,
+        username: &str,
+        jti: &str,
+        now: i64,
+    ) -> Result<(), ServiceError> {
+        sqlx::query(
+            "UPDATE access_token a INNER JOIN user u on a.userId = u.id SET a.revoked_at = ? WHERE a.jti = ? and u.username = ? and a.revoked_at is null",
+        )
+        .bind(now)
+        .bind(jti)
+        .bind(username)
+        .execute(&self.pool)
+        .await
+        .map(|_| ())
+        .map_err(ServiceError::from)
+    }
+
+    async fn revoke_refresh_token(
+        &self,
+        username: &str,
+        token_hash: &str,
+        now: i64,
+    ) -> Result<(), ServiceError> {
+        sqlx::query(
+            "UPDATE refresh_token r INNER JOIN user u on r.userId = u.id SET r.revoked_at = ? WHERE r.token_hash = ? and u.username = ? and r.revoked_at is null",
+        )
+        .bind(now)
+        .bind(token_hash)
+        .bind(username)
+        .execute(&self.pool)
+        .await
+        .map(|_| ())
+        .map_err(ServiceError::from)
+    }
+
+    async fn revoke_all_tokens(
+        &self,
+        username: &str,
+        now: i64,
+    ) -> Result<Vec<RevokedTokenDb>, ServiceError> {
+        let mut tx = self.pool.begin().await?;
+        let revoked = revoke_user_tokens(&mut tx, username, now).await?;
+        tx.commit().await?;
+        Ok(revoked)
+    }
+
+    async fn rename_user(
+        &self,
+        username: &str,
+        new_username: &str,
+        now: i64,
+    ) -> Result<Vec<RevokedTokenDb>, ServiceError> {
+        let mut tx = self.pool.begin().await?;
+        let user_id = user_id(&mut tx, username).await?;
+        let revoked = revoke_user_tokens(&mut tx, username, now).await?;
+        let result = sqlx::query("UPDATE user SET username = ? WHERE id = ?")
+            .bind(new_username)
+            .bind(user_id)
+            .execute(&mut tx)
+            .await;
+        match result {
+            Ok(_) => {}
+            Err(e) if is_unique_violation(&e) => {
+                return Err(ServiceError::AlreadyExists(format!(
+                    "User {}",
+                    new_username
+                )))
+            }
+            Err(e) => return Err(ServiceError::from(e)),
+        }
+        sqlx::query("UPDATE todo_transition SET changed_by = ? WHERE changed_by = ?")
+            .bind(new_username)
+            .bind(username)
+            .execute(&mut tx)
+            .await?;
+        tx.commit().await?;
+        Ok(revoked)
+    }
+
+    async fn delete_user(
+        &self,
+        username: &str,
+        tombstone: &str,
+        now: i64,
+    ) -> Result<(Vec<RevokedTokenDb>, Vec<TodoItem>), ServiceError> {
+        let mut tx = self.pool.begin().await?;
+        let user_id = user_id(&mut tx, username).await?;
+        let revoked = revoke_user_tokens(&mut tx, username, now).await?;
+        let deleted = sqlx::query_as::<_, (u32, Option<u32>)>(
+            "select id, projectId from todo WHERE userId = ?",
+        )
+        .bind(user_id)
+        .fetch_all(&mut tx)
+        .await?
+        .into_iter()
+        .map(|(id, project_id)| TodoItem {
+            id,
+            project_id: project_id.unwrap_or_default(),
+            ..Default::default()
+        })
+        .collect();
+        // Todos of a project belong to its owner, so deleting the todos and
+        // projects of the user leaves no todo pointing at them.
+        for statement in [
+            "DELETE FROM todo WHERE userId = ?",
+            "DELETE FROM project WHERE userId = ?",
+            "DELETE FROM project_member WHERE userId = ?",
+            "DELETE FROM project_invitation WHERE ? in (userId, invitedById)",
+            "DELETE FROM tag WHERE userId = ?",
+            "DELETE FROM recovery_code WHERE userId = ?",
+            "DELETE FROM sign_in_challenge WHERE userId = ?",
+        ] {
+            sqlx::query(statement)
+                .bind(user_id)
+                .execute(&mut tx)
+                .await?;
+        }
+        sqlx::query("UPDATE todo_transition SET changed_by = ? WHERE changed_by = ?")
+            .bind(tombstone)
+            .bind(username)
+            .execute(&mut tx)
+            .await?;
+        sqlx::query(
+            "UPDATE user SET username = ?, pin = ?, password = null, totp_secret = null, totp_enabled = false, totp_last_step = null WHERE id = ?",
+        )
+        .bind(tombstone)
+        .bind(DELETED_USER_PIN)
+        .bind(user_id)
+        .execute(&mut tx)
+        .await?;
+        tx.commit().await?;
+        Ok((revoked, deleted))
+    }
+
+    async fn revoked_tokens(&self, now: i64) -> Result<Vec<RevokedTokenDb>, ServiceError> {
+        sqlx::query_as::<_, RevokedTokenDb>(
+            "select jti, expires_at from access_token where revoked_at is not null and expires_at > ?",
+        )
+        .bind(now)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(ServiceError::from)
+    }
+}
+
+#[tonic::async_trait]
+impl SecondFactorStore for MySqlStore {
+    async fn set_totp_secret(&self, username: &str, secret: &str) -> Result<bool, ServiceError> {
+        let result = sqlx::query(
+            "UPDATE user SET totp_secret = ? WHERE username = ? and totp_enabled = false",
+        )
+        .bind(secret)
+        .bind(username)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn enable_totp(
+        &self,
+        username: &str,
+        step: i64,
+        recovery_code_hashes: &[String],
+    ) -> Result<bool, ServiceError> {
+        let mut tx = self.pool.begin().await?;
+        let user_id = user_id(&mut tx, username).await?;
+        let enabled = sqlx::query(
+            "UPDATE user SET totp_enabled = true, totp_last_step = ? WHERE id = ? and totp_enabled = false and totp_secret is not null",
+        )
+        .bind(step)
+        .bind(user_id)
+        .execute(&mut tx)
+        .await?;
+        if enabled.rows_affected() == 0 {
+            return Ok(false);
+        }
+        sqlx::query("DELETE FROM recovery_code WHERE userId = ?")
+            .bind(user_id)
+            .execute(&mut tx)
+            .await?;
+        for code_hash in recovery_code_hashes {
+            sqlx::query("INSERT into recovery_code (userId, code_hash) VALUES (?, ?)")
+                .bind(user_id)
+                .bind(code_hash)
+                .execute(&mut tx)
+                .await?;
+        }
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    async fn disable_totp(&self, username: &str) -> Result<(), ServiceError> {
+        let mut tx = self.pool.begin().await?;
+        let user_id = user_id(&mut tx, username).await?;
+        for statement in [
+            "UPDATE user SET totp_secret = null, totp_enabled = false, totp_last_step = null WHERE id = ?",
+            "DELETE FROM recovery_code WHERE userId = ?",
+            "DELETE FROM sign_in_challenge WHERE userId = ?",
+        ] {
+            sqlx::query(statement)
+                .bind(user_id)
+                .execute(&mut tx)
+                .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn use_totp_step(&self, username: &str, step: i64) -> Result<bool, ServiceError> {
+        let result = sqlx::query(
+            "UPDATE user SET totp_last_step = ? WHERE username = ? and (totp_last_step is null or totp_last_step < ?)",
+        )
+        .bind(step)
+        .bind(username)
+        .bind(step)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn use_recovery_code(
+        &self,
+        username: &str,
+        code_hash: &str,
+        now: i64,
+    ) -> Result<bool, ServiceError> {
+        let result = sqlx::query(
+            "UPDATE recovery_code r INNER JOIN user u on r.userId = u.id SET r.used_at = ? WHERE u.username = ? and r.code_hash = ? and r.used_at is null",
+        )
+        .bind(now)
+        .bind(username)
+        .bind(code_hash)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn insert_challenge(
+        &self,
+        username: &str,
+        token_hash: &str,
+        expires_at: i64,
+    ) -> Result<(), ServiceError> {
+        sqlx::query(
+            "INSERT into sign_in_challenge (userId, token_hash, expires_at) SELECT id, ?, ? FROM user WHERE username = ?",
+        )
+        .bind(token_hash)
+        .bind(expires_at)
+        .bind(username)
+        .execute(&self.pool)
+        .await
+        .map(|_| ())
+        .map_err(ServiceError::from)
+    }
+
+    async fn attempt_challenge(
+        &self,
+        token_hash: &str,
+        now: i64,
+        max_attempts: i32,
+    ) -> Result<Option<String>, ServiceError> {
+        let attempted = sqlx::query(
+            "UPDATE sign_in_challenge SET attempts = attempts + 1 WHERE token_hash = ? and consumed_at is null and expires_at > ? and attempts < ?",
+        )
+        .bind(token_hash)
+        .bind(now)
+        .bind(max_attempts)
+        .execute(&self.pool)
+        .await?;
+        if attempted.rows_affected() == 0 {
+            return Ok(None);
+        }
+        sqlx::query_scalar::<_, String>(
+            "select u.username from sign_in_challenge c INNER JOIN user u on c.userId = u.id where c.token_hash = ?",
+        )
+        .bind(token_hash)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(ServiceError::from)
+    }
+
+    async fn consume_challenge(&self, token_hash: &str, now: i64) -> Result<bool, ServiceError> {
+        let result = sqlx::query(
+            "UPDATE sign_in_challenge SET consumed_at = ? WHERE token_hash = ? and consumed_at is null",
+        )
+        .bind(now)
+        .bind(token_hash)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+#[tonic::async_trait]
+impl TodoStore for MySqlStore {
+    async fn get_todos(
+        &self,
+        username: &str,
+        query: &TodoQuery,
+        resp: Sender<Result<TodoItem, ServiceError>>,
+    ) {
+        let (sql, args) = query.to_sql(username);
+        let mut todo_query = sqlx::query_as::<_, TodoItemDb>(&sql);
+        for arg in args {
+            todo_query = match arg {
+                SqlArg::Int(value) => todo_query.bind(value),
+                SqlArg::Text(value) => todo_query.bind(value),
+            };
+        }
+        // Pages are bounded, so they are read whole to load their tags at once.
+        let mut todo_items = match todo_query.fetch_all(&self.pool).await {
+            Ok(rows) => rows.into_iter().map(TodoItem::from).collect::<Vec<_>>(),
+            Err(e) => {
+                let _ = resp.send(Err(ServiceError::from(e))).await;
+                return;
+            }
+        };
+        if let Err(e) = self.load_related(&mut todo_items).await {
+            let _ = resp.send(Err(ServiceError::from(e))).await;
+            return;
+        }
+        for todo_item in todo_items {
+            if let Err(e) = resp.send(Ok(todo_item)).await {
+                // The receiver is gone, so the client has disconnected.
+                error!("Unable to send back from Get todos {:?}", e);
+                break;
+            }
+        }
+    }
+
+    async fn get_todo(&self, username: &str, id: u32) -> Result<Option<TodoItem>, ServiceError> {
+        let sql = format!(
+            "select {} from todo t INNER JOIN user u on t.userId = u.id where t.id = ? and u.username = ?",
+            TODO_COLUMNS
+        );
+        let result = sqlx::query_as::<_, TodoItemDb>(&sql)
+            .bind(id)
+            .bind(username)
+            .fetch_optional(&self.pool)
+            .await;
+        let mut todo_items = match result {
+            Ok(todo_item_db) => todo_item_db
+                .map(TodoItem::from)
+                .into_iter()
+                .collect::<Vec<_>>(),
+            Err(e) => return Err(ServiceError::from(e)),
+        };
+        self.load_related(&mut todo_items).await?;
+        Ok(todo_items.pop())
+    }
+
+    async fn create_todo(
+        &self,
+        username: &str,
+        todo: TodoItemDb,
+    ) -> Result<TodoItem, ServiceError> {
+        let mut tx = self.pool.begin().await?;
+        let user_id = user_id(&mut tx, username).await?;
+        let todo_item = insert_todo(&mut tx, user_id, todo).await?;
+        tx.commit().await?;
+        Ok(todo_item)
+    }
+
+    async fn import_todos(
+        &self,
+        username: &str,
+        todos: Vec<TodoItemDb>,
+    ) -> Result<Vec<TodoItem>, ServiceError> {
+        let mut tx = self.pool.begin().await?;
+        let user_id = user_id(&mut tx, username).await?;
+        let mut imported = Vec::with_capacity(todos.len());
+        for todo in todos {
+            imported.push(insert_todo(&mut tx, user_id, todo).await?);
+        }
+        tx.commit().await?;
+        Ok(imported)
+    }
+
+    async fn update_todo(
+        &self,
+        username: &str,
+        req: UpdateTodoRequest,
+        expected_status: i32,
+        updated_at: i64,
+    ) -> Result<Option<TodoItem>, ServiceError> {
+        let mut tx = self.pool.begin().await?;
+        let mysql_result = sqlx::query(
+            "UPDATE todo t INNER JOIN user u on t.userId = u.id SET t.title = ?, t.description = ?, t.notes = ?, t.status = ?, t.priority = ?, t.due_at = ?, t.recurrence = ?, t.reminder_at = ?, t.updated_at = ?, t.completed_at = CASE WHEN ? THEN coalesce(t.completed_at, ?) ELSE NULL END WHERE t.id = ? and u.username = ? and t.status = ?",
+        )
+        .bind(req.title)
+        .bind(req.description)
+        .bind(req.notes)
+        .bind(req.status)
+        .bind(req.priority)
+        .bind(to_seconds(&req.due_at))
+        .bind(Some(req.recurrence).filter(|recurrence| !recurrence.is_empty()))
+        .bind(to_seconds(&req.reminder_at))
+        .bind(updated_at)
+        .bind(req.status == TodoStatus::Completed as i32)
+        .bind(updated_at)
+        .bind(req.id)
+        .bind(username)
+        .bind(expected_status)
+        .execute(&mut tx)
+        .await?;
+        if mysql_result.rows_affected() == 0 {
+            return Ok(None);
+        }
+        if req.status != expected_status {
+            let transition = TodoTransitionDb {
+                todo_id: req.id,
+                from_status: expected_status,
+                to_status: req.status,
+                changed_by: username.to_string(),
+                changed_at: updated_at,
+            };
+            insert_transition(&mut tx, &transition).await?;
+        }
+        tx.commit().await?;
+        self.get_todo(username, req.id).await
+    }
+
+    async fn transition_todo(
+        &self,
+        username: &str,
+        transition: &TodoTransitionDb,
+    ) -> Result<Option<TodoItem>, ServiceError> {
+        let mut tx = self.pool.begin().await?;
+        let mysql_result = sqlx::query(
+            "UPDATE todo t INNER JOIN user u on t.userId = u.id SET t.status = ?, t.updated_at = ?, t.completed_at = CASE WHEN ? THEN coalesce(t.completed_at, ?) ELSE NULL END WHERE t.id = ? and u.username = ? and t.status = ?",
+        )
+        .bind(transition.to_status)
+        .bind(transition.changed_at)
+        .bind(transition.to_status == TodoStatus::Completed as i32)
+        .bind(transition.changed_at)
+        .bind(transition.todo_id)
+        .bind(username)
+        .bind(transition.from_status)
+        .execute(&mut tx)
+        .await?;
+        if mysql_result.rows_affected() == 0 {
+            return Ok(None);
+        }
+        insert_transition(&mut tx, transition).await?;
+        tx.commit().await?;
+        self.get_todo(username, transition.todo_id).await
+    }
+
+    async fn delete_todo(&self, username: &str, id: u32) -> Result<bool, ServiceError> {
+        let result = sqlx::query(
+            "DELETE t FROM todo t INNER JOIN user u on t.userId = u.id WHERE t.id = ? and u.username = ?",
+        )
+        .bind(id)
+        .bind(username)
+        .execute(&self.pool)
+        .await;
+        match result {
+            Ok(mysql_result) => Ok(mysql_result.rows_affected() > 0),
+            Err(e) => Err(ServiceError::from(e)),
+        }
+    }
+
+    async fn add_tags(
+        &self,
+        username: &str,
+        id: u32,
+        tags: &[String],
+        updated_at: i64,
+    ) -> Result<Option<TodoItem>, ServiceError> {
+        let mut tx = self.pool.begin().await?;
+        if !touch_todo(&mut tx, username, id, updated_at).await? {
+            return Ok(None);
+        }
+        let user_id = user_id(&mut tx, username).await?;
+        for tag in tags {
+            sqlx::query("INSERT IGNORE into tag (userId, name) VALUES (?, ?)")
+                .bind(user_id)
+                .bind(tag)
+                .execute(&mut tx)
+                .await?;
+            sqlx::query(
+                "INSERT IGNORE into todo_tag (todoId, tagId) SELECT ?, id FROM tag WHERE userId = ? and name = ?",
+            )
+            .bind(id)
+            .bind(user_id)
+            .bind(tag)
+            .execute(&mut tx)
+            .await?;
+        }
+        tx.commit().await?;
+        self.get_todo(username, id).await
+    }
+
+    async fn remove_tags(
+        &self,
+        username: &str,
+        id: u32,
+        tags: &[String],
+        updated_at: i64,
+    ) -> Result<Option<TodoItem>, ServiceError> {
+        let mut tx = self.pool.begin().await?;
+        if !touch_todo(&mut tx, username, id, updated_at).await? {
+            return Ok(None);
+        }
+        if !tags.is_empty() {
+            let sql = format!(
+                "DELETE FROM todo_tag WHERE todoId = ? and tagId in (select g.id from tag g INNER JOIN user u on g.userId = u.id where u.username = ? and g.name in ({}))",
+                placeholders(tags.len())
+            );
+            let mut query = sqlx::query(&sql).bind(id).bind(username);
+            for tag in tags {
+                query = query.bind(tag);
+            }
+            query.execute(&mut tx).await?;
+        }
+        tx.commit().await?;
+        self.get_todo(username, id).await
+    }
+
+    async fn list_tags(&self, username: &str) -> Result<Vec<TagDb>, ServiceError> {
+        sqlx::query_as::<_, TagDb>(
+            "select g.name, count(*) as todo_count from tag g INNER JOIN todo_tag tt on tt.tagId = g.id INNER JOIN user u on g.userId = u.id where u.username = ? group by g.id, g.name order by g.name",
+        )
+        .bind(username)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(ServiceError::from)
+    }
+
+    async fn move_todo(
+        &self,
+        username: &str,
+        id: u32,
+        project_id: Option<u32>,
+        position: u32,
+        updated_at: i64,
+    ) -> Result<Option<TodoItem>, ServiceError> {
+        let mut tx = self.pool.begin().await?;
+        let user_id = sqlx::query_scalar::<_, u32>(
+            "select t.userId from todo t INNER JOIN user u on t.userId = u.id where t.id = ? and u.username = ? FOR UPDATE",
+        )
+        .bind(id)
+        .bind(username)
+        .fetch_optional(&mut tx)
+        .await?;
+        let user_id = match user_id {
+            Some(user_id) => user_id,
+            None => return Ok(None),
+        };
+        let mut siblings = sqlx::query_as::<_, (u32, i64)>(
+            "select id, position from todo where userId = ? and projectId <=> ? and id <> ? order by position, id FOR UPDATE",
+        )
+        .bind(user_id)
+        .bind(project_id)
+        .bind(id)
+        .fetch_all(&mut tx)
+        .await?;
+        let index = (position as usize).min(siblings.len());
+        siblings.insert(index, (id, -1));
+        for (new_position, (todo_id, old_position)) in siblings.into_iter().enumerate() {
+            if new_position as i64 == old_position {
+                continue;
+            }
+            sqlx::query("UPDATE todo SET position = ? WHERE id = ?")
+                .bind(new_position as i64)
+                .bind(todo_id)
+                .execute(&mut tx)
+                .await?;
+        }
+        sqlx::query("UPDATE todo SET projectId = ?, updated_at = ? WHERE id = ?")
+            .bind(project_id)
+            .bind(updated_at)
+            .bind(id)
+            .execute(&mut tx)
+            .await?;
+        tx.commit().await?;
+        self.get_todo(username, id).await
+    }
+
+    async fn set_parent(
+        &self,
+        username: &str,
+        id: u32,
+        parent_id: Option<u32>,
+        updated_at: i64,
+    ) -> Result<Option<TodoItem>, ServiceError> {
+        let mysql_result = sqlx::query(
+            "UPDATE todo t INNER JOIN user u on t.userId = u.id SET t.parentId = ?, t.updated_at = ? WHERE t.id = ? and u.username = ?",
+        )
+        .bind(parent_id)
+        .bind(updated_at)
+        .bind(id)
+        .bind(username)
+        .execute(&self.pool)
+        .await?;
+        if mysql_result.rows_affected() == 0 {
+            return Ok(None);
+        }
+        self.get_todo(username, id).await
+    }
+
+    async fn get_subtasks(
+        &self,
+        username: &str,
+        parent_id: u32,
+    ) -> Result<Vec<TodoItem>, ServiceError> {
+        let sql = format!(
+            "select {} from todo t INNER JOIN user u on t.userId = u.id where t.parentId = ? and u.username = ? order by t.position, t.id",
+            TODO_COLUMNS
+        );
+        let mut todo_items = sqlx::query_as::<_, TodoItemDb>(&sql)
+            .bind(parent_id)
+            .bind(username)
+            .fetch_all(&self.pool)
+            .await?
+            .into_iter()
+            .map(TodoItem::from)
+            .collect::<Vec<_>>();
+        self.load_related(&mut todo_items).await?;
+        Ok(todo_items)
+    }
+
+    async fn get_parent_links(&self, username: &str) -> Result<Vec<(u32, u32)>, ServiceError> {
+        sqlx::query_as::<_, (u32, u32)>(
+            "select t.id, t.parentId from todo t INNER JOIN user u on t.userId = u.id where t.parentId is not null and u.username = ?",
+        )
+        .bind(username)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(ServiceError::from)
+    }
+
+    async fn get_dependency_links(&self, username: &str) -> Result<Vec<(u32, u32)>, ServiceError> {
+        sqlx::query_as::<_, (u32, u32)>(
+            "select d.todoId, d.blockedById from todo_dependency d INNER JOIN todo t on d.todoId = t.id INNER JOIN user u on t.userId = u.id where u.username = ?",
+        )
+        .bind(username)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(ServiceError::from)
+    }
+
+    async fn link_todos(
+        &self,
+        username: &str,
+        id: u32,
+        blocked_by_id: u32,
+        updated_at: i64,
+    ) -> Result<Option<TodoItem>, ServiceError> {
+        let mut tx = self.pool.begin().await?;
+        if !touch_todo(&mut tx, username, id, updated_at).await? {
+            return Ok(None);
+        }
+        sqlx::query("INSERT IGNORE into todo_dependency (todoId, blockedById) VALUES (?, ?)")
+            .bind(id)
+            .bind(blocked_by_id)
+            .execute(&mut tx)
+            .await?;
+        tx.commit().await?;
+        self.get_todo(username, id).await
+    }
+
+    async fn unlink_todos(
+        &self,
+        username: &str,
+        id: u32,
+        blocked_by_id: u32,
+        updated_at: i64,
+    ) -> Result<Option<TodoItem>, ServiceError> {
+        let mut tx = self.pool.begin().await?;
+        if !touch_todo(&mut tx, username, id, updated_at).await? {
+            return Ok(None);
+        }
+        sqlx::query("DELETE FROM todo_dependency WHERE todoId = ? and blockedById = ?")
+            .bind(id)
+            .bind(blocked_by_id)
+            .execute(&mut tx)
+            .await?;
+        tx.commit().await?;
+        self.get_todo(username, id).await
+    }
+
+    async fn recur_todo(
+        &self,
+        username: &str,
+        id: u32,
+        recurrence: &str,
+        next: Option<TodoItemDb>,
+        updated_at: i64,
+    ) -> Result<Option<TodoItem>, ServiceError> {
+        let mut tx = self.pool.begin().await?;
+        let mysql_result = sqlx::query(
+            "UPDATE todo t INNER JOIN user u on t.userId = u.id SET t.recurrence = NULL, t.updated_at = ? WHERE t.id = ? and u.username = ? and t.recurrence = ?",
+        )
+        .bind(updated_at)
+        .bind(id)
+        .bind(username)
+        .bind(recurrence)
+        .execute(&mut tx)
+        .await?;
+        if mysql_result.rows_affected() == 0 {
+            return Ok(None);
+        }
+        let next = match next {
+            Some(next) => next,
+            None => {
+                tx.commit().await?;
+                return Ok(None);
+            }
+        };
+        let user_id = user_id(&mut tx, username).await?;
+        let todo_item = insert_todo(&mut tx, user_id, next).await?;
+        sqlx::query(
+            "INSERT INTO todo_tag (todoId, tagId) SELECT ?, tagId FROM todo_tag WHERE todoId = ?",
+        )
+        .bind(todo_item.id)
+        .bind(id)
+        .execute(&mut tx)
+        .await?;
+        tx.commit().await?;
+        self.get_todo(username, todo_item.id).await
+    }
+
+    async fn get_overdue_recurrences(&self, now: i64) -> Result<Vec<(String, u32)>, ServiceError> {
+        sqlx::query_as::<_, (String, u32)>(
+            "select u.username, t.id from todo t INNER JOIN user u on t.userId = u.id where t.recurrence is not null and t.due_at < ? and t.status in (?, ?, ?)",
+        )
+        .bind(now)
+        .bind(TodoStatus::Active as i32)
+        .bind(TodoStatus::InProgress as i32)
+        .bind(TodoStatus::Blocked as i32)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(ServiceError::from)
+    }
+
+    async fn get_due_reminders(&self, now: i64) -> Result<Vec<(String, u32, i64)>, ServiceError> {
+        sqlx::query_as::<_, (String, u32, i64)>(
+            "select u.username, t.id, t.reminder_at from todo t INNER JOIN user u on t.userId = u.id where t.reminder_at <= ? and (t.delivered_reminder_at is null or t.delivered_reminder_at <> t.reminder_at) and t.status in (?, ?, ?) order by t.reminder_at, t.id",
+        )
+        .bind(now)
+        .bind(TodoStatus::Active as i32)
+        .bind(TodoStatus::InProgress as i32)
+        .bind(TodoStatus::Blocked as i32)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(ServiceError::from)
+    }
+
+    async fn mark_reminder_delivered(
+        &self,
+        username: &str,
+        id: u32,
+        reminder_at: i64,
+    ) -> Result<bool, ServiceError> {
+        let mysql_result = sqlx::query(
+            "UPDATE todo t INNER JOIN user u on t.userId = u.id SET t.delivered_reminder_at = t.reminder_at WHERE t.id = ? and u.username = ? and t.reminder_at = ? and (t.delivered_reminder_at is null or t.delivered_reminder_at <> t.reminder_at)",
+        )
+        .bind(id)
+        .bind(username)
+        .bind(reminder_at)
+        .execute(&self.pool)
+        .await?;
+        Ok(mysql_result.rows_affected() > 0)
+    }
+}
+
+#[tonic::async_trait]
+impl ProjectStore for MySqlStore {
+    async fn create_project(
+        &self,
+        username: &str,
+        project: ProjectDb,
+    ) -> Result<ProjectDb, ServiceError> {
+        let mut tx = self.pool.begin().await?;
+        let user_id = user_id(&mut tx, username).await?;
+        let result = sqlx::query(
+            "INSERT into project (name, created_at, updated_at, userId) VALUES (?, ?, ?, ?)",
+        )
+        .bind(&project.name)
+        .bind(project.created_at)
+        .bind(project.updated_at)
+        .bind(user_id)
+        .execute(&mut tx)
+        .await;
+        let id = match result {
+            Ok(mysql_result) => mysql_result.last_insert_id() as u32,
+            Err(e) if is_unique_violation(&e) => {
+                return Err(ServiceError::AlreadyExists(format!(
+                    "Project {}",
+                    project.name
+                )))
+            }
+            Err(e) => return Err(ServiceError::from(e)),
+        };
+        sqlx::query("INSERT into project_member (projectId, userId, role) VALUES (?, ?, ?)")
+            .bind(id)
+            .bind(user_id)
+            .bind(ProjectRole::Owner as i32)
+            .execute(&mut tx)
+            .await?;
+        tx.commit().await?;
+        Ok(ProjectDb {
+            id,
+            owner: username.to_string(),
+            role: ProjectRole::Owner as i32,
+            ..project
+        })
+    }
+
+    async fn get_projects(&self, username: &str) -> Result<Vec<ProjectDb>, ServiceError> {
+        let sql = format!("{} where mu.username = ? order by p.name", PROJECT_SELECT);
+        sqlx::query_as::<_, ProjectDb>(&sql)
+            .bind(username)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(ServiceError::from)
+    }
+
+    async fn get_project(
+        &self,
+        username: &str,
+        id: u32,
+    ) -> Result<Option<ProjectDb>, ServiceError> {
+        let sql = format!("{} where p.id = ? and mu.username = ?", PROJECT_SELECT);
+        sqlx::query_as::<_, ProjectDb>(&sql)
+            .bind(id)
+            .bind(username)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(ServiceError::from)
+    }
+
+    async fn rename_project(
+        &self,
+        username: &str,
+        id: u32,
+        name: &str,
+        updated_at: i64,
+    ) -> Result<Option<ProjectDb>, ServiceError> {
+        let result = sqlx::query(
+            "UPDATE project p INNER JOIN user u on p.userId = u.id SET p.name = ?, p.updated_at = ? WHERE p.id = ? and u.username = ?",
+        )
+        .bind(name)
+        .bind(updated_at)
+        .bind(id)
+        .bind(username)
+        .execute(&self.pool)
+        .await;
+        match result {
+            Ok(_) => self.get_project(username, id).await,
+            Err(e) if is_unique_violation(&e) => {
+                Err(ServiceError::AlreadyExists(format!("Project {}", name)))
+            }
+            Err(e) => Err(ServiceError::from(e)),
+        }
+    }
+
+    async fn delete_project(
+        &self,
+        username: &str,
+        id: u32,
+        updated_at: i64,
+    ) -> Result<Option<Vec<TodoItem>>, ServiceError> {
+        let mut tx = self.pool.begin().await?;
+        let user_id = sqlx::query_scalar::<_, u32>(
+            "select p.userId from project p INNER JOIN user u on p.userId = u.id where p.id = ? and u.username = ? FOR UPDATE",
+        )
+        .bind(id)
+        .bind(username)
+        .fetch_optional(&mut tx)
+        .await?;
+        let user_id = match user_id {
+            Some(user_id) => user_id,
+            None => return Ok(None),
+        };
+        let moved =
+            sqlx::query_scalar::<_, u32>("select id from todo where projectId = ? FOR UPDATE")
+                .bind(id)
+                .fetch_all(&mut tx)
+                .await?;
+        let inbox_end = next_position(&mut tx, user_id, None).await?;
+        sqlx::query(
+            "UPDATE todo SET projectId = NULL, position = position + ?, updated_at = ? WHERE projectId = ?",
+        )
+        .bind(inbox_end)
+        .bind(updated_at)
+        .bind(id)
+        .execute(&mut tx)
+        .await?;
+        sqlx::query("DELETE FROM project WHERE id = ?")
+            .bind(id)
+            .execute(&mut tx)
+            .await?;
+        tx.commit().await?;
+        Ok(Some(self.todos_by_ids(&moved).await?))
+    }
+}
+
+#[tonic::async_trait]
+impl MemberStore for MySqlStore {
+    async fn todo_access(&self, username: &str, id: u32) -> Result<Option<AccessDb>, ServiceError> {
+        sqlx::query_as::<_, AccessDb>(
+            "select o.username as owner, m.role from todo t INNER JOIN user o on t.userId = o.id LEFT JOIN project_member m on m.projectId = t.projectId and m.userId = (select id from user where username = ?) where t.id = ? and (o.username = ? or m.role is not null)",
+        )
+        .bind(username)
+        .bind(id)
+        .bind(username)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(ServiceError::from)
+    }
+
+    async fn get_members(&self, project_id: u32) -> Result<Vec<MemberDb>, ServiceError> {
+        sqlx::query_as::<_, MemberDb>(
+            "select u.username, m.role from project_member m INNER JOIN user u on m.userId = u.id where m.projectId = ? order by u.username",
+        )
+        .bind(project_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(ServiceError::from)
+    }
+
+    async fn set_member_role(
+        &self,
+        project_id: u32,
+        username: &str,
+        role: i32,
+    ) -> Result<bool, ServiceError> {
+        let mysql_result = sqlx::query(
+            "UPDATE project_member m INNER JOIN user u on m.userId = u.id SET m.role = ? WHERE m.projectId = ? and u.username = ?",
+        )
+        .bind(role)
+        .bind(project_id)
+        .bind(username)
+        .execute(&self.pool)
+        .await?;
+        Ok(mysql_result.rows_affected() > 0)
+    }
+
+    async fn remove_member(
+        &self,
+        project_id: u32,
+        username: &str,
+    ) -> Result<Option<Vec<u32>>, ServiceError> {
+        let mut tx = self.pool.begin().await?;
+        let mysql_result = sqlx::query(
+            "DELETE m FROM project_member m INNER JOIN user u on m.userId = u.id WHERE m.projectId = ? and u.username = ?",
+        )
+        .bind(project_id)
+        .bind(username)
+        .execute(&mut tx)
+        .await?;
+        if mysql_result.rows_affected() == 0 {
+            return Ok(None);
+        }
+        let todo_ids = sqlx::query_scalar::<_, u32>("select id from todo where projectId = ?")
+            .bind(project_id)
+            .fetch_all(&mut tx)
+            .await?;
+        tx.commit().await?;
+        Ok(Some(todo_ids))
+    }
+
+    async fn create_invitation(
+        &self,
+        project_id: u32,
+        username: &str,
+        role: i32,
+        invited_by: &str,
+        created_at: i64,
+    ) -> Result<InvitationDb, ServiceError> {
+        let result = sqlx::query(
+            "INSERT into project_invitation (projectId, userId, role, invitedById, created_at) SELECT ?, i.id, ?, b.id, ? FROM user i, user b WHERE i.username = ? and b.username = ?",
+        )
+        .bind(project_id)
+        .bind(role)
+        .bind(created_at)
+        .bind(username)
+        .bind(invited_by)
+        .execute(&self.pool)
+        .await;
+        let id = match result {
+            Ok(mysql_result) if mysql_result.rows_affected() == 0 => {
+                return Err(ServiceError::NotFound(format!("User {}", username)))
+            }
+            Ok(mysql_result) => mysql_result.last_insert_id() as u32,
+            Err(e) if is_unique_violation(&e) => {
+                return Err(ServiceError::AlreadyExists(format!(
+                    "Invitation of {}",
+                    username
+                )))
+            }
+            Err(e) => return Err(ServiceError::from(e)),
+        };
+        let sql = format!("{} where i.id = ?", INVITATION_SELECT);
+        sqlx::query_as::<_, InvitationDb>(&sql)
+            .bind(id)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(ServiceError::from)
+    }
+
+    async fn get_invitations(&self, username: &str) -> Result<Vec<InvitationDb>, ServiceError> {
+        let sql = format!(
+            "{} INNER JOIN user u on i.userId = u.id where u.username = ? order by i.id",
+            INVITATION_SELECT
+        );
+        sqlx::query_as::<_, InvitationDb>(&sql)
+            .bind(username)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(ServiceError::from)
+    }
+
+    async fn accept_invitation(
+        &self,
+        username: &str,
+        id: u32,
+    ) -> Result<Option<u32>, ServiceError> {
+        let mut tx = self.pool.begin().await?;
+        let invitation = sqlx::query_as::<_, (u32, u32, i32)>(
+            "select i.projectId, i.userId, i.role from project_invitation i INNER JOIN user u on i.userId = u.id where i.id = ? and u.username = ? FOR UPDATE",
+        )
+        .bind(id)
+        .bind(username)
+        .fetch_optional(&mut tx)
+        .await?;
+        let (project_id, user_id, role) = match invitation {
+            Some(invitation) => invitation,
+            None => return Ok(None),
+        };
+        sqlx::query(
+            "INSERT into project_member (projectId, userId, role) VALUES (?, ?, ?) ON DUPLICATE KEY UPDATE role = VALUES(role)",
+        )
+        .bind(project_id)
+        .bind(user_id)
+        .bind(role)
+        .execute(&mut tx)
+        .await?;
+        sqlx::query("DELETE FROM project_invitation WHERE id = ?")
+            .bind(id)
+            .execute(&mut tx)
+            .await?;
+        tx.commit().await?;
+        Ok(Some(project_id))
+    }
+
+    async fn decline_invitation(&self, username: &str, id: u32) -> Result<bool, ServiceError> {
+        let mysql_result = sqlx::query(
+            "DELETE i FROM project_invitation i INNER JOIN user u on i.userId = u.id WHERE i.id = ? and u.username = ?",
+        )
+        .bind(id)
+        .bind(username)
+        .execute(&self.pool)
+        .await?;
+        Ok(mysql_result.rows_affected() > 0)
+    }
+}
@@ -0,0 +1,46 @@
+use crate::error::ServiceError;
+use proto::service::todo::Tag;
+use sqlx::FromRow;
+
+pub const MAX_TAG_LENGTH: usize = 64;
+
+/// A tag of a user with the number of their todos carrying it.
+#[derive(Debug, FromRow, Clone)]
+pub struct TagDb {
+    pub name: String,
+    pub todo_count: i64,
+}
+
+impl From<TagDb> for Tag {
+    fn from(tag: TagDb) -> Self {
+        Tag {
+            name: tag.name,
+            todo_count: tag.todo_count as u32,
+        }
+    }
+}
+
+/// Trims and lower-cases tag names so that they compare the same in every
+/// backend, dropping duplicates. `field` names the request field in errors.
+pub fn normalize_tags(field: &str, tags: Vec<String>) -> Result<Vec<String>, ServiceError> {
+    let mut normalized: Vec<String> = Vec::with_capacity(tags.len());
+    for tag in tags {
+        let tag = tag.trim().to_lowercase();
+        if tag.is_empty() {
+            return Err(ServiceError::invalid_argument(
+                field,
+                "Tags must not be empty",
+            ));
+        }
+        if tag.chars().count() > MAX_TAG_LENGTH {
+            return Err(ServiceError::invalid_argument(
+                field,
+                format!("Tags must be at most {} characters", MAX_TAG_LENGTH),
+            ));
+        }
+        if !normalized.contains(&tag) {
+            normalized.push(tag);
+        }
+    }
+    Ok(normalized)
+}
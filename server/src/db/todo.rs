@@ -1,6 +1,162 @@
-// #[derive(Debug, FromRow, Clone)]
+use prost_types::Timestamp;
+use proto::service::todo::{CreateTodoRequest, TodoItem, TodoStatus, TodoTransition};
+use sqlx::FromRow;
+
+/// Columns of `todo` selected into a `TodoItemDb`, for a table aliased `t`.
+pub const TODO_COLUMNS: &str = "t.id, t.title, t.description, t.notes, t.status, t.priority, t.due_at, t.created_at, t.updated_at, t.completed_at, t.projectId as project_id, t.position, t.parentId as parent_id, t.recurrence, t.reminder_at";
+
+/// Times are stored as seconds since the Unix epoch.
+#[derive(Debug, FromRow, Clone)]
 pub struct TodoItemDb {
     pub id: u32,
+    pub title: String,
     pub description: String,
+    pub notes: String,
     pub status: i32,
+    pub priority: i32,
+    pub due_at: Option<i64>,
+    pub created_at: i64,
+    pub updated_at: i64,
+    pub completed_at: Option<i64>,
+    /// `None` for todos in the inbox.
+    pub project_id: Option<u32>,
+    pub position: i64,
+    /// `None` for top-level todos.
+    pub parent_id: Option<u32>,
+    /// Canonical recurrence rule, `None` for todos that do not recur.
+    pub recurrence: Option<String>,
+    pub reminder_at: Option<i64>,
+}
+
+pub fn to_timestamp(seconds: i64) -> Timestamp {
+    Timestamp { seconds, nanos: 0 }
+}
+
+pub fn to_seconds(timestamp: &Option<Timestamp>) -> Option<i64> {
+    timestamp.as_ref().map(|timestamp| timestamp.seconds)
+}
+
+/// Completion time of a todo with `status`: kept when it was already
+/// completed, `now` when it has just been completed.
+pub fn completed_at(status: i32, completed_at: Option<i64>, now: i64) -> Option<i64> {
+    if status == TodoStatus::Completed as i32 {
+        Some(completed_at.unwrap_or(now))
+    } else {
+        None
+    }
+}
+
+impl TodoItemDb {
+    /// Row of a todo created at `now`. The id and position are assigned on
+    /// insert.
+    pub fn new(req: CreateTodoRequest, now: i64) -> Self {
+        Self {
+            id: 0,
+            title: req.title,
+            description: req.description,
+            notes: req.notes,
+            status: req.status,
+            priority: req.priority,
+            due_at: to_seconds(&req.due_at),
+            created_at: now,
+            updated_at: now,
+            completed_at: completed_at(req.status, None, now),
+            project_id: Some(req.project_id).filter(|project_id| *project_id != 0),
+            position: 0,
+            parent_id: Some(req.parent_id).filter(|parent_id| *parent_id != 0),
+            recurrence: Some(req.recurrence).filter(|recurrence| !recurrence.is_empty()),
+            reminder_at: to_seconds(&req.reminder_at),
+        }
+    }
+
+    /// Row of an imported todo, which keeps its timestamps when they are set.
+    pub fn imported(todo_item: TodoItem, now: i64) -> Self {
+        let created_at = to_seconds(&todo_item.created_at).unwrap_or(now);
+        Self {
+            id: 0,
+            title: todo_item.title,
+            description: todo_item.description,
+            notes: todo_item.notes,
+            status: todo_item.status,
+            priority: todo_item.priority,
+            due_at: to_seconds(&todo_item.due_at),
+            created_at,
+            updated_at: now,
+            completed_at: completed_at(
+                todo_item.status,
+                to_seconds(&todo_item.completed_at),
+                created_at,
+            ),
+            project_id: None,
+            position: 0,
+            parent_id: None,
+            recurrence: Some(todo_item.recurrence).filter(|recurrence| !recurrence.is_empty()),
+            reminder_at: to_seconds(&todo_item.reminder_at),
+        }
+    }
+}
+
+impl From<TodoItemDb> for TodoItem {
+    fn from(todo_item_db: TodoItemDb) -> Self {
+        TodoItem {
+            id: todo_item_db.id,
+            title: todo_item_db.title,
+            description: todo_item_db.description,
+            notes: todo_item_db.notes,
+            status: todo_item_db.status,
+            priority: todo_item_db.priority,
+            due_at: todo_item_db.due_at.map(to_timestamp),
+            created_at: Some(to_timestamp(todo_item_db.created_at)),
+            updated_at: Some(to_timestamp(todo_item_db.updated_at)),
+            completed_at: todo_item_db.completed_at.map(to_timestamp),
+            // Tags and dependencies live in their own tables and are filled in
+            // by the store.
+            tags: vec![],
+            blocked_by: vec![],
+            project_id: todo_item_db.project_id.unwrap_or_default(),
+            position: todo_item_db.position,
+            parent_id: todo_item_db.parent_id.unwrap_or_default(),
+            recurrence: todo_item_db.recurrence.unwrap_or_default(),
+            reminder_at: todo_item_db.reminder_at.map(to_timestamp),
+        }
+    }
+}
+
+/// A status change of a todo, recorded in `todo_transition`.
+#[derive(Debug, Clone)]
+pub struct TodoTransitionDb {
+    pub todo_id: u32,
+    pub from_status: i32,
+    pub to_status: i32,
+    pub changed_by: String,
+    pub changed_at: i64,
+}
+
+impl From<TodoTransitionDb> for TodoTransition {
+    fn from(transition: TodoTransitionDb) -> Self {
+        TodoTransition {
+            from_status: transition.from_status,
+            to_status: transition.to_status,
+            changed_by: transition.changed_by,
+            changed_at: Some(to_timestamp(transition.changed_at)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn completed_at_is_set_on_completion_and_kept_after() {
+        let completed = TodoStatus::Completed as i32;
+        assert_eq!(completed_at(completed, None, 100), Some(100));
+        assert_eq!(completed_at(completed, Some(50), 100), Some(50));
+    }
+
+    #[test]
+    fn completed_at_is_cleared_when_reopened() {
+        assert_eq!(completed_at(TodoStatus::Active as i32, Some(50), 100), None);
+        assert_eq!(completed_at(TodoStatus::Active as i32, None, 100), None);
+    }
 }
@@ -0,0 +1,414 @@
+use prost::Message;
+use proto::service::google::rpc::{bad_request::FieldViolation, BadRequest, ErrorInfo};
+use std::collections::HashMap;
+use thiserror::Error;
+use tonic::{Code, Status};
+use tracing::log::error;
+
+/// Domain reported in the `ErrorInfo` details of every error.
+const ERROR_DOMAIN: &str = "todo-rust-grpc";
+
+/// A field of a request and why its value is invalid.
+#[derive(Debug, Clone)]
+pub struct Violation {
+    pub field: String,
+    pub description: String,
+}
+
+impl Violation {
+    pub fn new(field: &str, description: impl Into<String>) -> Self {
+        Self {
+            field: field.to_string(),
+            description: description.into(),
+        }
+    }
+}
+
+/// Errors returned by the repository and the services. Converting one into a
+/// `Status` picks the gRPC code and attaches `google.rpc` error details, so
+/// database messages never reach the client.
+#[derive(Debug, Error)]
+pub enum ServiceError {
+    #[error("{0} already exists")]
+    AlreadyExists(String),
+    #[error("{0} not found")]
+    NotFound(String),
+    #[error("Invalid {field}: {description}")]
+    InvalidArgument { field: String, description: String },
+    /// Several fields are invalid, each reported as a field violation.
+    #[error("Invalid {}", .0.iter().map(|v| format!("{}: {}", v.field, v.description)).collect::<Vec<_>>().join(", "))]
+    InvalidArguments(Vec<Violation>),
+    #[error("A todo cannot go from {from} to {to}")]
+    InvalidTransition { from: String, to: String },
+    /// Linking two todos would make a todo its own ancestor or blocker.
+    #[error("Todo {id} cannot be linked to todo {other}, the {relation} would form a cycle")]
+    Cycle {
+        relation: &'static str,
+        id: u32,
+        other: u32,
+    },
+    /// The resource was changed by another request in the meantime.
+    #[error("{0} was changed concurrently, try again")]
+    Conflict(String),
+    /// The caller can see the resource but their role does not allow the call.
+    #[error("{role} role required on {resource}")]
+    PermissionDenied { resource: String, role: String },
+    #[error("Invalid username, password or PIN")]
+    InvalidCredentials,
+    #[error("Invalid or expired token")]
+    InvalidToken,
+    /// The TOTP or recovery code is wrong or used already, or the sign in
+    /// challenge it answers is unknown, expired or out of attempts.
+    #[error("Invalid two-factor code or expired sign in challenge")]
+    InvalidSecondFactor,
+    /// Sign in attempts are slowed down after failures.
+    #[error("Too many failed sign in attempts, retry in {retry_after} seconds")]
+    SignInThrottled { retry_after: i64 },
+    #[error(
+        "Account is locked after too many failed sign in attempts, retry in {retry_after} seconds"
+    )]
+    AccountLocked { retry_after: i64 },
+    #[error("Resume token has expired, fetch the todos again and watch without one")]
+    ResumeTokenExpired,
+    #[error("Watch fell behind, resume from the last received event")]
+    WatchLagged,
+    #[error("Too many concurrent requests, try again later")]
+    ResourceExhausted,
+    #[error("Database is unavailable")]
+    Unavailable,
+    /// Unexpected failures. The cause is logged but not sent to the client.
+    #[error("Internal error")]
+    Internal(String),
+}
+
+impl ServiceError {
+    pub fn invalid_argument(field: &str, description: impl Into<String>) -> Self {
+        ServiceError::InvalidArgument {
+            field: field.to_string(),
+            description: description.into(),
+        }
+    }
+
+    /// Fails with every violation found, or succeeds when there is none.
+    pub fn check(violations: Vec<Violation>) -> Result<(), ServiceError> {
+        match violations.len() {
+            0 => Ok(()),
+            1 => {
+                let violation = violations.into_iter().next().unwrap();
+                Err(ServiceError::InvalidArgument {
+                    field: violation.field,
+                    description: violation.description,
+                })
+            }
+            _ => Err(ServiceError::InvalidArguments(violations)),
+        }
+    }
+
+    fn code(&self) -> Code {
+        match self {
+            ServiceError::AlreadyExists(_) => Code::AlreadyExists,
+            ServiceError::NotFound(_) => Code::NotFound,
+            ServiceError::InvalidArgument { .. } | ServiceError::InvalidArguments(_) => {
+                Code::InvalidArgument
+            }
+            ServiceError::InvalidTransition { .. } | ServiceError::Cycle { .. } => {
+                Code::FailedPrecondition
+            }
+            ServiceError::Conflict(_) => Code::Aborted,
+            ServiceError::PermissionDenied { .. } => Code::PermissionDenied,
+            ServiceError::InvalidCredentials
+            | ServiceError::InvalidToken
+            | ServiceError::InvalidSecondFactor => Code::Unauthenticated,
+            ServiceError::SignInThrottled { .. } => Code::ResourceExhausted,
+            ServiceError::AccountLocked { .. } => Code::PermissionDenied,
+            ServiceError::ResumeTokenExpired => Code::OutOfRange,
+            ServiceError::WatchLagged => Code::Aborted,
+            ServiceError::ResourceExhausted => Code::ResourceExhausted,
+            ServiceError::Unavailable => Code::Unavailable,
+            ServiceError::Internal(_) => Code::Internal,
+        }
+    }
+
+    /// Machine readable cause reported as `ErrorInfo.reason`.
+    fn reason(&self) -> &'static str {
+        match self {
+            ServiceError::AlreadyExists(_) => "ALREADY_EXISTS",
+            ServiceError::NotFound(_) => "NOT_FOUND",
+            ServiceError::InvalidArgument { .. } | ServiceError::InvalidArguments(_) => {
+                "INVALID_ARGUMENT"
+            }
+            ServiceError::InvalidTransition { .. } => "INVALID_STATUS_TRANSITION",
+            ServiceError::Cycle { .. } => "CYCLE_DETECTED",
+            ServiceError::Conflict(_) => "CONFLICT",
+            ServiceError::PermissionDenied { .. } => "PERMISSION_DENIED",
+            ServiceError::InvalidCredentials => "INVALID_CREDENTIALS",
+            ServiceError::InvalidToken => "INVALID_TOKEN",
+            ServiceError::InvalidSecondFactor => "INVALID_SECOND_FACTOR",
+            ServiceError::SignInThrottled { .. } => "SIGN_IN_THROTTLED",
+            ServiceError::AccountLocked { .. } => "ACCOUNT_LOCKED",
+            ServiceError::ResumeTokenExpired => "RESUME_TOKEN_EXPIRED",
+            ServiceError::WatchLagged => "WATCH_LAGGED",
+            ServiceError::ResourceExhausted => "TOO_MANY_REQUESTS",
+            ServiceError::Unavailable => "DATABASE_UNAVAILABLE",
+            ServiceError::Internal(_) => "INTERNAL",
+        }
+    }
+
+    fn metadata(&self) -> HashMap<String, String> {
+        let mut metadata = HashMap::new();
+        match self {
+            ServiceError::AlreadyExists(resource)
+            | ServiceError::NotFound(resource)
+            | ServiceError::Conflict(resource) => {
+                metadata.insert(String::from("resource"), resource.clone());
+            }
+            ServiceError::PermissionDenied { resource, role } => {
+                metadata.insert(String::from("resource"), resource.clone());
+                metadata.insert(String::from("role"), role.clone());
+            }
+            ServiceError::InvalidArgument { field, .. } => {
+                metadata.insert(String::from("field"), field.clone());
+            }
+            ServiceError::InvalidArguments(violations) => {
+                let mut fields = violations
+                    .iter()
+                    .map(|violation| violation.field.as_str())
+                    .collect::<Vec<_>>();
+                fields.dedup();
+                metadata.insert(String::from("field"), fields.join(","));
+            }
+            ServiceError::SignInThrottled { retry_after }
+            | ServiceError::AccountLocked { retry_after } => {
+                metadata.insert(String::from("retry_after"), retry_after.to_string());
+            }
+            ServiceError::InvalidTransition { from, to } => {
+                metadata.insert(String::from("from"), from.clone());
+                metadata.insert(String::from("to"), to.clone());
+            }
+            ServiceError::Cycle {
+                relation,
+                id,
+                other,
+            } => {
+                metadata.insert(String::from("relation"), relation.to_string());
+                metadata.insert(String::from("id"), id.to_string());
+                metadata.insert(String::from("other"), other.to_string());
+            }
+            _ => {}
+        }
+        metadata
+    }
+}
+
+fn pack<M: Message>(type_name: &str, message: &M) -> prost_types::Any {
+    prost_types::Any {
+        type_url: format!("type.googleapis.com/{}", type_name),
+        value: message.encode_to_vec(),
+    }
+}
+
+impl From<ServiceError> for Status {
+    fn from(e: ServiceError) -> Self {
+        if let ServiceError::Internal(cause) = &e {
+            error!("Internal error {}", cause);
+        }
+        let code = e.code();
+        let message = e.to_string();
+        let mut details = vec![pack(
+            "google.rpc.ErrorInfo",
+            &ErrorInfo {
+                reason: e.reason().to_string(),
+                domain: ERROR_DOMAIN.to_string(),
+                metadata: e.metadata(),
+            },
+        )];
+        let field_violations = match e {
+            ServiceError::InvalidArgument { field, description } => {
+                vec![FieldViolation { field, description }]
+            }
+            ServiceError::InvalidArguments(violations) => violations
+                .into_iter()
+                .map(|violation| FieldViolation {
+                    field: violation.field,
+                    description: violation.description,
+                })
+                .collect(),
+            _ => vec![],
+        };
+        if !field_violations.is_empty() {
+            details.push(pack(
+                "google.rpc.BadRequest",
+                &BadRequest { field_violations },
+            ));
+        }
+        let status = proto::service::google::rpc::Status {
+            code: code as i32,
+            message: message.clone(),
+            details,
+        };
+        Status::with_details(code, message, status.encode_to_vec().into())
+    }
+}
+
+impl From<sqlx::Error> for ServiceError {
+    fn from(e: sqlx::Error) -> Self {
+        match e {
+            sqlx::Error::PoolTimedOut | sqlx::Error::PoolClosed | sqlx::Error::Io(_) => {
+                error!("Database unavailable {:?}", e);
+                ServiceError::Unavailable
+            }
+            e => ServiceError::Internal(format!("Database error {:?}", e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The `ErrorInfo` and `BadRequest` details of a status.
+    fn details(status: &Status) -> (ErrorInfo, Option<BadRequest>) {
+        let status = proto::service::google::rpc::Status::decode(status.details()).unwrap();
+        let mut error_info = None;
+        let mut bad_request = None;
+        for detail in status.details {
+            match detail.type_url.as_str() {
+                "type.googleapis.com/google.rpc.ErrorInfo" => {
+                    error_info = Some(ErrorInfo::decode(detail.value.as_slice()).unwrap())
+                }
+                "type.googleapis.com/google.rpc.BadRequest" => {
+                    bad_request = Some(BadRequest::decode(detail.value.as_slice()).unwrap())
+                }
+                type_url => panic!("Unexpected detail {}", type_url),
+            }
+        }
+        (error_info.unwrap(), bad_request)
+    }
+
+    #[test]
+    fn reports_the_resource_that_was_not_found() {
+        let status = Status::from(ServiceError::NotFound(String::from("Todo item 7")));
+        assert_eq!(status.code(), Code::NotFound);
+        assert_eq!(status.message(), "Todo item 7 not found");
+        let (error_info, bad_request) = details(&status);
+        assert_eq!(error_info.reason, "NOT_FOUND");
+        assert_eq!(error_info.domain, ERROR_DOMAIN);
+        assert_eq!(error_info.metadata["resource"], "Todo item 7");
+        assert!(bad_request.is_none());
+    }
+
+    #[test]
+    fn reports_invalid_fields_as_field_violations() {
+        let status = Status::from(ServiceError::invalid_argument("title", "Title is empty"));
+        assert_eq!(status.code(), Code::InvalidArgument);
+        let (error_info, bad_request) = details(&status);
+        assert_eq!(error_info.reason, "INVALID_ARGUMENT");
+        assert_eq!(error_info.metadata["field"], "title");
+        let violations = bad_request.unwrap().field_violations;
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].field, "title");
+        assert_eq!(violations[0].description, "Title is empty");
+    }
+
+    #[test]
+    fn reports_every_violation_of_a_failed_check() {
+        assert!(ServiceError::check(vec![]).is_ok());
+        let error = ServiceError::check(vec![
+            Violation::new("password", "Password is too short"),
+            Violation::new("password", "Password has no digit"),
+            Violation::new("username", "Username is too long"),
+        ])
+        .unwrap_err();
+        let status = Status::from(error);
+        assert_eq!(status.code(), Code::InvalidArgument);
+        let (error_info, bad_request) = details(&status);
+        assert_eq!(error_info.metadata["field"], "password,username");
+        let fields = bad_request
+            .unwrap()
+            .field_violations
+            .into_iter()
+            .map(|violation| violation.field)
+            .collect::<Vec<_>>();
+        assert_eq!(fields, vec!["password", "password", "username"]);
+    }
+
+    #[test]
+    fn maps_errors_to_codes_and_reasons() {
+        let cases = [
+            (
+                ServiceError::InvalidTransition {
+                    from: String::from("COMPLETED"),
+                    to: String::from("BLOCKED"),
+                },
+                Code::FailedPrecondition,
+                "INVALID_STATUS_TRANSITION",
+            ),
+            (
+                ServiceError::Cycle {
+                    relation: "parent",
+                    id: 1,
+                    other: 2,
+                },
+                Code::FailedPrecondition,
+                "CYCLE_DETECTED",
+            ),
+            (
+                ServiceError::PermissionDenied {
+                    resource: String::from("Project 3"),
+                    role: String::from("EDITOR"),
+                },
+                Code::PermissionDenied,
+                "PERMISSION_DENIED",
+            ),
+            (
+                ServiceError::InvalidCredentials,
+                Code::Unauthenticated,
+                "INVALID_CREDENTIALS",
+            ),
+            (
+                ServiceError::SignInThrottled { retry_after: 30 },
+                Code::ResourceExhausted,
+                "SIGN_IN_THROTTLED",
+            ),
+            (
+                ServiceError::AccountLocked { retry_after: 600 },
+                Code::PermissionDenied,
+                "ACCOUNT_LOCKED",
+            ),
+            (
+                ServiceError::ResumeTokenExpired,
+                Code::OutOfRange,
+                "RESUME_TOKEN_EXPIRED",
+            ),
+            (ServiceError::WatchLagged, Code::Aborted, "WATCH_LAGGED"),
+            (
+                ServiceError::Unavailable,
+                Code::Unavailable,
+                "DATABASE_UNAVAILABLE",
+            ),
+        ];
+        for (error, code, reason) in cases {
+            let status = Status::from(error);
+            assert_eq!(status.code(), code);
+            assert_eq!(details(&status).0.reason, reason);
+        }
+    }
+
+    #[test]
+    fn reports_when_to_retry_a_throttled_sign_in() {
+        let status = Status::from(ServiceError::SignInThrottled { retry_after: 30 });
+        assert_eq!(details(&status).0.metadata["retry_after"], "30");
+    }
+
+    #[test]
+    fn hides_the_cause_of_internal_errors() {
+        let status = Status::from(ServiceError::Internal(String::from(
+            "Database error: secret table",
+        )));
+        assert_eq!(status.code(), Code::Internal);
+        assert_eq!(status.message(), "Internal error");
+        let (error_info, _) = details(&status);
+        assert_eq!(error_info.reason, "INTERNAL");
+        assert!(error_info.metadata.is_empty());
+    }
+}
@@ -0,0 +1,252 @@
+use crate::config::env_or;
+use crate::error::ServiceError;
+use proto::service::todo::{TodoEvent, TodoEventKind, TodoItem};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+/// Number of recent events kept per user for resuming watchers, and of events
+/// a watcher can fall behind on before it lags, `TODO_EVENT_BUFFER` (1024 by
+/// default).
+pub fn event_buffer_size() -> usize {
+    env_or("TODO_EVENT_BUFFER", 1024).max(1)
+}
+
+/// Recent and live events of a user.
+#[derive(Debug, Default)]
+struct UserEvents {
+    /// The most recent events with their sequence, oldest first.
+    recent: VecDeque<(u64, TodoEvent)>,
+    /// Sequence of the last event dropped from `recent`, 0 until one is.
+    dropped: u64,
+    /// Set while the user is being watched.
+    sender: Option<broadcast::Sender<TodoEvent>>,
+}
+
+#[derive(Debug)]
+struct EventLog {
+    /// Identifies this process in resume tokens, since sequences restart with it.
+    instance: String,
+    next_sequence: u64,
+    capacity: usize,
+    /// Each user has a log and a channel of their own, so that a busy account
+    /// can neither evict the history of others nor make their watchers lag.
+    users: HashMap<String, UserEvents>,
+}
+
+impl EventLog {
+    fn resume_token(&self, sequence: u64) -> String {
+        format!("{}:{}", self.instance, sequence)
+    }
+
+    fn parse_resume_token(&self, resume_token: &str) -> Result<u64, ServiceError> {
+        let invalid = || ServiceError::invalid_argument("resume_token", "Invalid resume token");
+        let (instance, sequence) = resume_token.split_once(':').ok_or_else(invalid)?;
+        let sequence: u64 = sequence.parse().map_err(|_| invalid())?;
+        if instance != self.instance {
+            return Err(ServiceError::ResumeTokenExpired);
+        }
+        if sequence >= self.next_sequence {
+            return Err(invalid());
+        }
+        Ok(sequence)
+    }
+}
+
+/// Events of a user that happened after their resume token, followed by live ones.
+pub struct TodoSubscription {
+    pub missed: Vec<TodoEvent>,
+    pub live: broadcast::Receiver<TodoEvent>,
+}
+
+/// In-process hub fanning todo changes out to `WatchTodos` streams.
+///
+/// The most recent events of every user are kept so that a client
+/// reconnecting with the resume token of the last event it saw is sent the
+/// ones it missed.
+#[derive(Debug, Clone)]
+pub struct TodoEvents {
+    log: Arc<Mutex<EventLog>>,
+}
+
+impl TodoEvents {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            log: Arc::new(Mutex::new(EventLog {
+                instance: Uuid::new_v4().to_simple().to_string(),
+                next_sequence: 1,
+                capacity,
+                users: HashMap::new(),
+            })),
+        }
+    }
+
+    pub fn publish(&self, username: &str, kind: TodoEventKind, todo: TodoItem) {
+        let mut log = self.log.lock().unwrap();
+        let sequence = log.next_sequence;
+        log.next_sequence += 1;
+        let event = TodoEvent {
+            resume_token: log.resume_token(sequence),
+            kind: kind as i32,
+            todo: Some(todo),
+        };
+        let capacity = log.capacity;
+        let user = log.users.entry(username.to_string()).or_default();
+        if user.recent.len() == capacity {
+            if let Some((dropped, _)) = user.recent.pop_front() {
+                user.dropped = dropped;
+            }
+        }
+        user.recent.push_back((sequence, event.clone()));
+        // Sending only fails once every watcher of the user has gone.
+        if let Some(sender) = &user.sender {
+            if sender.send(event).is_err() {
+                user.sender = None;
+            }
+        }
+    }
+
+    /// Subscribes to the events of `username`. With a resume token, the events
+    /// after it are returned as `missed`; fails with `ResumeTokenExpired` when
+    /// some of them are no longer kept.
+    pub fn subscribe(
+        &self,
+        username: &str,
+        resume_token: &str,
+    ) -> Result<TodoSubscription, ServiceError> {
+        // Holding the lock while subscribing ensures no event falls between
+        // the missed ones and the live ones.
+        let mut log = self.log.lock().unwrap();
+        let after = match resume_token {
+            "" => None,
+            resume_token => Some(log.parse_resume_token(resume_token)?),
+        };
+        let capacity = log.capacity;
+        let user = log.users.entry(username.to_string()).or_default();
+        let mut missed = vec![];
+        if let Some(after) = after {
+            if user.dropped > after {
+                return Err(ServiceError::ResumeTokenExpired);
+            }
+            missed = user
+                .recent
+                .iter()
+                .filter(|(sequence, _)| *sequence > after)
+                .map(|(_, event)| event.clone())
+                .collect();
+        }
+        let live = user
+            .sender
+            .get_or_insert_with(|| broadcast::channel(capacity).0)
+            .subscribe();
+        Ok(TodoSubscription { missed, live })
+    }
+
+    /// Forgets the events of a user that was deleted or renamed. Their streams
+    /// end once they have received the events sent so far.
+    pub fn close(&self, username: &str) {
+        self.log.lock().unwrap().users.remove(username);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::sync::broadcast::error::TryRecvError;
+
+    fn todo(id: u32) -> TodoItem {
+        TodoItem {
+            id,
+            ..Default::default()
+        }
+    }
+
+    fn ids(events: &[TodoEvent]) -> Vec<u32> {
+        events
+            .iter()
+            .map(|event| event.todo.as_ref().unwrap().id)
+            .collect()
+    }
+
+    fn resume_token(events: &TodoEvents, sequence: u64) -> String {
+        events.log.lock().unwrap().resume_token(sequence)
+    }
+
+    #[test]
+    fn replays_the_events_of_the_user_after_the_resume_token() {
+        let events = TodoEvents::new(4);
+        events.publish("alice", TodoEventKind::Created, todo(1));
+        events.publish("alice", TodoEventKind::Created, todo(2));
+        events.publish("bob", TodoEventKind::Created, todo(3));
+        events.publish("alice", TodoEventKind::Updated, todo(2));
+
+        assert!(events.subscribe("alice", "").unwrap().missed.is_empty());
+        let subscription = events
+            .subscribe("alice", &resume_token(&events, 1))
+            .unwrap();
+        assert_eq!(ids(&subscription.missed), vec![2, 2]);
+    }
+
+    #[test]
+    fn keeps_the_history_of_a_user_when_another_one_is_busy() {
+        let events = TodoEvents::new(2);
+        events.publish("alice", TodoEventKind::Created, todo(1));
+        events.publish("alice", TodoEventKind::Created, todo(2));
+        for id in 3..100 {
+            events.publish("bob", TodoEventKind::Created, todo(id));
+        }
+        let subscription = events
+            .subscribe("alice", &resume_token(&events, 1))
+            .unwrap();
+        assert_eq!(ids(&subscription.missed), vec![2]);
+    }
+
+    #[test]
+    fn expires_resume_tokens_once_the_events_after_them_are_dropped() {
+        let events = TodoEvents::new(2);
+        for id in 1..=3 {
+            events.publish("alice", TodoEventKind::Created, todo(id));
+        }
+        assert!(matches!(
+            events.subscribe("alice", &resume_token(&events, 0)),
+            Err(ServiceError::ResumeTokenExpired)
+        ));
+        let subscription = events
+            .subscribe("alice", &resume_token(&events, 1))
+            .unwrap();
+        assert_eq!(ids(&subscription.missed), vec![2, 3]);
+        assert!(matches!(
+            events.subscribe("alice", "other:1"),
+            Err(ServiceError::ResumeTokenExpired)
+        ));
+        assert!(matches!(
+            events.subscribe("alice", &resume_token(&events, 9)),
+            Err(ServiceError::InvalidArgument { .. })
+        ));
+    }
+
+    #[test]
+    fn sends_live_events_to_the_watchers_of_the_user_only() {
+        let events = TodoEvents::new(4);
+        let mut alice = events.subscribe("alice", "").unwrap().live;
+        let mut bob = events.subscribe("bob", "").unwrap().live;
+        events.publish("alice", TodoEventKind::Created, todo(1));
+        assert_eq!(alice.try_recv().unwrap().todo.unwrap().id, 1);
+        assert!(bob.try_recv().is_err());
+    }
+
+    #[test]
+    fn ends_the_streams_and_forgets_the_events_of_a_closed_user() {
+        let events = TodoEvents::new(4);
+        let mut live = events.subscribe("alice", "").unwrap().live;
+        events.publish("alice", TodoEventKind::Deleted, todo(1));
+        events.close("alice");
+        assert_eq!(live.try_recv().unwrap().todo.unwrap().id, 1);
+        assert!(matches!(live.try_recv(), Err(TryRecvError::Closed)));
+        let subscription = events
+            .subscribe("alice", &resume_token(&events, 0))
+            .unwrap();
+        assert!(subscription.missed.is_empty());
+    }
+}
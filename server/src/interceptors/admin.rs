@@ -0,0 +1,48 @@
+use sha2::{Digest, Sha256};
+use std::env;
+use tonic::{service::Interceptor, Request, Status};
+use tracing::log::error;
+
+/// Lets through requests whose `authorization` metadata is the `ADMIN_TOKEN`
+/// of the server. Every request is rejected when no token is configured.
+#[derive(Clone, Debug)]
+pub struct AdminInterceptor {
+    token_digest: Option<Vec<u8>>,
+}
+
+impl AdminInterceptor {
+    pub fn from_env() -> Self {
+        let token = env::var("ADMIN_TOKEN")
+            .ok()
+            .filter(|token| !token.is_empty());
+        Self {
+            token_digest: token.map(|token| Sha256::digest(token.as_bytes()).to_vec()),
+        }
+    }
+}
+
+impl Interceptor for AdminInterceptor {
+    fn call(&mut self, request: Request<()>) -> Result<Request<()>, Status> {
+        let expected = match &self.token_digest {
+            Some(expected) => expected,
+            None => return Err(Status::permission_denied("Admin API is disabled")),
+        };
+        let token = match request.metadata().get("authorization") {
+            Some(token) => token.as_bytes(),
+            None => return Err(Status::unauthenticated("No admin token")),
+        };
+        // Digests have the same length, and comparing every byte takes the
+        // same time wherever they differ.
+        let digest = Sha256::digest(token);
+        let difference = digest
+            .iter()
+            .zip(expected.iter())
+            .fold(0, |difference, (a, b)| difference | (a ^ b));
+        if difference == 0 {
+            Ok(request)
+        } else {
+            error!("Rejected request with an invalid admin token");
+            Err(Status::unauthenticated("Invalid admin token"))
+        }
+    }
+}
@@ -1,45 +1,65 @@
-use hmac::{Hmac, NewMac};
-use jwt::VerifyWithKey;
-use sha2::Sha256;
-use std::collections::BTreeMap;
-use std::env;
+use crate::tokens::{verify_access_token, AccessClaims, RevocationCache};
+use tonic::metadata::MetadataMap;
 use tonic::{service::Interceptor, Request, Status};
 use tracing::log::{error, info};
 
 pub struct AuthExtension {
     pub username: String,
 }
-#[derive(Clone, Default, Debug)]
-pub struct AuthInterceptor {}
 
-impl Interceptor for AuthInterceptor {
-    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
-        match request.metadata().get("authorization") {
-            Some(t) => match (*t).to_str() {
-                Ok(full_token) => {
-                    let secret = env::var("SECRET").expect("SECRET env missing");
-                    let key: Hmac<Sha256> = Hmac::new_from_slice(secret.as_bytes()).unwrap();
-                    let claims_result: Result<BTreeMap<String, String>, jwt::error::Error> =
-                        full_token.verify_with_key(&key);
-                    match claims_result {
-                        Ok(claims) => {
-                            info!("Claims token {:?}", claims);
-                            let username: String = claims["sub"].to_owned();
-                            request.extensions_mut().insert(AuthExtension { username });
-                            return Ok(request);
-                        }
-                        Err(e) => {
-                            error!("Error while parsing token {:?}", e);
-                            return Err(Status::unauthenticated("No valid auth token"));
-                        }
-                    }
+impl From<AccessClaims> for AuthExtension {
+    fn from(claims: AccessClaims) -> Self {
+        AuthExtension {
+            username: claims.username,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct AuthInterceptor {
+    revocations: RevocationCache,
+}
+
+impl AuthInterceptor {
+    pub fn new(revocations: RevocationCache) -> Self {
+        Self { revocations }
+    }
+}
+
+/// Verifies the `authorization` metadata and checks the token against the revocation cache.
+pub fn authenticate(
+    metadata: &MetadataMap,
+    revocations: &RevocationCache,
+) -> Result<AccessClaims, Status> {
+    match metadata.get("authorization") {
+        Some(t) => match (*t).to_str() {
+            Ok(full_token) => match verify_access_token(full_token) {
+                Ok(claims) if revocations.is_revoked(&claims.jti) => {
+                    info!("Rejected revoked token {}", claims.jti);
+                    Err(Status::unauthenticated("Token has been revoked"))
+                }
+                Ok(claims) => {
+                    info!("Claims token {:?}", claims);
+                    Ok(claims)
                 }
                 Err(e) => {
-                    error!("Error while parsing token {:?}", e);
-                    return Err(Status::unauthenticated("No valid auth token"));
+                    error!("Error while verifying token {:?}", e);
+                    Err(Status::unauthenticated("No valid auth token"))
                 }
             },
-            _ => return Err(Status::unauthenticated("No valid auth token")),
-        }
+            Err(e) => {
+                error!("Error while parsing token {:?}", e);
+                Err(Status::unauthenticated("No valid auth token"))
+            }
+        },
+        _ => Err(Status::unauthenticated("No valid auth token")),
+    }
+}
+
+impl Interceptor for AuthInterceptor {
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        let claims = authenticate(request.metadata(), &self.revocations)?;
+        request.extensions_mut().insert(AuthExtension::from(claims));
+        Ok(request)
     }
 }
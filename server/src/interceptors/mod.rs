@@ -1,3 +1,5 @@
+pub mod admin;
 pub mod auth;
 
-pub use crate::interceptors::auth::{AuthExtension, AuthInterceptor};
+pub use crate::interceptors::admin::AdminInterceptor;
+pub use crate::interceptors::auth::{authenticate, AuthExtension, AuthInterceptor};
@@ -1,11 +1,27 @@
+mod clock;
+mod config;
 mod db;
+mod error;
+mod events;
 mod interceptors;
+mod reminders;
 mod service_impl;
+mod throttle;
+mod tokens;
 
-use crate::db::{get_connection_pool, Manager, Message};
-use crate::interceptors::AuthInterceptor;
-use crate::service_impl::{AuthService, TodoService};
+use crate::db::{
+    acquire_timeout, get_store, max_concurrency, migrations_enabled, recurrence_interval,
+    Repository,
+};
+use crate::events::{event_buffer_size, TodoEvents};
+use crate::interceptors::{AdminInterceptor, AuthInterceptor};
+use crate::reminders::{reminder_interval, ReminderScheduler, Reminders, Webhook};
+use crate::service_impl::{AccountService, AdminService, AuthService, TodoService};
+use crate::throttle::SignInThrottle;
+use crate::tokens::RevocationCache;
 use dotenv::dotenv;
+use proto::service::auth::account_server::AccountServer;
+use proto::service::auth::admin_server::AdminServer;
 use proto::service::auth::auth_server::AuthServer;
 use proto::service::todo::todo_server::TodoServer;
 use std::env;
@@ -19,31 +35,64 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     tracing_subscriber::fmt::init();
     dotenv().ok();
 
-    // Database Manager setup
-    let pool = get_connection_pool().await?;
-    let (db_tx, db_rx) = tokio::sync::mpsc::channel::<Message>(32);
-    tokio::spawn(async move {
-        let mut manager = Manager::new(pool, db_rx);
-        manager.listen().await;
-    });
+    // Database setup
+    let store = get_store().await?;
+    if migrations_enabled() {
+        store.migrate().await?;
+        info!("Database migrations applied");
+    } else {
+        info!("Skipping database migrations");
+    }
+    let events = TodoEvents::new(event_buffer_size());
+    let repository = Repository::new(store, events.clone(), max_concurrency(), acquire_timeout())?;
+    tokio::spawn(repository.clone().run_recurrences(recurrence_interval()));
+    let reminders = Reminders::new(event_buffer_size());
+    let scheduler = ReminderScheduler {
+        repository: repository.clone(),
+        reminders: reminders.clone(),
+        webhook: Webhook::from_env()?,
+    };
+    tokio::spawn(scheduler.run(reminder_interval()));
+    let revoked_tokens = repository.revoked_tokens().await?;
+    let revocations = RevocationCache::new(
+        revoked_tokens
+            .into_iter()
+            .map(|token| (token.jti, token.expires_at))
+            .collect(),
+    );
 
     // Middleware manager
-    let auth_interceptor = AuthInterceptor::default();
+    let auth_interceptor = AuthInterceptor::new(revocations.clone());
 
     let port = env::var("PORT").unwrap_or(String::from("50050"));
     // Address
     let adder = format!("0.0.0.0:{}", port).parse()?;
     info!("Server running on {:?}", adder);
     // Initiate service defaults
-    let auth_service = AuthService::new(db_tx.clone());
-    let todo_service = TodoService::new(db_tx.clone());
+    let throttle = SignInThrottle::from_env();
+    let auth_service = AuthService::new(repository.clone(), revocations.clone(), throttle.clone());
+    let account_service = AccountService::new(
+        repository.clone(),
+        revocations,
+        throttle.clone(),
+        events.clone(),
+        reminders.clone(),
+    );
+    let admin_service = AdminService::new(throttle);
+    let todo_service = TodoService::new(repository, events, reminders);
 
     let auth_service = AuthServer::new(auth_service);
+    let account_service_with_interceptor =
+        AccountServer::with_interceptor(account_service, auth_interceptor.clone());
+    let admin_service_with_interceptor =
+        AdminServer::with_interceptor(admin_service, AdminInterceptor::from_env());
     let todo_service_with_interceptor =
         TodoServer::with_interceptor(todo_service, auth_interceptor);
 
     Server::builder()
         .add_service(auth_service)
+        .add_service(account_service_with_interceptor)
+        .add_service(admin_service_with_interceptor)
         .add_service(todo_service_with_interceptor)
         .serve(adder)
         .await?;
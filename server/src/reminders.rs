@@ -0,0 +1,293 @@
+use crate::config::env_or;
+use crate::db::Repository;
+use crate::tokens::to_hex;
+use hmac::{Hmac, Mac, NewMac};
+use hyper::client::HttpConnector;
+use hyper::{Body, Client, Uri};
+use hyper_rustls::HttpsConnector;
+use proto::service::todo::Reminder;
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::env;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tokio::time::{interval, sleep};
+use tracing::info;
+use tracing::log::error;
+
+/// Header carrying the hex encoded HMAC-SHA256 of the webhook payload, signed
+/// with `REMINDER_WEBHOOK_SECRET`.
+const SIGNATURE_HEADER: &str = "x-reminder-signature";
+
+/// Attempts made to deliver a reminder to the webhook before giving up.
+const WEBHOOK_ATTEMPTS: u32 = 3;
+
+/// How often due reminders are looked for, `REMINDER_INTERVAL_SECS`
+/// (10 seconds by default).
+pub fn reminder_interval() -> Duration {
+    Duration::from_secs(env_or("REMINDER_INTERVAL_SECS", 10).max(1))
+}
+
+/// A reminder that came due, along with the users to remind.
+#[derive(Debug)]
+pub struct DueReminder {
+    /// Owner of the todo.
+    pub owner: String,
+    pub usernames: Vec<String>,
+    pub reminder: Reminder,
+}
+
+/// In-process hub fanning due reminders out to `StreamReminders` streams.
+///
+/// Each user has a channel of their own, so that the reminders of others can
+/// neither reach nor make their streams lag.
+#[derive(Debug, Clone)]
+pub struct Reminders {
+    senders: Arc<Mutex<HashMap<String, broadcast::Sender<Reminder>>>>,
+    capacity: usize,
+}
+
+impl Reminders {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            senders: Arc::new(Mutex::new(HashMap::new())),
+            capacity,
+        }
+    }
+
+    /// Sends a reminder to the streams of its users. Returns whether any of
+    /// them was open.
+    pub fn publish(&self, due: &DueReminder) -> bool {
+        let mut senders = self.senders.lock().unwrap();
+        let mut sent = false;
+        for username in &due.usernames {
+            // Sending only fails once every stream of the user has gone.
+            if let Some(sender) = senders.get(username) {
+                match sender.send(due.reminder.clone()) {
+                    Ok(_) => sent = true,
+                    Err(_) => {
+                        senders.remove(username);
+                    }
+                }
+            }
+        }
+        sent
+    }
+
+    pub fn subscribe(&self, username: &str) -> broadcast::Receiver<Reminder> {
+        let mut senders = self.senders.lock().unwrap();
+        senders
+            .entry(username.to_string())
+            .or_insert_with(|| broadcast::channel(self.capacity).0)
+            .subscribe()
+    }
+
+    /// Ends the streams of a user that was deleted or renamed.
+    pub fn close(&self, username: &str) {
+        self.senders.lock().unwrap().remove(username);
+    }
+}
+
+/// Outbound webhook due reminders are posted to as JSON, configured with
+/// `REMINDER_WEBHOOK_URL` and optionally signed with `REMINDER_WEBHOOK_SECRET`.
+#[derive(Debug, Clone)]
+pub struct Webhook {
+    url: Uri,
+    secret: Option<String>,
+    client: Client<HttpsConnector<HttpConnector>>,
+}
+
+impl Webhook {
+    /// The webhook of the environment, `None` when no URL is configured.
+    pub fn from_env() -> Result<Option<Self>, String> {
+        let url = match env::var("REMINDER_WEBHOOK_URL") {
+            Ok(url) if !url.trim().is_empty() => url,
+            _ => return Ok(None),
+        };
+        let url = url
+            .trim()
+            .parse()
+            .map_err(|e| format!("Invalid REMINDER_WEBHOOK_URL: {}", e))?;
+        Ok(Some(Self {
+            url,
+            secret: env::var("REMINDER_WEBHOOK_SECRET")
+                .ok()
+                .filter(|secret| !secret.is_empty()),
+            client: Client::builder().build(HttpsConnector::with_native_roots()),
+        }))
+    }
+
+    fn payload(due: &DueReminder) -> String {
+        let todo = due.reminder.todo.clone().unwrap_or_default();
+        let seconds = |timestamp: &Option<prost_types::Timestamp>| {
+            timestamp.as_ref().map(|timestamp| timestamp.seconds)
+        };
+        serde_json::json!({
+            "usernames": due.usernames,
+            "reminder_at": seconds(&due.reminder.reminder_at),
+            "todo": {
+                "id": todo.id,
+                "title": todo.title,
+                "description": todo.description,
+                "status": todo.status,
+                "priority": todo.priority,
+                "due_at": seconds(&todo.due_at),
+                "project_id": todo.project_id,
+            },
+        })
+        .to_string()
+    }
+
+    fn signature(&self, payload: &str) -> Option<String> {
+        let secret = self.secret.as_ref()?;
+        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).ok()?;
+        mac.update(payload.as_bytes());
+        Some(to_hex(&mac.finalize().into_bytes()))
+    }
+
+    async fn post(&self, payload: &str) -> Result<(), String> {
+        let mut request = hyper::Request::post(self.url.clone())
+            .header(hyper::header::CONTENT_TYPE, "application/json");
+        if let Some(signature) = self.signature(payload) {
+            request = request.header(SIGNATURE_HEADER, signature);
+        }
+        let request = request
+            .body(Body::from(payload.to_string()))
+            .map_err(|e| e.to_string())?;
+        let response = self
+            .client
+            .request(request)
+            .await
+            .map_err(|e| e.to_string())?;
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(format!("Webhook responded {}", response.status()))
+        }
+    }
+
+    /// Posts a reminder, retrying with a growing delay. Returns whether the
+    /// webhook accepted it.
+    pub async fn deliver(&self, due: &DueReminder) -> bool {
+        let payload = Self::payload(due);
+        for attempt in 1..=WEBHOOK_ATTEMPTS {
+            match self.post(&payload).await {
+                Ok(()) => return true,
+                Err(e) if attempt < WEBHOOK_ATTEMPTS => {
+                    error!("Reminder webhook attempt {} failed {}", attempt, e);
+                    sleep(Duration::from_secs(1 << attempt)).await;
+                }
+                Err(e) => error!("Giving up on reminder webhook {}", e),
+            }
+        }
+        false
+    }
+}
+
+/// Background task sending the reminders that come due.
+///
+/// Reminders are marked delivered once a stream of one of their users or the
+/// webhook has received them. Until then they are sent again on every run,
+/// so those that came due while nobody was listening, or while the server was
+/// down, are not lost.
+pub struct ReminderScheduler {
+    pub repository: Repository,
+    pub reminders: Reminders,
+    pub webhook: Option<Webhook>,
+}
+
+impl ReminderScheduler {
+    /// Sends due reminders every `period`, for as long as the server runs.
+    pub async fn run(self, period: Duration) {
+        let mut ticks = interval(period);
+        loop {
+            ticks.tick().await;
+            let due = match self.repository.acquire().await {
+                Ok(conn) => conn.due_reminders().await,
+                Err(e) => Err(e),
+            };
+            let due = match due {
+                Ok(due) => due,
+                Err(e) => {
+                    error!("Unable to load due reminders {:?}", e);
+                    continue;
+                }
+            };
+            if !due.is_empty() {
+                info!("Sending {} reminders", due.len());
+            }
+            for due in due {
+                let streamed = self.reminders.publish(&due);
+                let posted = match &self.webhook {
+                    Some(webhook) => webhook.deliver(&due).await,
+                    None => false,
+                };
+                if !streamed && !posted {
+                    continue;
+                }
+                let marked = match self.repository.acquire().await {
+                    Ok(conn) => conn.mark_reminder_delivered(&due).await,
+                    Err(e) => Err(e),
+                };
+                if let Err(e) = marked {
+                    error!("Unable to mark reminder delivered {:?}", e);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proto::service::todo::TodoItem;
+    use tokio::sync::broadcast::error::TryRecvError;
+
+    fn due(id: u32, usernames: &[&str]) -> DueReminder {
+        DueReminder {
+            owner: usernames[0].to_string(),
+            usernames: usernames
+                .iter()
+                .map(|username| username.to_string())
+                .collect(),
+            reminder: Reminder {
+                todo: Some(TodoItem {
+                    id,
+                    ..Default::default()
+                }),
+                reminder_at: None,
+            },
+        }
+    }
+
+    #[test]
+    fn sends_reminders_to_the_streams_of_their_users_only() {
+        let reminders = Reminders::new(4);
+        let mut alice = reminders.subscribe("alice");
+        let mut bob = reminders.subscribe("bob");
+        assert!(reminders.publish(&due(1, &["alice"])));
+        assert!(reminders.publish(&due(2, &["alice", "bob"])));
+        assert_eq!(alice.try_recv().unwrap().todo.unwrap().id, 1);
+        assert_eq!(alice.try_recv().unwrap().todo.unwrap().id, 2);
+        assert_eq!(bob.try_recv().unwrap().todo.unwrap().id, 2);
+        assert!(bob.try_recv().is_err());
+    }
+
+    #[test]
+    fn reports_reminders_nobody_is_streaming() {
+        let reminders = Reminders::new(4);
+        assert!(!reminders.publish(&due(1, &["alice"])));
+        drop(reminders.subscribe("alice"));
+        assert!(!reminders.publish(&due(1, &["alice"])));
+    }
+
+    #[test]
+    fn ends_the_streams_of_a_closed_user() {
+        let reminders = Reminders::new(4);
+        let mut live = reminders.subscribe("alice");
+        reminders.close("alice");
+        assert!(!reminders.publish(&due(1, &["alice"])));
+        assert!(matches!(live.try_recv(), Err(TryRecvError::Closed)));
+    }
+}
@@ -0,0 +1,273 @@
+use crate::db::models::RevokedTokenDb;
+use crate::db::Repository;
+use crate::error::ServiceError;
+use crate::events::TodoEvents;
+use crate::reminders::Reminders;
+use crate::service_impl::credentials::{pin_violations, username_violations, PasswordPolicy};
+use crate::service_impl::todo::username;
+use crate::throttle::SignInThrottle;
+use crate::tokens::RevocationCache;
+use proto::service::auth::account_server::Account;
+use proto::service::auth::{
+    ChangePasswordRequest, ChangePasswordResponse, ChangePinRequest, ChangePinResponse,
+    ChangeUsernameRequest, ChangeUsernameResponse, ConfirmTotpRequest, ConfirmTotpResponse,
+    DeleteAccountRequest, DeleteAccountResponse, DisableTotpRequest, DisableTotpResponse,
+    EnrollTotpRequest, EnrollTotpResponse,
+};
+use tonic::{Request, Response, Status};
+use tracing::{error, info};
+
+#[derive(Debug, Clone)]
+pub struct AccountService {
+    repository: Repository,
+    revocations: RevocationCache,
+    password_policy: PasswordPolicy,
+    throttle: SignInThrottle,
+    events: TodoEvents,
+    reminders: Reminders,
+}
+
+impl AccountService {
+    pub fn new(
+        repository: Repository,
+        revocations: RevocationCache,
+        throttle: SignInThrottle,
+        events: TodoEvents,
+        reminders: Reminders,
+    ) -> Self {
+        Self {
+            repository,
+            revocations,
+            password_policy: PasswordPolicy::from_env(),
+            throttle,
+            events,
+            reminders,
+        }
+    }
+
+    fn revoke(&self, revoked: Vec<RevokedTokenDb>) {
+        for token in revoked {
+            self.revocations.revoke(token.jti, token.expires_at);
+        }
+    }
+
+    /// Ends the streams of a user that no longer goes by `username`, since
+    /// they outlive the access tokens they were opened with.
+    fn close_streams(&self, username: &str) {
+        self.events.close(username);
+        self.reminders.close(username);
+    }
+}
+
+#[tonic::async_trait]
+impl Account for AccountService {
+    async fn change_pin(
+        &self,
+        request: Request<ChangePinRequest>,
+    ) -> Result<Response<ChangePinResponse>, Status> {
+        let username = username(&request)?;
+        let address = request.remote_addr().map(|address| address.ip());
+        let req = request.into_inner();
+        ServiceError::check(pin_violations("new_pin", req.new_pin, true))?;
+        let result = self
+            .throttle
+            .check_credentials(&username, address, async {
+                let conn = self.repository.acquire().await?;
+                conn.change_pin(username.clone(), req.password, req.current_pin, req.new_pin)
+                    .await
+            })
+            .await;
+        match result {
+            Ok((token_pair, revoked)) => {
+                self.revoke(revoked);
+                info!("Changed PIN of user: {}", username);
+                Ok(Response::new(ChangePinResponse {
+                    token: token_pair.access_token,
+                    refresh_token: token_pair.refresh_token,
+                    expires_in: token_pair.expires_in,
+                }))
+            }
+            Err(e) => {
+                error!("Error while changing PIN {:?}", e);
+                Err(e.into())
+            }
+        }
+    }
+
+    async fn change_password(
+        &self,
+        request: Request<ChangePasswordRequest>,
+    ) -> Result<Response<ChangePasswordResponse>, Status> {
+        let username = username(&request)?;
+        let address = request.remote_addr().map(|address| address.ip());
+        let req = request.into_inner();
+        ServiceError::check(self.password_policy.violations(
+            "new_password",
+            &req.new_password,
+            &username,
+        ))?;
+        let result = self
+            .throttle
+            .check_credentials(&username, address, async {
+                let conn = self.repository.acquire().await?;
+                conn.change_password(
+                    username.clone(),
+                    req.current_password,
+                    req.pin,
+                    req.new_password,
+                )
+                .await
+            })
+            .await;
+        match result {
+            Ok((token_pair, revoked)) => {
+                self.revoke(revoked);
+                info!("Changed password of user: {}", username);
+                Ok(Response::new(ChangePasswordResponse {
+                    token: token_pair.access_token,
+                    refresh_token: token_pair.refresh_token,
+                    expires_in: token_pair.expires_in,
+                }))
+            }
+            Err(e) => {
+                error!("Error while changing password {:?}", e);
+                Err(e.into())
+            }
+        }
+    }
+
+    async fn change_username(
+        &self,
+        request: Request<ChangeUsernameRequest>,
+    ) -> Result<Response<ChangeUsernameResponse>, Status> {
+        let username = username(&request)?;
+        let new_username = request.into_inner().username;
+        ServiceError::check(username_violations("username", &new_username))?;
+        if new_username == username {
+            return Err(ServiceError::invalid_argument(
+                "username",
+                "Username is the same as the current one",
+            )
+            .into());
+        }
+        let conn = self.repository.acquire().await?;
+        match conn
+            .change_username(username.clone(), new_username.clone())
+            .await
+        {
+            Ok((token_pair, revoked)) => {
+                self.revoke(revoked);
+                self.close_streams(&username);
+                info!("Renamed user {} to {}", username, new_username);
+                Ok(Response::new(ChangeUsernameResponse {
+                    token: token_pair.access_token,
+                    refresh_token: token_pair.refresh_token,
+                    expires_in: token_pair.expires_in,
+                }))
+            }
+            Err(e) => {
+                error!("Error while changing username {:?}", e);
+                Err(e.into())
+            }
+        }
+    }
+
+    async fn delete_account(
+        &self,
+        request: Request<DeleteAccountRequest>,
+    ) -> Result<Response<DeleteAccountResponse>, Status> {
+        let username = username(&request)?;
+        let address = request.remote_addr().map(|address| address.ip());
+        let req = request.into_inner();
+        let result = self
+            .throttle
+            .check_credentials(&username, address, async {
+                let conn = self.repository.acquire().await?;
+                conn.delete_account(username.clone(), req.password, req.pin)
+                    .await
+            })
+            .await;
+        match result {
+            Ok(revoked) => {
+                self.revoke(revoked);
+                self.close_streams(&username);
+                info!("Deleted account of user: {}", username);
+                Ok(Response::new(DeleteAccountResponse {}))
+            }
+            Err(e) => {
+                error!("Error while deleting account {:?}", e);
+                Err(e.into())
+            }
+        }
+    }
+
+    async fn enroll_totp(
+        &self,
+        request: Request<EnrollTotpRequest>,
+    ) -> Result<Response<EnrollTotpResponse>, Status> {
+        let username = username(&request)?;
+        let address = request.remote_addr().map(|address| address.ip());
+        let req = request.into_inner();
+        let result = self
+            .throttle
+            .check_credentials(&username, address, async {
+                let conn = self.repository.acquire().await?;
+                conn.enroll_totp(username.clone(), req.password, req.pin)
+                    .await
+            })
+            .await;
+        match result {
+            Ok((secret, otpauth_uri)) => {
+                info!("Started TOTP enrollment of user: {}", username);
+                Ok(Response::new(EnrollTotpResponse {
+                    secret,
+                    otpauth_uri,
+                }))
+            }
+            Err(e) => {
+                error!("Error while enrolling TOTP {:?}", e);
+                Err(e.into())
+            }
+        }
+    }
+
+    async fn confirm_totp(
+        &self,
+        request: Request<ConfirmTotpRequest>,
+    ) -> Result<Response<ConfirmTotpResponse>, Status> {
+        let username = username(&request)?;
+        let code = request.into_inner().code;
+        let conn = self.repository.acquire().await?;
+        match conn.confirm_totp(username, code).await {
+            Ok(recovery_codes) => Ok(Response::new(ConfirmTotpResponse { recovery_codes })),
+            Err(e) => {
+                error!("Error while confirming TOTP {:?}", e);
+                Err(e.into())
+            }
+        }
+    }
+
+    async fn disable_totp(
+        &self,
+        request: Request<DisableTotpRequest>,
+    ) -> Result<Response<DisableTotpResponse>, Status> {
+        let username = username(&request)?;
+        let address = request.remote_addr().map(|address| address.ip());
+        let req = request.into_inner();
+        let result = self
+            .throttle
+            .check_credentials(&username, address, async {
+                let conn = self.repository.acquire().await?;
+                conn.disable_totp(username.clone(), req.password, req.pin, req.code)
+                    .await
+            })
+            .await;
+        match result {
+            Ok(()) => Ok(Response::new(DisableTotpResponse {})),
+            Err(e) => {
+                error!("Error while disabling TOTP {:?}", e);
+                Err(e.into())
+            }
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use crate::error::ServiceError;
+use crate::throttle::{Lockout as LockoutState, SignInThrottle};
+use proto::service::auth::admin_server::Admin;
+use proto::service::auth::{
+    GetLockoutsRequest, GetLockoutsResponse, Lockout, UnlockRequest, UnlockResponse,
+};
+use std::net::IpAddr;
+use tonic::{Request, Response, Status};
+use tracing::info;
+
+#[derive(Debug, Clone)]
+pub struct AdminService {
+    throttle: SignInThrottle,
+}
+
+impl AdminService {
+    pub fn new(throttle: SignInThrottle) -> Self {
+        Self { throttle }
+    }
+}
+
+impl From<LockoutState> for Lockout {
+    fn from(lockout: LockoutState) -> Self {
+        Lockout {
+            username: lockout.username.unwrap_or_default(),
+            address: lockout
+                .address
+                .map(|address| address.to_string())
+                .unwrap_or_default(),
+            failures: lockout.failures,
+            locked: lockout.locked,
+            blocked_until: Some(prost_types::Timestamp {
+                seconds: lockout.blocked_until,
+                nanos: 0,
+            }),
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl Admin for AdminService {
+    async fn get_lockouts(
+        &self,
+        _request: Request<GetLockoutsRequest>,
+    ) -> Result<Response<GetLockoutsResponse>, Status> {
+        let lockouts = self
+            .throttle
+            .lockouts()
+            .into_iter()
+            .map(Lockout::from)
+            .collect();
+        Ok(Response::new(GetLockoutsResponse { lockouts }))
+    }
+
+    async fn unlock(
+        &self,
+        request: Request<UnlockRequest>,
+    ) -> Result<Response<UnlockResponse>, Status> {
+        let req = request.into_inner();
+        let unlocked = match (req.username.is_empty(), req.address.is_empty()) {
+            (false, true) => self.throttle.unlock_username(&req.username),
+            (true, false) => {
+                let address = req
+                    .address
+                    .parse::<IpAddr>()
+                    .map_err(|_| ServiceError::invalid_argument("address", "Invalid IP address"))?;
+                self.throttle.unlock_address(address)
+            }
+            _ => {
+                return Err(ServiceError::invalid_argument(
+                    "username",
+                    "Exactly one of username and address should be set",
+                )
+                .into())
+            }
+        };
+        if unlocked {
+            info!("Unlocked sign in of {}{}", req.username, req.address);
+        }
+        Ok(Response::new(UnlockResponse { unlocked }))
+    }
+}
@@ -1,20 +1,38 @@
-use crate::db::models::User;
-use crate::db::Message;
+use crate::db::Repository;
+use crate::error::ServiceError;
+use crate::interceptors::authenticate;
+use crate::service_impl::credentials::{pin_violations, username_violations, PasswordPolicy};
+use crate::throttle::SignInThrottle;
+use crate::tokens::{RevocationCache, SignInOutcome};
 use proto::service::auth::auth_server::Auth;
-use proto::service::auth::{SignInRequest, SignInResponse, SignUpRequest, SignUpResponse};
-use tokio::sync::mpsc::Sender;
-use tokio::sync::oneshot::channel;
+use proto::service::auth::{
+    RefreshTokenRequest, RefreshTokenResponse, SignInRequest, SignInResponse,
+    SignOutEverywhereRequest, SignOutEverywhereResponse, SignOutRequest, SignOutResponse,
+    SignUpRequest, SignUpResponse, VerifySecondFactorRequest, VerifySecondFactorResponse,
+};
 use tonic::{Request, Response, Status};
 use tracing::{error, info};
 
 #[derive(Debug, Clone)]
 pub struct AuthService {
-    db_message_sender: Sender<Message>,
+    repository: Repository,
+    revocations: RevocationCache,
+    password_policy: PasswordPolicy,
+    throttle: SignInThrottle,
 }
 
 impl AuthService {
-    pub fn new(db_message_sender: Sender<Message>) -> Self {
-        Self { db_message_sender }
+    pub fn new(
+        repository: Repository,
+        revocations: RevocationCache,
+        throttle: SignInThrottle,
+    ) -> Self {
+        Self {
+            repository,
+            revocations,
+            password_policy: PasswordPolicy::from_env(),
+            throttle,
+        }
     }
 }
 
@@ -24,44 +42,31 @@ impl Auth for AuthService {
         &self,
         request: Request<SignUpRequest>,
     ) -> Result<Response<SignUpResponse>, Status> {
-        if request.get_ref().pin > 9999 || request.get_ref().pin < 1000 {
-            let error_message = format!(
-                "Sign Up for Username: {} - PIN should consist only 4 digits",
-                request.get_ref().username.to_string()
-            );
-            error!("{}", error_message);
-            return Err(Status::invalid_argument(error_message));
-        }
-        let (tx, rx) = channel::<Result<User, String>>();
-        match self
-            .db_message_sender
-            .send(Message::SignUp {
-                req: request.get_ref().clone(),
-                resp: tx,
-            })
-            .await
-        {
-            Ok(_) => {}
-            Err(e) => error!("Failed to send sign up message to DB manager {:?}", e),
+        let req = request.get_ref();
+        let mut violations = username_violations("username", &req.username);
+        violations.extend(self.password_policy.violations(
+            "password",
+            &req.password,
+            &req.username,
+        ));
+        violations.extend(pin_violations("pin", req.pin, true));
+        if let Err(e) = ServiceError::check(violations) {
+            error!("Sign Up for Username: {} - {}", req.username, e);
+            return Err(e.into());
         }
-        match rx.await {
-            Ok(res) => match res {
-                Ok(user) => {
-                    info!("Signed up {:?}", user);
-                    let reply = SignUpResponse {
-                        message: "Signed up successfully".into(),
-                        success: true,
-                    };
-                    Ok(Response::new(reply))
-                }
-                Err(e) => {
-                    error!("Error while signing up {:?}", e);
-                    Err(Status::aborted(format!("Error while signing up: {}", e)))
-                }
-            },
+        let conn = self.repository.acquire().await?;
+        match conn.sign_up(request.into_inner()).await {
+            Ok(user) => {
+                info!("Signed up {:?}", user);
+                let reply = SignUpResponse {
+                    message: "Signed up successfully".into(),
+                    success: true,
+                };
+                Ok(Response::new(reply))
+            }
             Err(e) => {
                 error!("Error while signing up {:?}", e);
-                Err(Status::aborted("Error while signing up"))
+                Err(e.into())
             }
         }
     }
@@ -70,33 +75,151 @@ impl Auth for AuthService {
         &self,
         request: Request<SignInRequest>,
     ) -> Result<Response<SignInResponse>, Status> {
-        let (tx, rx) = channel::<Result<String, String>>();
-        match self
-            .db_message_sender
-            .send(Message::SignIn {
-                req: request.get_ref().clone(),
-                resp: tx,
-            })
+        let address = request.remote_addr().map(|address| address.ip());
+        let username = request.get_ref().username.clone();
+        if let Err(e) = self.throttle.attempt(&username, address) {
+            error!(
+                "Rejected sign in of {} from {:?} {:?}",
+                username, address, e
+            );
+            return Err(e.into());
+        }
+        let conn = match self.repository.acquire().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                self.throttle.abandoned(&username, address);
+                return Err(e.into());
+            }
+        };
+        match conn.sign_in(request.into_inner()).await {
+            Ok(SignInOutcome::Signed(token_pair)) => {
+                self.throttle.succeeded(&username, address);
+                info!("Signed in user: {}", username);
+                let reply = SignInResponse {
+                    token: token_pair.access_token,
+                    refresh_token: token_pair.refresh_token,
+                    expires_in: token_pair.expires_in,
+                    ..Default::default()
+                };
+                Ok(Response::new(reply))
+            }
+            // The attempt stays counted as failed until the challenge is
+            // answered, so that a leaked password alone cannot clear failures.
+            Ok(SignInOutcome::SecondFactorRequired {
+                challenge_token,
+                expires_in,
+            }) => {
+                info!("Challenged sign in of user: {}", username);
+                let reply = SignInResponse {
+                    expires_in,
+                    challenge_token,
+                    second_factor_required: true,
+                    ..Default::default()
+                };
+                Ok(Response::new(reply))
+            }
+            Err(e) => {
+                if !matches!(e, ServiceError::InvalidCredentials) {
+                    self.throttle.abandoned(&username, address);
+                }
+                error!("Error while signing in {:?}", e);
+                Err(e.into())
+            }
+        }
+    }
+
+    async fn verify_second_factor(
+        &self,
+        request: Request<VerifySecondFactorRequest>,
+    ) -> Result<Response<VerifySecondFactorResponse>, Status> {
+        let address = request.remote_addr().map(|address| address.ip());
+        let req = request.into_inner();
+        let conn = self.repository.acquire().await?;
+        match conn
+            .verify_second_factor(req.challenge_token, req.code)
+            .await
+        {
+            Ok((username, token_pair)) => {
+                self.throttle.succeeded(&username, address);
+                info!("Signed in user with a second factor: {}", username);
+                Ok(Response::new(VerifySecondFactorResponse {
+                    token: token_pair.access_token,
+                    refresh_token: token_pair.refresh_token,
+                    expires_in: token_pair.expires_in,
+                }))
+            }
+            Err(e) => {
+                error!("Error while verifying second factor {:?}", e);
+                Err(e.into())
+            }
+        }
+    }
+
+    async fn refresh_token(
+        &self,
+        request: Request<RefreshTokenRequest>,
+    ) -> Result<Response<RefreshTokenResponse>, Status> {
+        let conn = self.repository.acquire().await?;
+        match conn.refresh_token(request.into_inner().refresh_token).await {
+            Ok(token_pair) => {
+                let reply = RefreshTokenResponse {
+                    token: token_pair.access_token,
+                    refresh_token: token_pair.refresh_token,
+                    expires_in: token_pair.expires_in,
+                };
+                Ok(Response::new(reply))
+            }
+            Err(e) => {
+                error!("Error while refreshing token {:?}", e);
+                Err(e.into())
+            }
+        }
+    }
+
+    async fn sign_out(
+        &self,
+        request: Request<SignOutRequest>,
+    ) -> Result<Response<SignOutResponse>, Status> {
+        let claims = authenticate(request.metadata(), &self.revocations)?;
+        let refresh_token = Some(request.into_inner().refresh_token).filter(|t| !t.is_empty());
+        let conn = self.repository.acquire().await?;
+        match conn
+            .sign_out(claims.username.clone(), claims.jti.clone(), refresh_token)
             .await
         {
-            Ok(_) => {}
-            Err(e) => error!("Failed to send sign in message to DB manager {:?}", e),
+            Ok(()) => {
+                self.revocations.revoke(claims.jti, claims.expires_at);
+                info!("Signed out user: {}", claims.username);
+                Ok(Response::new(SignOutResponse {}))
+            }
+            Err(e) => {
+                error!("Error while signing out {:?}", e);
+                Err(e.into())
+            }
         }
-        match rx.await {
-            Ok(res) => match res {
-                Ok(token) => {
-                    info!("Signed in user: {}", request.get_ref().username);
-                    let reply = SignInResponse { token };
-                    Ok(Response::new(reply))
-                }
-                Err(e) => {
-                    error!("Error while signing in {:?}", e);
-                    Err(Status::unauthenticated(e))
+    }
+
+    async fn sign_out_everywhere(
+        &self,
+        request: Request<SignOutEverywhereRequest>,
+    ) -> Result<Response<SignOutEverywhereResponse>, Status> {
+        let claims = authenticate(request.metadata(), &self.revocations)?;
+        let conn = self.repository.acquire().await?;
+        match conn.sign_out_everywhere(claims.username.clone()).await {
+            Ok(revoked) => {
+                let revoked_tokens = revoked.len() as u32;
+                for token in revoked {
+                    self.revocations.revoke(token.jti, token.expires_at);
                 }
-            },
+                info!(
+                    "Signed out user {} everywhere, revoked {} tokens",
+                    claims.username, revoked_tokens
+                );
+                Ok(Response::new(SignOutEverywhereResponse { revoked_tokens }))
+            }
             Err(e) => {
-                error!("Error while signing up {:?}", e);
-                Err(Status::aborted("Error while signing up"))
+                error!("Error while signing out everywhere {:?}", e);
+                Err(e.into())
             }
         }
     }
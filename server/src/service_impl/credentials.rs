@@ -0,0 +1,252 @@
+use crate::config::env_or;
+use crate::error::Violation;
+
+const MIN_USERNAME_LENGTH: usize = 3;
+const MAX_USERNAME_LENGTH: usize = 32;
+
+/// Names that could pass for the server or the people running it.
+const RESERVED_USERNAMES: [&str; 9] = [
+    "admin",
+    "administrator",
+    "anonymous",
+    "deleted",
+    "moderator",
+    "root",
+    "support",
+    "system",
+    "todo",
+];
+
+/// Longer passwords are rejected so that hashing them stays cheap.
+const MAX_PASSWORD_LENGTH: usize = 128;
+
+/// Violations of the username rules: 3 to 32 ASCII letters, digits, '.', '_'
+/// or '-', starting with a letter or a digit, and not a reserved name.
+pub(crate) fn username_violations(field: &str, username: &str) -> Vec<Violation> {
+    let mut violations = vec![];
+    let length = username.chars().count();
+    if !(MIN_USERNAME_LENGTH..=MAX_USERNAME_LENGTH).contains(&length) {
+        violations.push(Violation::new(
+            field,
+            format!(
+                "Username should be {} to {} characters long",
+                MIN_USERNAME_LENGTH, MAX_USERNAME_LENGTH
+            ),
+        ));
+    }
+    if let Some(c) = username
+        .chars()
+        .find(|c| !c.is_ascii_alphanumeric() && !matches!(c, '.' | '_' | '-'))
+    {
+        violations.push(Violation::new(
+            field,
+            format!(
+                "Username should only contain letters, digits, '.', '_' or '-', found {:?}",
+                c
+            ),
+        ));
+    }
+    if matches!(username.chars().next(), Some(c) if !c.is_ascii_alphanumeric()) {
+        violations.push(Violation::new(
+            field,
+            "Username should start with a letter or a digit",
+        ));
+    }
+    if RESERVED_USERNAMES
+        .iter()
+        .any(|reserved| reserved.eq_ignore_ascii_case(username))
+    {
+        violations.push(Violation::new(
+            field,
+            format!("Username {} is reserved", username),
+        ));
+    }
+    violations
+}
+
+/// Violations of the PIN rules: exactly 4 digits, or 0 when the PIN is
+/// `optional` and not given.
+pub(crate) fn pin_violations(field: &str, pin: i32, optional: bool) -> Vec<Violation> {
+    if (1000..=9999).contains(&pin) || (optional && pin == 0) {
+        vec![]
+    } else {
+        vec![Violation::new(field, "PIN should consist only 4 digits")]
+    }
+}
+
+/// Rules passwords are checked against, configured with `PASSWORD_MIN_LENGTH`
+/// (10 by default) and `PASSWORD_MIN_CHARACTER_CLASSES`, the number of
+/// lowercase, uppercase, digit and other characters a password should mix
+/// (2 by default).
+#[derive(Debug, Clone)]
+pub(crate) struct PasswordPolicy {
+    min_length: usize,
+    min_classes: usize,
+}
+
+impl PasswordPolicy {
+    pub fn from_env() -> Self {
+        let setting = |name: &str, default: usize, max: usize| env_or(name, default).clamp(1, max);
+        Self {
+            min_length: setting("PASSWORD_MIN_LENGTH", 10, MAX_PASSWORD_LENGTH),
+            min_classes: setting("PASSWORD_MIN_CHARACTER_CLASSES", 2, 4),
+        }
+    }
+
+    /// Violations of the policy by the password of `username`.
+    pub fn violations(&self, field: &str, password: &str, username: &str) -> Vec<Violation> {
+        if password.is_empty() {
+            return vec![Violation::new(field, "Password is required")];
+        }
+        let mut violations = vec![];
+        let length = password.chars().count();
+        if length < self.min_length {
+            violations.push(Violation::new(
+                field,
+                format!(
+                    "Password should be at least {} characters long",
+                    self.min_length
+                ),
+            ));
+        }
+        if length > MAX_PASSWORD_LENGTH {
+            violations.push(Violation::new(
+                field,
+                format!(
+                    "Password should be at most {} characters long",
+                    MAX_PASSWORD_LENGTH
+                ),
+            ));
+        }
+        let classes = [
+            password.chars().any(char::is_lowercase),
+            password.chars().any(char::is_uppercase),
+            password.chars().any(char::is_numeric),
+            password.chars().any(|c| !c.is_alphanumeric()),
+        ];
+        if classes.iter().filter(|class| **class).count() < self.min_classes {
+            violations.push(Violation::new(
+                field,
+                format!(
+                    "Password should mix at least {} of lowercase letters, uppercase letters, digits and other characters",
+                    self.min_classes
+                ),
+            ));
+        }
+        if !username.is_empty() && password.to_lowercase().contains(&username.to_lowercase()) {
+            violations.push(Violation::new(
+                field,
+                "Password should not contain the username",
+            ));
+        }
+        violations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn descriptions(violations: Vec<Violation>) -> Vec<String> {
+        violations
+            .into_iter()
+            .map(|violation| violation.description)
+            .collect()
+    }
+
+    fn policy() -> PasswordPolicy {
+        PasswordPolicy {
+            min_length: 10,
+            min_classes: 2,
+        }
+    }
+
+    #[test]
+    fn accepts_valid_usernames() {
+        for username in ["bob", "alice.smith", "j_doe-42", "7of9"] {
+            assert!(username_violations("username", username).is_empty());
+        }
+    }
+
+    #[test]
+    fn rejects_usernames_breaking_each_rule() {
+        assert_eq!(
+            descriptions(username_violations("username", "al")),
+            vec!["Username should be 3 to 32 characters long"]
+        );
+        assert_eq!(username_violations("username", &"a".repeat(33)).len(), 1);
+        assert_eq!(
+            descriptions(username_violations("username", "alice smith")),
+            vec!["Username should only contain letters, digits, '.', '_' or '-', found ' '"]
+        );
+        assert_eq!(
+            descriptions(username_violations("username", "_alice")),
+            vec!["Username should start with a letter or a digit"]
+        );
+        assert_eq!(
+            descriptions(username_violations("username", "Admin")),
+            vec!["Username Admin is reserved"]
+        );
+        // Every broken rule is reported.
+        let violations = username_violations("new_username", "é");
+        assert_eq!(violations.len(), 3);
+        assert!(violations
+            .iter()
+            .all(|violation| violation.field == "new_username"));
+    }
+
+    #[test]
+    fn accepts_four_digit_pins_and_no_pin_when_optional() {
+        assert!(pin_violations("pin", 1234, false).is_empty());
+        assert!(pin_violations("pin", 0, true).is_empty());
+        assert_eq!(pin_violations("pin", 0, false).len(), 1);
+        assert_eq!(pin_violations("pin", 999, true).len(), 1);
+        assert_eq!(pin_violations("pin", 12345, true).len(), 1);
+    }
+
+    #[test]
+    fn accepts_passwords_following_the_policy() {
+        assert!(policy()
+            .violations("password", "correct horse battery staple", "alice")
+            .is_empty());
+        assert!(policy()
+            .violations("password", "Tr0ub4dor&3", "alice")
+            .is_empty());
+    }
+
+    #[test]
+    fn rejects_passwords_breaking_the_policy() {
+        assert_eq!(
+            descriptions(policy().violations("password", "", "alice")),
+            vec!["Password is required"]
+        );
+        assert_eq!(
+            descriptions(policy().violations("password", "Short1", "alice")),
+            vec!["Password should be at least 10 characters long"]
+        );
+        assert_eq!(
+            descriptions(policy().violations("password", &"aB".repeat(65), "alice")),
+            vec!["Password should be at most 128 characters long"]
+        );
+        assert_eq!(
+            descriptions(policy().violations("password", "onlylowercase", "alice")),
+            vec![
+                "Password should mix at least 2 of lowercase letters, uppercase letters, digits and other characters"
+            ]
+        );
+        assert_eq!(
+            descriptions(policy().violations("password", "my name is Alice", "alice")),
+            vec!["Password should not contain the username"]
+        );
+    }
+
+    #[test]
+    fn counts_character_classes_against_the_configured_minimum() {
+        let strict = PasswordPolicy {
+            min_length: 4,
+            min_classes: 4,
+        };
+        assert_eq!(strict.violations("password", "Abcdef12", "bob").len(), 1);
+        assert!(strict.violations("password", "Abcdef1!", "bob").is_empty());
+    }
+}
@@ -1,5 +1,10 @@
+mod account;
+mod admin;
 mod auth;
+mod credentials;
 mod todo;
 
+pub use account::AccountService;
+pub use admin::AdminService;
 pub use auth::AuthService;
 pub use todo::TodoService;
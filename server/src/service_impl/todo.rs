@@ -1,59 +1,877 @@
-use crate::db::Message;
+use crate::db::{normalize_tags, Recurrence, Repository, TodoQuery};
+use crate::error::ServiceError;
+use crate::events::TodoEvents;
 use crate::interceptors::AuthExtension;
-use proto::service::todo::{todo_server::Todo, GetTodoRequest, TodoItem};
-use tokio::sync::mpsc::{self, Sender};
+use crate::reminders::Reminders;
+use prost_types::Timestamp;
+use proto::service::todo::{
+    get_todos_response, sync_change::Change, sync_response, todo_server::Todo,
+    AcceptInvitationRequest, CreateProjectRequest, CreateTodoRequest, DeclineInvitationRequest,
+    DeclineInvitationResponse, DeleteProjectRequest, DeleteProjectResponse, DeleteTodoRequest,
+    DeleteTodoResponse, GetInvitationsRequest, GetInvitationsResponse, GetMembersRequest,
+    GetMembersResponse, GetProjectsRequest, GetProjectsResponse, GetTodoByIdRequest,
+    GetTodoRequest, GetTodoTreeRequest, GetTodosResponse, ImportRejection, ImportSummary,
+    Invitation, InviteMemberRequest, LinkTodosRequest, ListTagsRequest, ListTagsResponse,
+    MoveTodoRequest, PageInfo, Project, ProjectMember, ProjectRole, Reminder, RemoveMemberRequest,
+    RemoveMemberResponse, SetMemberRoleRequest, SetParentRequest, StreamRemindersRequest, SyncAck,
+    SyncChange, SyncResponse, TagTodoRequest, TodoEvent, TodoItem, TodoPriority, TodoStatus,
+    TodoTree, TransitionTodoRequest, TransitionTodoResponse, UpdateProjectRequest,
+    UpdateTodoRequest, WatchTodosRequest,
+};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::mpsc;
 use tokio_stream::wrappers::ReceiverStream;
-use tonic::{Request, Response, Status};
+use tonic::{Request, Response, Status, Streaming};
+use tracing::info;
 use tracing::log::error;
+
+/// Length of the `title` column.
+const MAX_TITLE_LENGTH: usize = 255;
+
+/// Length of the `name` column of `project`.
+const MAX_PROJECT_NAME_LENGTH: usize = 255;
+
+/// Number of imported todos inserted per transaction.
+const IMPORT_BATCH_SIZE: usize = 100;
+
 #[derive(Debug)]
 pub struct TodoService {
-    db_message_sender: Sender<Message>,
+    repository: Repository,
+    events: TodoEvents,
+    reminders: Reminders,
 }
 
 impl TodoService {
-    pub fn new(db_message_sender: Sender<Message>) -> Self {
-        Self { db_message_sender }
+    pub fn new(repository: Repository, events: TodoEvents, reminders: Reminders) -> Self {
+        Self {
+            repository,
+            events,
+            reminders,
+        }
+    }
+}
+
+fn failed(action: &str, e: ServiceError) -> Status {
+    error!("Error while {} {:?}", action, e);
+    e.into()
+}
+
+pub(crate) fn username<T>(request: &Request<T>) -> Result<String, Status> {
+    match request.extensions().get::<AuthExtension>() {
+        Some(auth_extensions) => Ok(auth_extensions.username.to_string()),
+        None => Err(Status::unauthenticated("Unauthorized request")),
+    }
+}
+
+/// Normalized tags of an AddTags or RemoveTags request, which must name at
+/// least one.
+fn request_tags(tags: Vec<String>) -> Result<Vec<String>, ServiceError> {
+    let tags = normalize_tags("tags", tags)?;
+    if tags.is_empty() {
+        return Err(ServiceError::invalid_argument(
+            "tags",
+            "At least one tag is required",
+        ));
+    }
+    Ok(tags)
+}
+
+/// Trimmed name of a project, which must not be empty.
+fn project_name(name: &str) -> Result<String, ServiceError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(ServiceError::invalid_argument(
+            "name",
+            "Project name should not be empty",
+        ));
+    }
+    if name.chars().count() > MAX_PROJECT_NAME_LENGTH {
+        return Err(ServiceError::invalid_argument(
+            "name",
+            format!(
+                "Project name should be at most {} characters",
+                MAX_PROJECT_NAME_LENGTH
+            ),
+        ));
+    }
+    Ok(name.to_string())
+}
+
+fn project_not_found(id: u32) -> Status {
+    ServiceError::NotFound(format!("Project {}", id)).into()
+}
+
+fn validate_member(username: &str, role: i32) -> Result<(), ServiceError> {
+    if username.trim().is_empty() {
+        return Err(ServiceError::invalid_argument(
+            "username",
+            "Username should not be empty",
+        ));
+    }
+    if ProjectRole::from_i32(role).is_none() {
+        return Err(ServiceError::invalid_argument(
+            "role",
+            format!("Unknown project role {}", role),
+        ));
+    }
+    Ok(())
+}
+
+fn validate_todo(
+    title: &str,
+    description: &str,
+    status: i32,
+    priority: i32,
+) -> Result<(), ServiceError> {
+    if title.chars().count() > MAX_TITLE_LENGTH {
+        return Err(ServiceError::invalid_argument(
+            "title",
+            format!("Title should be at most {} characters", MAX_TITLE_LENGTH),
+        ));
+    }
+    if description.trim().is_empty() {
+        return Err(ServiceError::invalid_argument(
+            "description",
+            "Description should not be empty",
+        ));
+    }
+    if TodoStatus::from_i32(status).is_none() {
+        return Err(ServiceError::invalid_argument(
+            "status",
+            format!("Unknown todo status {}", status),
+        ));
+    }
+    if TodoPriority::from_i32(priority).is_none() {
+        return Err(ServiceError::invalid_argument(
+            "priority",
+            format!("Unknown todo priority {}", priority),
+        ));
+    }
+    Ok(())
+}
+
+/// Canonical form of the recurrence rule of a todo due at `due_at`, which is
+/// required when the todo recurs.
+fn recurrence(rule: &str, due_at: &Option<Timestamp>) -> Result<String, ServiceError> {
+    if rule.trim().is_empty() {
+        return Ok(String::new());
+    }
+    let rule = Recurrence::parse(rule)?;
+    if due_at.is_none() {
+        return Err(ServiceError::invalid_argument(
+            "due_at",
+            "Recurring todos should have a due date",
+        ));
+    }
+    Ok(rule.to_string())
+}
+
+fn todo_not_found(id: u32) -> Status {
+    todo_not_found_error(id).into()
+}
+
+fn todo_not_found_error(id: u32) -> ServiceError {
+    ServiceError::NotFound(format!("Todo item {}", id))
+}
+
+/// Imports one batch, recording every todo of a failed batch as rejected.
+async fn import_batch(
+    repository: &Repository,
+    username: &str,
+    batch: Vec<(u32, TodoItem)>,
+    summary: &mut ImportSummary,
+) -> Result<(), Status> {
+    let (indexes, todos): (Vec<u32>, Vec<TodoItem>) = batch.into_iter().unzip();
+    let conn = repository.acquire().await?;
+    match conn.import_todos(username.to_string(), todos).await {
+        Ok(imported) => summary.imported += imported.len() as u32,
+        Err(e) => {
+            error!("Error while importing todos {:?}", e);
+            let reason = e.to_string();
+            summary
+                .rejected
+                .extend(indexes.into_iter().map(|index| ImportRejection {
+                    index,
+                    reason: reason.clone(),
+                }));
+        }
+    }
+    Ok(())
+}
+
+async fn apply_change(
+    repository: &Repository,
+    username: String,
+    change: Option<Change>,
+) -> Result<TodoItem, ServiceError> {
+    let change = match change {
+        Some(change) => change,
+        None => {
+            return Err(ServiceError::invalid_argument(
+                "change",
+                "Change is missing",
+            ))
+        }
+    };
+    let conn = repository.acquire().await?;
+    match change {
+        Change::Create(mut req) => {
+            validate_todo(&req.title, &req.description, req.status, req.priority)?;
+            req.recurrence = recurrence(&req.recurrence, &req.due_at)?;
+            conn.create_todo(username, req).await
+        }
+        Change::Update(mut req) => {
+            validate_todo(&req.title, &req.description, req.status, req.priority)?;
+            req.recurrence = recurrence(&req.recurrence, &req.due_at)?;
+            let id = req.id;
+            conn.update_todo(username, req)
+                .await?
+                .ok_or_else(|| todo_not_found_error(id))
+        }
+        Change::Delete(req) => {
+            if conn.delete_todo(username, req.id).await? {
+                Ok(TodoItem {
+                    id: req.id,
+                    ..Default::default()
+                })
+            } else {
+                Err(todo_not_found_error(req.id))
+            }
+        }
+    }
+}
+
+/// Applies a change received on a SyncTodos stream and builds its acknowledgement.
+async fn acknowledge(repository: &Repository, username: &str, change: SyncChange) -> SyncResponse {
+    let ack = match apply_change(repository, username.to_string(), change.change).await {
+        Ok(todo) => SyncAck {
+            change_id: change.change_id,
+            success: true,
+            error: String::new(),
+            todo: Some(todo),
+        },
+        Err(e) => {
+            error!("Error while syncing change {} {:?}", change.change_id, e);
+            SyncAck {
+                change_id: change.change_id,
+                success: false,
+                error: e.to_string(),
+                todo: None,
+            }
+        }
+    };
+    SyncResponse {
+        message: Some(sync_response::Message::Ack(ack)),
     }
 }
 
 #[tonic::async_trait]
 impl Todo for TodoService {
-    type GetTodosStream = ReceiverStream<Result<TodoItem, Status>>;
+    type GetTodosStream = ReceiverStream<Result<GetTodosResponse, Status>>;
+    type WatchTodosStream = ReceiverStream<Result<TodoEvent, Status>>;
+    type StreamRemindersStream = ReceiverStream<Result<Reminder, Status>>;
 
     async fn get_todos(
         &self,
         request: Request<GetTodoRequest>,
     ) -> Result<Response<Self::GetTodosStream>, Status> {
-        if let Some(auth_extensions) = request.extensions().get::<AuthExtension>() {
-            let (tx, rx) = mpsc::channel::<Result<TodoItem, Status>>(4);
-            let (db_tx, mut db_rx) = mpsc::channel::<Result<TodoItem, String>>(4);
-            match self
-                .db_message_sender
-                .send(Message::GetTodos {
-                    username: auth_extensions.username.to_string(),
-                    resp: db_tx,
-                })
-                .await
+        let username = username(&request)?;
+        let query = TodoQuery::from_request(request.into_inner())?;
+        let page_size = query.page_size as usize;
+        let (db_tx, mut db_rx) = mpsc::channel::<Result<TodoItem, ServiceError>>(4);
+        let conn = self.repository.acquire().await?;
+        let db_query = query.clone();
+        tokio::spawn(async move {
+            conn.get_todos(username, db_query, db_tx).await;
+        });
+
+        let (tx, rx) = mpsc::channel::<Result<GetTodosResponse, Status>>(4);
+        tokio::spawn(async move {
+            let todo = |todo_item| GetTodosResponse {
+                message: Some(get_todos_response::Message::Todo(todo_item)),
+            };
+            // The store reads one todo past the page, telling whether there is
+            // a next one.
+            let mut sent = 0;
+            let mut last = None;
+            let mut next_page_token = String::new();
+            while let Some(message) = db_rx.recv().await {
+                let todo_item = match message {
+                    Ok(todo_item) => todo_item,
+                    Err(e) => {
+                        let _ = tx.send(Err(failed("getting todos", e))).await;
+                        return;
+                    }
+                };
+                if sent == page_size {
+                    if let Some(last) = &last {
+                        next_page_token = query.page_token(last);
+                    }
+                    break;
+                }
+                sent += 1;
+                last = Some(todo_item.clone());
+                if tx.send(Ok(todo(todo_item))).await.is_err() {
+                    return;
+                }
+            }
+            let page_info = GetTodosResponse {
+                message: Some(get_todos_response::Message::PageInfo(PageInfo {
+                    next_page_token,
+                })),
+            };
+            let _ = tx.send(Ok(page_info)).await;
+        });
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+
+    async fn get_todo(
+        &self,
+        request: Request<GetTodoByIdRequest>,
+    ) -> Result<Response<TodoItem>, Status> {
+        let username = username(&request)?;
+        let id = request.get_ref().id;
+        let conn = self.repository.acquire().await?;
+        match conn.get_todo(username, id).await {
+            Ok(Some(todo_item)) => Ok(Response::new(todo_item)),
+            Ok(None) => Err(todo_not_found(id)),
+            Err(e) => Err(failed("getting todo", e)),
+        }
+    }
+
+    async fn create_todo(
+        &self,
+        request: Request<CreateTodoRequest>,
+    ) -> Result<Response<TodoItem>, Status> {
+        let username = username(&request)?;
+        let mut req = request.into_inner();
+        validate_todo(&req.title, &req.description, req.status, req.priority)?;
+        req.recurrence = recurrence(&req.recurrence, &req.due_at)?;
+        let conn = self.repository.acquire().await?;
+        match conn.create_todo(username, req).await {
+            Ok(todo_item) => Ok(Response::new(todo_item)),
+            Err(e) => Err(failed("creating todo", e)),
+        }
+    }
+
+    async fn update_todo(
+        &self,
+        request: Request<UpdateTodoRequest>,
+    ) -> Result<Response<TodoItem>, Status> {
+        let username = username(&request)?;
+        let mut req = request.into_inner();
+        validate_todo(&req.title, &req.description, req.status, req.priority)?;
+        req.recurrence = recurrence(&req.recurrence, &req.due_at)?;
+        let id = req.id;
+        let conn = self.repository.acquire().await?;
+        match conn.update_todo(username, req).await {
+            Ok(Some(todo_item)) => Ok(Response::new(todo_item)),
+            Ok(None) => Err(todo_not_found(id)),
+            Err(e) => Err(failed("updating todo", e)),
+        }
+    }
+
+    async fn delete_todo(
+        &self,
+        request: Request<DeleteTodoRequest>,
+    ) -> Result<Response<DeleteTodoResponse>, Status> {
+        let username = username(&request)?;
+        let id = request.get_ref().id;
+        let conn = self.repository.acquire().await?;
+        match conn.delete_todo(username, id).await {
+            Ok(true) => Ok(Response::new(DeleteTodoResponse { id })),
+            Ok(false) => Err(todo_not_found(id)),
+            Err(e) => Err(failed("deleting todo", e)),
+        }
+    }
+
+    async fn transition_todo(
+        &self,
+        request: Request<TransitionTodoRequest>,
+    ) -> Result<Response<TransitionTodoResponse>, Status> {
+        let username = username(&request)?;
+        let req = request.into_inner();
+        if TodoStatus::from_i32(req.status).is_none() {
+            return Err(ServiceError::invalid_argument(
+                "status",
+                format!("Unknown todo status {}", req.status),
+            )
+            .into());
+        }
+        let conn = self.repository.acquire().await?;
+        match conn.transition_todo(username, req.id, req.status).await {
+            Ok(Some((todo_item, transition))) => Ok(Response::new(TransitionTodoResponse {
+                todo: Some(todo_item),
+                transition: Some(transition.into()),
+            })),
+            Ok(None) => Err(todo_not_found(req.id)),
+            Err(e) => Err(failed("transitioning todo", e)),
+        }
+    }
+
+    async fn add_tags(
+        &self,
+        request: Request<TagTodoRequest>,
+    ) -> Result<Response<TodoItem>, Status> {
+        let username = username(&request)?;
+        let req = request.into_inner();
+        let tags = request_tags(req.tags)?;
+        let conn = self.repository.acquire().await?;
+        match conn.add_tags(username, req.id, tags).await {
+            Ok(Some(todo_item)) => Ok(Response::new(todo_item)),
+            Ok(None) => Err(todo_not_found(req.id)),
+            Err(e) => Err(failed("tagging todo", e)),
+        }
+    }
+
+    async fn remove_tags(
+        &self,
+        request: Request<TagTodoRequest>,
+    ) -> Result<Response<TodoItem>, Status> {
+        let username = username(&request)?;
+        let req = request.into_inner();
+        let tags = request_tags(req.tags)?;
+        let conn = self.repository.acquire().await?;
+        match conn.remove_tags(username, req.id, tags).await {
+            Ok(Some(todo_item)) => Ok(Response::new(todo_item)),
+            Ok(None) => Err(todo_not_found(req.id)),
+            Err(e) => Err(failed("untagging todo", e)),
+        }
+    }
+
+    async fn list_tags(
+        &self,
+        request: Request<ListTagsRequest>,
+    ) -> Result<Response<ListTagsResponse>, Status> {
+        let username = username(&request)?;
+        let conn = self.repository.acquire().await?;
+        match conn.list_tags(username).await {
+            Ok(tags) => Ok(Response::new(ListTagsResponse {
+                tags: tags.into_iter().map(Into::into).collect(),
+            })),
+            Err(e) => Err(failed("listing tags", e)),
+        }
+    }
+
+    async fn create_project(
+        &self,
+        request: Request<CreateProjectRequest>,
+    ) -> Result<Response<Project>, Status> {
+        let username = username(&request)?;
+        let name = project_name(&request.get_ref().name)?;
+        let conn = self.repository.acquire().await?;
+        match conn.create_project(username, name).await {
+            Ok(project) => Ok(Response::new(project.into())),
+            Err(e) => Err(failed("creating project", e)),
+        }
+    }
+
+    async fn get_projects(
+        &self,
+        request: Request<GetProjectsRequest>,
+    ) -> Result<Response<GetProjectsResponse>, Status> {
+        let username = username(&request)?;
+        let conn = self.repository.acquire().await?;
+        match conn.get_projects(username).await {
+            Ok(projects) => Ok(Response::new(GetProjectsResponse {
+                projects: projects.into_iter().map(Into::into).collect(),
+            })),
+            Err(e) => Err(failed("getting projects", e)),
+        }
+    }
+
+    async fn update_project(
+        &self,
+        request: Request<UpdateProjectRequest>,
+    ) -> Result<Response<Project>, Status> {
+        let username = username(&request)?;
+        let req = request.into_inner();
+        let name = project_name(&req.name)?;
+        let conn = self.repository.acquire().await?;
+        match conn.rename_project(username, req.id, name).await {
+            Ok(Some(project)) => Ok(Response::new(project.into())),
+            Ok(None) => Err(project_not_found(req.id)),
+            Err(e) => Err(failed("updating project", e)),
+        }
+    }
+
+    async fn delete_project(
+        &self,
+        request: Request<DeleteProjectRequest>,
+    ) -> Result<Response<DeleteProjectResponse>, Status> {
+        let username = username(&request)?;
+        let id = request.get_ref().id;
+        let conn = self.repository.acquire().await?;
+        match conn.delete_project(username, id).await {
+            Ok(true) => Ok(Response::new(DeleteProjectResponse {})),
+            Ok(false) => Err(project_not_found(id)),
+            Err(e) => Err(failed("deleting project", e)),
+        }
+    }
+
+    async fn move_todo(
+        &self,
+        request: Request<MoveTodoRequest>,
+    ) -> Result<Response<TodoItem>, Status> {
+        let username = username(&request)?;
+        let req = request.into_inner();
+        let conn = self.repository.acquire().await?;
+        match conn
+            .move_todo(username, req.id, req.project_id, req.position)
+            .await
+        {
+            Ok(Some(todo_item)) => Ok(Response::new(todo_item)),
+            Ok(None) => Err(todo_not_found(req.id)),
+            Err(e) => Err(failed("moving todo", e)),
+        }
+    }
+
+    async fn set_parent(
+        &self,
+        request: Request<SetParentRequest>,
+    ) -> Result<Response<TodoItem>, Status> {
+        let username = username(&request)?;
+        let req = request.into_inner();
+        let conn = self.repository.acquire().await?;
+        match conn.set_parent(username, req.id, req.parent_id).await {
+            Ok(Some(todo_item)) => Ok(Response::new(todo_item)),
+            Ok(None) => Err(todo_not_found(req.id)),
+            Err(e) => Err(failed("setting todo parent", e)),
+        }
+    }
+
+    async fn link_todos(
+        &self,
+        request: Request<LinkTodosRequest>,
+    ) -> Result<Response<TodoItem>, Status> {
+        let username = username(&request)?;
+        let req = request.into_inner();
+        let conn = self.repository.acquire().await?;
+        match conn.link_todos(username, req.id, req.blocked_by_id).await {
+            Ok(Some(todo_item)) => Ok(Response::new(todo_item)),
+            Ok(None) => Err(todo_not_found(req.id)),
+            Err(e) => Err(failed("linking todos", e)),
+        }
+    }
+
+    async fn unlink_todos(
+        &self,
+        request: Request<LinkTodosRequest>,
+    ) -> Result<Response<TodoItem>, Status> {
+        let username = username(&request)?;
+        let req = request.into_inner();
+        let conn = self.repository.acquire().await?;
+        match conn.unlink_todos(username, req.id, req.blocked_by_id).await {
+            Ok(Some(todo_item)) => Ok(Response::new(todo_item)),
+            Ok(None) => Err(todo_not_found(req.id)),
+            Err(e) => Err(failed("unlinking todos", e)),
+        }
+    }
+
+    async fn get_todo_tree(
+        &self,
+        request: Request<GetTodoTreeRequest>,
+    ) -> Result<Response<TodoTree>, Status> {
+        let username = username(&request)?;
+        let id = request.get_ref().id;
+        let conn = self.repository.acquire().await?;
+        match conn.get_todo_tree(username, id).await {
+            Ok(Some(todo_tree)) => Ok(Response::new(todo_tree)),
+            Ok(None) => Err(todo_not_found(id)),
+            Err(e) => Err(failed("getting todo tree", e)),
+        }
+    }
+
+    async fn get_members(
+        &self,
+        request: Request<GetMembersRequest>,
+    ) -> Result<Response<GetMembersResponse>, Status> {
+        let username = username(&request)?;
+        let project_id = request.get_ref().project_id;
+        let conn = self.repository.acquire().await?;
+        match conn.get_members(username, project_id).await {
+            Ok(members) => Ok(Response::new(GetMembersResponse {
+                members: members.into_iter().map(Into::into).collect(),
+            })),
+            Err(e) => Err(failed("getting project members", e)),
+        }
+    }
+
+    async fn invite_member(
+        &self,
+        request: Request<InviteMemberRequest>,
+    ) -> Result<Response<Invitation>, Status> {
+        let username = username(&request)?;
+        let req = request.into_inner();
+        validate_member(&req.username, req.role)?;
+        let conn = self.repository.acquire().await?;
+        match conn
+            .invite_member(username, req.project_id, req.username, req.role)
+            .await
+        {
+            Ok(invitation) => Ok(Response::new(invitation.into())),
+            Err(e) => Err(failed("inviting project member", e)),
+        }
+    }
+
+    async fn get_invitations(
+        &self,
+        request: Request<GetInvitationsRequest>,
+    ) -> Result<Response<GetInvitationsResponse>, Status> {
+        let username = username(&request)?;
+        let conn = self.repository.acquire().await?;
+        match conn.get_invitations(username).await {
+            Ok(invitations) => Ok(Response::new(GetInvitationsResponse {
+                invitations: invitations.into_iter().map(Into::into).collect(),
+            })),
+            Err(e) => Err(failed("getting invitations", e)),
+        }
+    }
+
+    async fn accept_invitation(
+        &self,
+        request: Request<AcceptInvitationRequest>,
+    ) -> Result<Response<Project>, Status> {
+        let username = username(&request)?;
+        let id = request.get_ref().id;
+        let conn = self.repository.acquire().await?;
+        match conn.accept_invitation(username, id).await {
+            Ok(Some(project)) => Ok(Response::new(project.into())),
+            Ok(None) => Err(ServiceError::NotFound(format!("Invitation {}", id)).into()),
+            Err(e) => Err(failed("accepting invitation", e)),
+        }
+    }
+
+    async fn decline_invitation(
+        &self,
+        request: Request<DeclineInvitationRequest>,
+    ) -> Result<Response<DeclineInvitationResponse>, Status> {
+        let username = username(&request)?;
+        let id = request.get_ref().id;
+        let conn = self.repository.acquire().await?;
+        match conn.decline_invitation(username, id).await {
+            Ok(true) => Ok(Response::new(DeclineInvitationResponse {})),
+            Ok(false) => Err(ServiceError::NotFound(format!("Invitation {}", id)).into()),
+            Err(e) => Err(failed("declining invitation", e)),
+        }
+    }
+
+    async fn set_member_role(
+        &self,
+        request: Request<SetMemberRoleRequest>,
+    ) -> Result<Response<ProjectMember>, Status> {
+        let username = username(&request)?;
+        let req = request.into_inner();
+        validate_member(&req.username, req.role)?;
+        let conn = self.repository.acquire().await?;
+        match conn
+            .set_member_role(username, req.project_id, req.username.clone(), req.role)
+            .await
+        {
+            Ok(Some(member)) => Ok(Response::new(member.into())),
+            Ok(None) => Err(ServiceError::NotFound(format!("Member {}", req.username)).into()),
+            Err(e) => Err(failed("setting member role", e)),
+        }
+    }
+
+    async fn remove_member(
+        &self,
+        request: Request<RemoveMemberRequest>,
+    ) -> Result<Response<RemoveMemberResponse>, Status> {
+        let username = username(&request)?;
+        let req = request.into_inner();
+        let conn = self.repository.acquire().await?;
+        match conn
+            .remove_member(username, req.project_id, req.username.clone())
+            .await
+        {
+            Ok(true) => Ok(Response::new(RemoveMemberResponse {})),
+            Ok(false) => Err(ServiceError::NotFound(format!("Member {}", req.username)).into()),
+            Err(e) => Err(failed("removing project member", e)),
+        }
+    }
+
+    async fn watch_todos(
+        &self,
+        request: Request<WatchTodosRequest>,
+    ) -> Result<Response<Self::WatchTodosStream>, Status> {
+        let username = username(&request)?;
+        let subscription = self
+            .events
+            .subscribe(&username, &request.get_ref().resume_token)?;
+        let (tx, rx) = mpsc::channel::<Result<TodoEvent, Status>>(4);
+        tokio::spawn(async move {
+            for event in subscription.missed {
+                if tx.send(Ok(event)).await.is_err() {
+                    return;
+                }
+            }
+            let mut live = subscription.live;
+            loop {
+                // Stop as soon as the client goes away, even when the user is idle.
+                let received = tokio::select! {
+                    received = live.recv() => received,
+                    _ = tx.closed() => return,
+                };
+                let event = match received {
+                    Ok(event) => event,
+                    Err(RecvError::Lagged(skipped)) => {
+                        error!("Watch of {} skipped {} events", username, skipped);
+                        let _ = tx.send(Err(ServiceError::WatchLagged.into())).await;
+                        return;
+                    }
+                    Err(RecvError::Closed) => return,
+                };
+                if tx.send(Ok(event)).await.is_err() {
+                    return;
+                }
+            }
+        });
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+
+    async fn stream_reminders(
+        &self,
+        request: Request<StreamRemindersRequest>,
+    ) -> Result<Response<Self::StreamRemindersStream>, Status> {
+        let username = username(&request)?;
+        let mut live = self.reminders.subscribe(&username);
+        let (tx, rx) = mpsc::channel::<Result<Reminder, Status>>(4);
+        tokio::spawn(async move {
+            loop {
+                let received = tokio::select! {
+                    received = live.recv() => received,
+                    _ = tx.closed() => return,
+                };
+                let reminder = match received {
+                    Ok(reminder) => reminder,
+                    Err(RecvError::Lagged(skipped)) => {
+                        error!("Reminders of {} skipped {} reminders", username, skipped);
+                        let _ = tx.send(Err(ServiceError::WatchLagged.into())).await;
+                        return;
+                    }
+                    Err(RecvError::Closed) => return,
+                };
+                if tx.send(Ok(reminder)).await.is_err() {
+                    return;
+                }
+            }
+        });
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+
+    async fn import_todos(
+        &self,
+        request: Request<Streaming<TodoItem>>,
+    ) -> Result<Response<ImportSummary>, Status> {
+        let username = username(&request)?;
+        let mut stream = request.into_inner();
+        let mut summary = ImportSummary::default();
+        let mut batch = Vec::with_capacity(IMPORT_BATCH_SIZE);
+        let mut index = 0;
+        while let Some(mut todo_item) = stream.message().await? {
+            match validate_todo(
+                &todo_item.title,
+                &todo_item.description,
+                todo_item.status,
+                todo_item.priority,
+            )
+            .and_then(|()| recurrence(&todo_item.recurrence, &todo_item.due_at))
             {
-                Ok(_) => {}
-                Err(e) => error!("Failed to send get todos message to DB manager {:?}", e),
-            }
-            tokio::spawn(async move {
-                while let Some(message) = db_rx.recv().await {
-                    match message {
-                        Ok(res) => {
-                            tx.send(Ok(res)).await.unwrap();
+                Ok(rule) => {
+                    todo_item.recurrence = rule;
+                    batch.push((index, todo_item))
+                }
+                Err(e) => summary.rejected.push(ImportRejection {
+                    index,
+                    reason: e.to_string(),
+                }),
+            }
+            index += 1;
+            if batch.len() == IMPORT_BATCH_SIZE {
+                import_batch(&self.repository, &username, batch, &mut summary).await?;
+                batch = Vec::with_capacity(IMPORT_BATCH_SIZE);
+            }
+        }
+        if !batch.is_empty() {
+            import_batch(&self.repository, &username, batch, &mut summary).await?;
+        }
+        info!(
+            "Imported {} todos for {}, rejected {}",
+            summary.imported,
+            username,
+            summary.rejected.len()
+        );
+        Ok(Response::new(summary))
+    }
+
+    type SyncTodosStream = ReceiverStream<Result<SyncResponse, Status>>;
+
+    async fn sync_todos(
+        &self,
+        request: Request<Streaming<SyncChange>>,
+    ) -> Result<Response<Self::SyncTodosStream>, Status> {
+        let username = username(&request)?;
+        let mut changes = request.into_inner();
+        // The resume token comes with the first message, so it is awaited
+        // before subscribing.
+        let first = changes.message().await?;
+        let resume_token = first
+            .as_ref()
+            .map(|change| change.resume_token.as_str())
+            .unwrap_or_default();
+        let subscription = self.events.subscribe(&username, resume_token)?;
+        let repository = self.repository.clone();
+        let (tx, rx) = mpsc::channel::<Result<SyncResponse, Status>>(4);
+        tokio::spawn(async move {
+            let event = |event| SyncResponse {
+                message: Some(sync_response::Message::Event(event)),
+            };
+            for missed in subscription.missed {
+                if tx.send(Ok(event(missed))).await.is_err() {
+                    return;
+                }
+            }
+            let mut receiving = first.is_some();
+            // A first message carrying only the resume token is not acknowledged.
+            if let Some(change) = first.filter(|change| change.change.is_some()) {
+                let ack = acknowledge(&repository, &username, change).await;
+                if tx.send(Ok(ack)).await.is_err() {
+                    return;
+                }
+            }
+            let mut live = subscription.live;
+            // After the client has sent all its changes, it keeps receiving
+            // events until it cancels the call.
+            loop {
+                let response = tokio::select! {
+                    _ = tx.closed() => return,
+                    received = live.recv() => match received {
+                        Ok(received) => event(received),
+                        Err(RecvError::Lagged(skipped)) => {
+                            error!("Sync of {} skipped {} events", username, skipped);
+                            let _ = tx.send(Err(ServiceError::WatchLagged.into())).await;
+                            return;
+                        }
+                        Err(RecvError::Closed) => return,
+                    },
+                    change = changes.message(), if receiving => match change {
+                        Ok(Some(change)) => acknowledge(&repository, &username, change).await,
+                        Ok(None) => {
+                            receiving = false;
+                            continue;
                         }
                         Err(e) => {
-                            error!("Error while getting todos {:?}", e);
-                            // Err(Status::aborted("Error while signing up"));
+                            error!("Error while receiving sync changes {:?}", e);
+                            return;
                         }
-                    }
+                    },
+                };
+                if tx.send(Ok(response)).await.is_err() {
+                    return;
                 }
-            });
-            Ok(Response::new(ReceiverStream::new(rx)))
-        } else {
-            return Err(Status::unauthenticated("Unauthorized request"));
-        }
+            }
+        });
+        Ok(Response::new(ReceiverStream::new(rx)))
     }
 }
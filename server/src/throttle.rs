@@ -0,0 +1,460 @@
+use crate::clock;
+use crate::config::env_or;
+use crate::error::ServiceError;
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::future::Future;
+use std::hash::Hash;
+use std::net::IpAddr;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::{Arc, Mutex};
+use tracing::log::warn;
+
+/// Longest delay imposed between two attempts before the lockout kicks in.
+const MAX_BACKOFF_SECS: i64 = 60;
+
+/// Failed sign in attempts for a username or a peer address.
+#[derive(Debug, Clone, Copy, Default)]
+struct Failures {
+    count: u32,
+    last_failure_at: i64,
+}
+
+/// Failures of a username or a peer address that currently block sign in.
+#[derive(Debug, Clone)]
+pub struct Lockout {
+    pub username: Option<String>,
+    pub address: Option<IpAddr>,
+    pub failures: u32,
+    /// Whether the maximum of failures was reached, as opposed to a backoff
+    /// between two attempts.
+    pub locked: bool,
+    pub blocked_until: i64,
+}
+
+#[derive(Debug, Clone)]
+struct ThrottlePolicy {
+    max_failures: u32,
+    max_address_failures: u32,
+    lockout_secs: i64,
+}
+
+impl ThrottlePolicy {
+    /// Until when failures block sign in: twice as long after each failure,
+    /// then for the whole lockout once `max_failures` is reached.
+    fn blocked_until(&self, failures: &Failures, max_failures: u32) -> i64 {
+        if failures.count >= max_failures {
+            return failures.last_failure_at + self.lockout_secs;
+        }
+        let backoff = 2_i64
+            .saturating_pow(failures.count.saturating_sub(1))
+            .min(MAX_BACKOFF_SECS);
+        failures.last_failure_at + backoff
+    }
+}
+
+/// In-memory count of failed sign in attempts, per username and per peer
+/// address, checked before `SignIn` and the Account calls that check
+/// credentials reach the database.
+///
+/// Each failure doubles the delay before the next attempt is allowed, and
+/// reaching `SIGN_IN_MAX_FAILURES` (5 by default) failures for a username, or
+/// `SIGN_IN_MAX_ADDRESS_FAILURES` (20 by default) for an address, locks it for
+/// `SIGN_IN_LOCKOUT_SECS` (15 minutes by default). Failures are forgotten a
+/// lockout period after the last one, or when an admin unlocks them. A
+/// successful sign in clears the failures of the username only, so that an
+/// attacker cannot reset their address with an account of their own.
+#[derive(Debug, Clone)]
+pub struct SignInThrottle {
+    policy: ThrottlePolicy,
+    usernames: Arc<Mutex<HashMap<String, Failures>>>,
+    addresses: Arc<Mutex<HashMap<IpAddr, Failures>>>,
+    /// When failures older than a lockout period were last dropped.
+    swept_at: Arc<AtomicI64>,
+}
+
+impl SignInThrottle {
+    pub fn from_env() -> Self {
+        Self {
+            policy: ThrottlePolicy {
+                max_failures: env_or("SIGN_IN_MAX_FAILURES", 5).max(1),
+                max_address_failures: env_or("SIGN_IN_MAX_ADDRESS_FAILURES", 20).max(1),
+                lockout_secs: env_or("SIGN_IN_LOCKOUT_SECS", 15 * 60).max(1),
+            },
+            usernames: Arc::new(Mutex::new(HashMap::new())),
+            addresses: Arc::new(Mutex::new(HashMap::new())),
+            swept_at: Arc::new(AtomicI64::new(0)),
+        }
+    }
+
+    /// Starts a sign in attempt, failing when `username` or `address` has to
+    /// wait before trying again: with `AccountLocked` when the username is
+    /// locked, `SignInThrottled` otherwise.
+    ///
+    /// The attempt is counted as failed right away, so that concurrent
+    /// attempts cannot slip past the backoff. It is taken back by `succeeded`
+    /// or `abandoned`.
+    pub fn attempt(&self, username: &str, address: Option<IpAddr>) -> Result<(), ServiceError> {
+        let now = clock::now();
+        let mut usernames = self.usernames.lock().unwrap();
+        let mut addresses = self.addresses.lock().unwrap();
+        if let Some(failures) = usernames.get(username) {
+            let blocked_until = self
+                .policy
+                .blocked_until(failures, self.policy.max_failures);
+            if blocked_until > now {
+                let retry_after = blocked_until - now;
+                return Err(if failures.count >= self.policy.max_failures {
+                    ServiceError::AccountLocked { retry_after }
+                } else {
+                    ServiceError::SignInThrottled { retry_after }
+                });
+            }
+        }
+        if let Some(failures) = address.and_then(|address| addresses.get(&address)) {
+            let blocked_until = self
+                .policy
+                .blocked_until(failures, self.policy.max_address_failures);
+            if blocked_until > now {
+                return Err(ServiceError::SignInThrottled {
+                    retry_after: blocked_until - now,
+                });
+            }
+        }
+        self.sweep(&mut usernames, &mut addresses, now);
+        let count = self.fail(&mut usernames, username.to_string(), now);
+        if count == self.policy.max_failures {
+            warn!(
+                "Locked {} after {} failed sign in attempts",
+                username, count
+            );
+        }
+        if let Some(address) = address {
+            let count = self.fail(&mut addresses, address, now);
+            if count == self.policy.max_address_failures {
+                warn!("Locked {} after {} failed sign in attempts", address, count);
+            }
+        }
+        Ok(())
+    }
+
+    /// Ends an attempt that signed in, clearing the failures of the username.
+    pub fn succeeded(&self, username: &str, address: Option<IpAddr>) {
+        self.usernames.lock().unwrap().remove(username);
+        if let Some(address) = address {
+            forgive(&mut self.addresses.lock().unwrap(), &address);
+        }
+    }
+
+    /// Ends an attempt that failed for another reason than bad credentials.
+    pub fn abandoned(&self, username: &str, address: Option<IpAddr>) {
+        forgive(&mut self.usernames.lock().unwrap(), username);
+        if let Some(address) = address {
+            forgive(&mut self.addresses.lock().unwrap(), &address);
+        }
+    }
+
+    /// Runs `check`, a call checking the credentials of `username`, as an
+    /// attempt: failures of the credentials or of the second factor count as
+    /// failed attempts, so that a stolen access token does not give
+    /// unthrottled guesses at a PIN or a password either.
+    pub async fn check_credentials<T, F>(
+        &self,
+        username: &str,
+        address: Option<IpAddr>,
+        check: F,
+    ) -> Result<T, ServiceError>
+    where
+        F: Future<Output = Result<T, ServiceError>>,
+    {
+        self.attempt(username, address)?;
+        let result = check.await;
+        match &result {
+            Ok(_) => self.succeeded(username, address),
+            Err(ServiceError::InvalidCredentials | ServiceError::InvalidSecondFactor) => {}
+            Err(_) => self.abandoned(username, address),
+        }
+        result
+    }
+
+    /// Counts a failure for `key`, returning its failures so far. Failures
+    /// older than a lockout period are forgotten first.
+    fn fail<K: Eq + Hash>(&self, entries: &mut HashMap<K, Failures>, key: K, now: i64) -> u32 {
+        let failures = entries.entry(key).or_default();
+        if failures.last_failure_at + self.policy.lockout_secs <= now {
+            failures.count = 0;
+        }
+        failures.count += 1;
+        failures.last_failure_at = now;
+        failures.count
+    }
+
+    /// Drops the failures older than a lockout period, at most once per
+    /// lockout period so that attempts do not each go through every entry.
+    fn sweep(
+        &self,
+        usernames: &mut HashMap<String, Failures>,
+        addresses: &mut HashMap<IpAddr, Failures>,
+        now: i64,
+    ) {
+        let lockout_secs = self.policy.lockout_secs;
+        if self.swept_at.load(Ordering::Relaxed) + lockout_secs > now {
+            return;
+        }
+        self.swept_at.store(now, Ordering::Relaxed);
+        usernames.retain(|_, failures| failures.last_failure_at + lockout_secs > now);
+        addresses.retain(|_, failures| failures.last_failure_at + lockout_secs > now);
+    }
+
+    /// Usernames and addresses that currently cannot sign in.
+    pub fn lockouts(&self) -> Vec<Lockout> {
+        let now = clock::now();
+        let policy = &self.policy;
+        let lockout = |failures: &Failures, max_failures: u32| {
+            let blocked_until = policy.blocked_until(failures, max_failures);
+            Some(Lockout {
+                username: None,
+                address: None,
+                failures: failures.count,
+                locked: failures.count >= max_failures,
+                blocked_until,
+            })
+            .filter(|_| blocked_until > now)
+        };
+        let mut lockouts = self
+            .usernames
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|(username, failures)| {
+                lockout(failures, policy.max_failures).map(|lockout| Lockout {
+                    username: Some(username.clone()),
+                    ..lockout
+                })
+            })
+            .collect::<Vec<_>>();
+        lockouts.extend(
+            self.addresses
+                .lock()
+                .unwrap()
+                .iter()
+                .filter_map(|(address, failures)| {
+                    lockout(failures, policy.max_address_failures).map(|lockout| Lockout {
+                        address: Some(*address),
+                        ..lockout
+                    })
+                }),
+        );
+        lockouts.sort_by_key(|lockout| lockout.blocked_until);
+        lockouts
+    }
+
+    /// Forgets the failures of `username`, returning whether there were any.
+    pub fn unlock_username(&self, username: &str) -> bool {
+        self.usernames.lock().unwrap().remove(username).is_some()
+    }
+
+    /// Forgets the failures of `address`, returning whether there were any.
+    pub fn unlock_address(&self, address: IpAddr) -> bool {
+        self.addresses.lock().unwrap().remove(&address).is_some()
+    }
+}
+
+/// Takes back a failure counted by `SignInThrottle::attempt`.
+fn forgive<K, Q>(entries: &mut HashMap<K, Failures>, key: &Q)
+where
+    K: Eq + Hash + Borrow<Q>,
+    Q: Eq + Hash + ?Sized,
+{
+    if let Some(failures) = entries.get_mut(key) {
+        failures.count = failures.count.saturating_sub(1);
+        if failures.count == 0 {
+            entries.remove(key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::Ipv4Addr;
+
+    const ADDRESS: IpAddr = IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1));
+
+    fn throttle(max_failures: u32, max_address_failures: u32) -> SignInThrottle {
+        SignInThrottle {
+            policy: ThrottlePolicy {
+                max_failures,
+                max_address_failures,
+                lockout_secs: 600,
+            },
+            usernames: Arc::new(Mutex::new(HashMap::new())),
+            addresses: Arc::new(Mutex::new(HashMap::new())),
+            swept_at: Arc::new(AtomicI64::new(0)),
+        }
+    }
+
+    fn failures(throttle: &SignInThrottle, username: &str) -> u32 {
+        let usernames = throttle.usernames.lock().unwrap();
+        usernames.get(username).map_or(0, |failures| failures.count)
+    }
+
+    fn address_failures(throttle: &SignInThrottle, address: IpAddr) -> u32 {
+        let addresses = throttle.addresses.lock().unwrap();
+        addresses.get(&address).map_or(0, |failures| failures.count)
+    }
+
+    #[test]
+    fn doubles_the_backoff_after_each_failure() {
+        let throttle = throttle(10, 20);
+        let mut now = 1000;
+        for (count, backoff) in [1, 2, 4, 8, 16, 32, 60, 60].into_iter().enumerate() {
+            clock::freeze(now);
+            throttle.attempt("alice", None).unwrap();
+            assert_eq!(failures(&throttle, "alice"), count as u32 + 1);
+            assert!(matches!(
+                throttle.attempt("alice", None),
+                Err(ServiceError::SignInThrottled { retry_after }) if retry_after == backoff
+            ));
+            now += backoff;
+        }
+    }
+
+    #[test]
+    fn locks_a_username_after_max_failures() {
+        let throttle = throttle(3, 20);
+        for now in [1000, 1001, 1003] {
+            clock::freeze(now);
+            throttle.attempt("alice", Some(ADDRESS)).unwrap();
+        }
+        assert!(matches!(
+            throttle.attempt("alice", None),
+            Err(ServiceError::AccountLocked { retry_after: 600 })
+        ));
+        clock::freeze(1500);
+        assert!(matches!(
+            throttle.attempt("alice", None),
+            Err(ServiceError::AccountLocked { retry_after: 103 })
+        ));
+        let lockouts = throttle.lockouts();
+        assert_eq!(lockouts.len(), 1);
+        assert_eq!(lockouts[0].username.as_deref(), Some("alice"));
+        assert!(lockouts[0].locked);
+        assert_eq!(lockouts[0].failures, 3);
+        assert_eq!(lockouts[0].blocked_until, 1603);
+
+        // The failures are forgotten a lockout period after the last one.
+        clock::freeze(1603);
+        throttle.attempt("alice", None).unwrap();
+        assert_eq!(failures(&throttle, "alice"), 1);
+    }
+
+    #[test]
+    fn sweeps_stale_failures_once_per_lockout_period() {
+        let throttle = throttle(10, 20);
+        clock::freeze(900);
+        throttle.attempt("dave", None).unwrap();
+        clock::freeze(1000);
+        throttle.attempt("alice", Some(ADDRESS)).unwrap();
+        clock::freeze(1500);
+        throttle.attempt("bob", None).unwrap();
+        assert_eq!(failures(&throttle, "dave"), 0);
+        // Alice's failure is stale but only dropped by the next sweep.
+        clock::freeze(1700);
+        throttle.attempt("carol", None).unwrap();
+        assert_eq!(failures(&throttle, "alice"), 1);
+        clock::freeze(2100);
+        throttle.attempt("carol", None).unwrap();
+        assert_eq!(failures(&throttle, "alice"), 0);
+        assert_eq!(address_failures(&throttle, ADDRESS), 0);
+        assert_eq!(failures(&throttle, "bob"), 0);
+        assert_eq!(failures(&throttle, "carol"), 2);
+    }
+
+    #[test]
+    fn locks_an_address_after_max_address_failures() {
+        let throttle = throttle(3, 5);
+        let mut now = 1000;
+        for (username, backoff) in [("a", 1), ("b", 2), ("c", 4), ("d", 8), ("e", 0)] {
+            clock::freeze(now);
+            throttle.attempt(username, Some(ADDRESS)).unwrap();
+            now += backoff;
+        }
+        assert!(matches!(
+            throttle.attempt("f", Some(ADDRESS)),
+            Err(ServiceError::SignInThrottled { retry_after: 600 })
+        ));
+        throttle.attempt("f", None).unwrap();
+    }
+
+    #[test]
+    fn forgives_the_attempts_that_did_not_fail() {
+        let throttle = throttle(3, 5);
+        clock::freeze(1000);
+        throttle.attempt("alice", Some(ADDRESS)).unwrap();
+        throttle.abandoned("alice", Some(ADDRESS));
+        assert_eq!(failures(&throttle, "alice"), 0);
+        assert_eq!(address_failures(&throttle, ADDRESS), 0);
+        throttle.attempt("alice", Some(ADDRESS)).unwrap();
+
+        // Signing in to another account only takes back the failure of that
+        // attempt for the address.
+        clock::freeze(1001);
+        throttle.attempt("mallory", Some(ADDRESS)).unwrap();
+        throttle.succeeded("mallory", Some(ADDRESS));
+        assert_eq!(failures(&throttle, "mallory"), 0);
+        assert_eq!(failures(&throttle, "alice"), 1);
+        assert_eq!(address_failures(&throttle, ADDRESS), 1);
+    }
+
+    #[tokio::test]
+    async fn counts_only_credential_failures_of_checks() {
+        let throttle = throttle(3, 5);
+        clock::freeze(1000);
+        let result = throttle
+            .check_credentials("alice", Some(ADDRESS), async {
+                Err::<(), _>(ServiceError::InvalidCredentials)
+            })
+            .await;
+        assert!(matches!(result, Err(ServiceError::InvalidCredentials)));
+        assert_eq!(failures(&throttle, "alice"), 1);
+
+        clock::freeze(1001);
+        let result = throttle
+            .check_credentials("alice", Some(ADDRESS), async {
+                Err::<(), _>(ServiceError::NotFound("User alice".to_string()))
+            })
+            .await;
+        assert!(matches!(result, Err(ServiceError::NotFound(_))));
+        assert_eq!(failures(&throttle, "alice"), 1);
+
+        clock::freeze(1003);
+        let result = throttle
+            .check_credentials("alice", Some(ADDRESS), async { Ok(()) })
+            .await;
+        assert!(result.is_ok());
+        assert_eq!(failures(&throttle, "alice"), 0);
+        assert_eq!(address_failures(&throttle, ADDRESS), 1);
+    }
+
+    #[test]
+    fn unlocks_usernames_and_addresses() {
+        let throttle = throttle(3, 3);
+        for now in [1000, 1001, 1003] {
+            clock::freeze(now);
+            throttle.attempt("alice", Some(ADDRESS)).unwrap();
+        }
+        assert!(throttle.attempt("alice", None).is_err());
+        assert!(throttle.attempt("bob", Some(ADDRESS)).is_err());
+
+        assert!(throttle.unlock_username("alice"));
+        throttle.attempt("alice", None).unwrap();
+        assert!(throttle.attempt("bob", Some(ADDRESS)).is_err());
+
+        assert!(throttle.unlock_address(ADDRESS));
+        throttle.attempt("bob", Some(ADDRESS)).unwrap();
+
+        assert!(!throttle.unlock_username("carol"));
+        assert!(!throttle.unlock_address(IpAddr::V4(Ipv4Addr::LOCALHOST)));
+    }
+}
@@ -0,0 +1,85 @@
+use crate::clock;
+use crate::tokens::{access_token_ttl, audience, issuer};
+use hmac::{Hmac, NewMac};
+use jwt::claims::{Claims, RegisteredClaims};
+use jwt::{SignWithKey, VerifyWithKey};
+use sha2::Sha256;
+use std::env;
+use uuid::Uuid;
+
+/// Clock skew tolerated when checking `exp` and `nbf`. Revocations are kept
+/// this long past `exp` since tokens are still accepted until then.
+pub const LEEWAY_SECS: i64 = 30;
+
+/// Verified contents of an access token.
+#[derive(Debug, Clone)]
+pub struct AccessClaims {
+    pub username: String,
+    pub jti: String,
+    pub expires_at: i64,
+}
+
+fn signing_key() -> Hmac<Sha256> {
+    let secret = env::var("SECRET").expect("SECRET env missing");
+    Hmac::new_from_slice(secret.as_bytes()).unwrap()
+}
+
+/// Signs a short-lived access token for `username`.
+pub fn issue_access_token(username: &str) -> Result<(String, AccessClaims), String> {
+    let now = clock::now();
+    let access_claims = AccessClaims {
+        username: username.to_string(),
+        jti: Uuid::new_v4().to_string(),
+        expires_at: now + access_token_ttl(),
+    };
+    let claims = Claims::new(RegisteredClaims {
+        issuer: Some(issuer()),
+        subject: Some(access_claims.username.clone()),
+        audience: Some(audience()),
+        expiration: Some(access_claims.expires_at as u64),
+        not_before: Some(now as u64),
+        issued_at: Some(now as u64),
+        json_web_token_id: Some(access_claims.jti.clone()),
+    });
+    match claims.sign_with_key(&signing_key()) {
+        Ok(token) => Ok((token, access_claims)),
+        Err(e) => Err(format!("Unable to sign access token: {}", e)),
+    }
+}
+
+/// Checks the signature and the registered claims of an access token.
+pub fn verify_access_token(token: &str) -> Result<AccessClaims, String> {
+    let claims: Claims = token
+        .verify_with_key(&signing_key())
+        .map_err(|e| format!("Invalid token signature: {}", e))?;
+    let registered = claims.registered;
+    let now = clock::now();
+
+    if registered.issuer != Some(issuer()) {
+        return Err(String::from("Unexpected token issuer"));
+    }
+    if registered.audience != Some(audience()) {
+        return Err(String::from("Unexpected token audience"));
+    }
+    let expires_at = match registered.expiration {
+        Some(exp) if exp as i64 + LEEWAY_SECS > now => exp as i64,
+        Some(_) => return Err(String::from("Token expired")),
+        None => return Err(String::from("Token has no expiry")),
+    };
+    match registered.not_before {
+        Some(nbf) if nbf as i64 - LEEWAY_SECS <= now => {}
+        Some(_) => return Err(String::from("Token not valid yet")),
+        None => return Err(String::from("Token has no not-before")),
+    }
+    if registered.issued_at.is_none() {
+        return Err(String::from("Token has no issued-at"));
+    }
+    match (registered.subject, registered.json_web_token_id) {
+        (Some(username), Some(jti)) => Ok(AccessClaims {
+            username,
+            jti,
+            expires_at,
+        }),
+        _ => Err(String::from("Token is missing subject or id")),
+    }
+}
@@ -0,0 +1,59 @@
+mod access;
+mod refresh;
+mod revocation;
+mod totp;
+
+use crate::config::env_or;
+pub use crate::tokens::access::{
+    issue_access_token, verify_access_token, AccessClaims, LEEWAY_SECS,
+};
+pub use crate::tokens::refresh::{generate_refresh_token, hash_refresh_token, to_hex};
+pub use crate::tokens::revocation::RevocationCache;
+pub use crate::tokens::totp::{
+    generate_recovery_codes, generate_totp_secret, hash_recovery_code, is_totp_code, otpauth_uri,
+    verify_totp_code,
+};
+
+/// Access and refresh token handed out by `SignIn` and `RefreshToken`.
+#[derive(Debug, Clone)]
+pub struct TokenPair {
+    pub access_token: String,
+    pub refresh_token: String,
+    /// Lifetime of the access token in seconds.
+    pub expires_in: i64,
+}
+
+/// Outcome of a sign in: tokens, or a challenge to answer with a second factor
+/// through `VerifySecondFactor` when the user has enabled one.
+#[derive(Debug, Clone)]
+pub enum SignInOutcome {
+    Signed(TokenPair),
+    SecondFactorRequired {
+        challenge_token: String,
+        expires_in: i64,
+    },
+}
+
+pub fn issuer() -> String {
+    env_or("JWT_ISSUER", String::from("todo-rust-grpc"))
+}
+
+pub fn audience() -> String {
+    env_or("JWT_AUDIENCE", String::from("todo-rust-grpc"))
+}
+
+/// Access token lifetime, `ACCESS_TOKEN_TTL_SECS` (15 minutes by default).
+pub fn access_token_ttl() -> i64 {
+    env_or("ACCESS_TOKEN_TTL_SECS", 15 * 60)
+}
+
+/// Refresh token lifetime, `REFRESH_TOKEN_TTL_SECS` (30 days by default).
+pub fn refresh_token_ttl() -> i64 {
+    env_or("REFRESH_TOKEN_TTL_SECS", 30 * 24 * 60 * 60)
+}
+
+/// Sign in challenge lifetime, `SIGN_IN_CHALLENGE_TTL_SECS` (5 minutes by
+/// default).
+pub fn sign_in_challenge_ttl() -> i64 {
+    env_or("SIGN_IN_CHALLENGE_TTL_SECS", 5 * 60)
+}
@@ -0,0 +1,25 @@
+use rand_core::{OsRng, RngCore};
+use sha2::{Digest, Sha256};
+use std::fmt::Write;
+
+/// Lowercase hex encoding of `bytes`.
+pub fn to_hex(bytes: &[u8]) -> String {
+    let mut hex = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        write!(hex, "{:02x}", byte).unwrap();
+    }
+    hex
+}
+
+/// Generates an opaque refresh token. Only its hash is persisted.
+pub fn generate_refresh_token() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    to_hex(&bytes)
+}
+
+/// Refresh tokens are high-entropy random values, so a plain SHA-256 is
+/// enough to keep a database leak from exposing usable tokens.
+pub fn hash_refresh_token(token: &str) -> String {
+    to_hex(&Sha256::digest(token.as_bytes()))
+}
@@ -0,0 +1,36 @@
+use crate::clock;
+use crate::tokens::LEEWAY_SECS;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+/// In-memory view of the revoked access tokens, keyed on `jti`.
+///
+/// The `access_token` table is the source of truth; this cache is loaded from
+/// it on startup and written through on every revocation so that
+/// `AuthInterceptor` never has to query the database.
+#[derive(Debug, Clone, Default)]
+pub struct RevocationCache {
+    revoked: Arc<RwLock<HashMap<String, i64>>>,
+}
+
+impl RevocationCache {
+    pub fn new(revoked: Vec<(String, i64)>) -> Self {
+        Self {
+            revoked: Arc::new(RwLock::new(revoked.into_iter().collect())),
+        }
+    }
+
+    pub fn is_revoked(&self, jti: &str) -> bool {
+        self.revoked.read().unwrap().contains_key(jti)
+    }
+
+    /// Records `jti` as revoked until `expires_at` plus the leeway, after
+    /// which the token is rejected on its expiry anyway and the entry can be
+    /// dropped.
+    pub fn revoke(&self, jti: String, expires_at: i64) {
+        let now = clock::now();
+        let mut revoked = self.revoked.write().unwrap();
+        revoked.retain(|_, expiry| *expiry + LEEWAY_SECS > now);
+        revoked.insert(jti, expires_at);
+    }
+}
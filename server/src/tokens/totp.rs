@@ -0,0 +1,195 @@
+use crate::config::env_or;
+use crate::tokens::{issuer, to_hex};
+use hmac::{Hmac, Mac, NewMac};
+use rand_core::{OsRng, RngCore};
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use std::fmt::Write;
+
+const SECRET_BYTES: usize = 20;
+const DIGITS: u32 = 6;
+const STEP_SECS: i64 = 30;
+
+/// Steps before and after the current one whose codes are accepted, making
+/// up for the clock of the authenticator drifting.
+const ALLOWED_DRIFT_STEPS: i64 = 1;
+
+const RECOVERY_CODES: usize = 10;
+const RECOVERY_CODE_BYTES: usize = 5;
+
+const BASE32: base32::Alphabet = base32::Alphabet::RFC4648 { padding: false };
+
+/// Generates the base32 secret an authenticator app is enrolled with.
+pub fn generate_totp_secret() -> String {
+    let mut secret = [0u8; SECRET_BYTES];
+    OsRng.fill_bytes(&mut secret);
+    base32::encode(BASE32, &secret)
+}
+
+/// Percent-encodes `value` for the label and the parameters of a URI.
+fn uri_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            encoded.push(byte as char);
+        } else {
+            write!(encoded, "%{:02X}", byte).unwrap();
+        }
+    }
+    encoded
+}
+
+/// `otpauth://` URI of `secret`, usually shown as a QR code. The issuer is
+/// `TOTP_ISSUER`, the JWT issuer by default.
+pub fn otpauth_uri(secret: &str, username: &str) -> String {
+    let issuer = uri_encode(&env_or("TOTP_ISSUER", issuer()));
+    format!(
+        "otpauth://totp/{issuer}:{username}?secret={secret}&issuer={issuer}&algorithm=SHA1&digits={digits}&period={period}",
+        issuer = issuer,
+        username = uri_encode(username),
+        secret = secret,
+        digits = DIGITS,
+        period = STEP_SECS
+    )
+}
+
+/// HOTP value of `secret` for the counter `step`, as in RFC 4226.
+fn code_at(secret: &[u8], step: i64) -> u32 {
+    let mut mac = Hmac::<Sha1>::new_from_slice(secret).unwrap();
+    mac.update(&step.to_be_bytes());
+    let hash = mac.finalize().into_bytes();
+    let offset = (hash[hash.len() - 1] & 0x0f) as usize;
+    let value = u32::from_be_bytes([
+        hash[offset] & 0x7f,
+        hash[offset + 1],
+        hash[offset + 2],
+        hash[offset + 3],
+    ]);
+    value % 10u32.pow(DIGITS)
+}
+
+/// Whether `code` looks like a TOTP code rather than a recovery code.
+pub fn is_totp_code(code: &str) -> bool {
+    code.len() == DIGITS as usize && code.bytes().all(|byte| byte.is_ascii_digit())
+}
+
+/// The time step `code` is the TOTP of `secret` for around `now`, `None` when
+/// the code is wrong.
+pub fn verify_totp_code(secret: &str, code: &str, now: i64) -> Option<i64> {
+    if !is_totp_code(code) {
+        return None;
+    }
+    let code = code.parse::<u32>().ok()?;
+    let secret = base32::decode(BASE32, secret)?;
+    let current = now / STEP_SECS;
+    (current - ALLOWED_DRIFT_STEPS..=current + ALLOWED_DRIFT_STEPS)
+        .find(|step| code_at(&secret, *step) == code)
+}
+
+/// Generates one-time recovery codes, formatted as a group of five characters
+/// and one of three.
+pub fn generate_recovery_codes() -> Vec<String> {
+    (0..RECOVERY_CODES)
+        .map(|_| {
+            let mut bytes = [0u8; RECOVERY_CODE_BYTES];
+            OsRng.fill_bytes(&mut bytes);
+            let code = base32::encode(BASE32, &bytes).to_lowercase();
+            format!("{}-{}", &code[..5], &code[5..])
+        })
+        .collect()
+}
+
+/// Recovery codes are random enough for a plain SHA-256, like refresh tokens.
+/// Case, spaces and dashes are ignored so that codes can be typed loosely.
+pub fn hash_recovery_code(code: &str) -> String {
+    let normalized = code
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '-')
+        .collect::<String>()
+        .to_lowercase();
+    to_hex(&Sha256::digest(normalized.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The SHA-1 secret of the RFC 6238 test vectors, `12345678901234567890`.
+    const RFC_SECRET: &str = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+
+    #[test]
+    fn matches_the_rfc_6238_test_vectors() {
+        // The last 6 of the 8 digits of the vectors.
+        let secret = base32::decode(BASE32, RFC_SECRET).unwrap();
+        for (time, code) in [
+            (59, "287082"),
+            (1_111_111_109, "081804"),
+            (1_234_567_890, "005924"),
+            (2_000_000_000, "279037"),
+        ] {
+            assert_eq!(
+                code_at(&secret, time / STEP_SECS),
+                code.parse::<u32>().unwrap()
+            );
+            assert_eq!(
+                verify_totp_code(RFC_SECRET, code, time),
+                Some(time / STEP_SECS)
+            );
+        }
+    }
+
+    #[test]
+    fn accepts_codes_of_the_neighbouring_steps_only() {
+        let (time, step) = (1_111_111_109, 1_111_111_109 / STEP_SECS);
+        for drift in [-1, 1] {
+            let now = time + drift * STEP_SECS;
+            assert_eq!(verify_totp_code(RFC_SECRET, "081804", now), Some(step));
+        }
+        for drift in [-2, 2] {
+            let now = time + drift * STEP_SECS;
+            assert_eq!(verify_totp_code(RFC_SECRET, "081804", now), None);
+        }
+        assert_eq!(verify_totp_code(RFC_SECRET, "081805", time), None);
+    }
+
+    #[test]
+    fn rejects_malformed_codes() {
+        for code in ["", "08180", "0818040", "08180a", " 81804"] {
+            assert!(!is_totp_code(code), "{}", code);
+            assert_eq!(verify_totp_code(RFC_SECRET, code, 1_111_111_109), None);
+        }
+        assert_eq!(
+            verify_totp_code("not base32!", "081804", 1_111_111_109),
+            None
+        );
+    }
+
+    #[test]
+    fn normalizes_recovery_codes_before_hashing() {
+        let hash = hash_recovery_code("abcde-fghij");
+        assert_eq!(
+            hash,
+            "72399361da6a7754fec986dca5b7cbaf1c810a28ded4abaf56b2106d06cb78b0"
+        );
+        for code in [
+            "ABCDE-FGHIJ",
+            "abcde fghij",
+            " abcdefghij\n",
+            "Ab-Cd-Ef-Gh-Ij",
+        ] {
+            assert_eq!(hash_recovery_code(code), hash, "{}", code);
+        }
+        assert_ne!(hash_recovery_code("abcde-fghik"), hash);
+    }
+
+    #[test]
+    fn generates_recovery_codes_in_groups() {
+        let codes = generate_recovery_codes();
+        assert_eq!(codes.len(), RECOVERY_CODES);
+        for code in &codes {
+            assert_eq!(code.len(), 9);
+            assert_eq!(&code[5..6], "-");
+            assert!(!is_totp_code(code));
+        }
+    }
+}